@@ -108,6 +108,12 @@ pub struct PositionData {
     pub keep_margin_rate: String,
     #[serde(rename = "marketPrice")]
     pub market_price: String,
+    /// Trading fees deducted so far
+    #[serde(rename = "deductedFee", default)]
+    pub deducted_fee: Option<String>,
+    /// Accumulated funding fee over the life of the position (negative = paid)
+    #[serde(rename = "totalFee", default)]
+    pub total_fee: Option<String>,
     #[serde(rename = "cTime")]
     pub c_time: String,
     #[serde(rename = "uTime")]
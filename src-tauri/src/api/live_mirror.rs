@@ -1,5 +1,9 @@
 use crate::api::bitget::websocket::{BitgetWebSocketClient, PositionData, PositionEvent};
 use crate::api::credentials::{retrieve_api_key, retrieve_api_secret, retrieve_passphrase};
+use crate::commands::capital::load_account_balance;
+use crate::commands::daily_stats::refresh_trade_days;
+use crate::commands::revisions::{record_revision, trade_snapshot};
+use crate::commands::search::reindex_trade;
 use crate::db::Database;
 use crate::models::Trade;
 use chrono::Utc;
@@ -206,14 +210,11 @@ async fn create_live_trade(
 ) -> Result<String, String> {
     let conn = db.conn.lock().map_err(|e| e.to_string())?;
 
-    // Get portfolio settings
-    let (portfolio_value, r_percent): (f64, f64) = conn
-        .query_row(
-            "SELECT initial_capital, current_r_percent FROM settings WHERE id = 1",
-            [],
-            |row| Ok((row.get(0)?, row.get(1)?)),
-        )
+    // Get portfolio settings, portfolio value is the ledger-aware account balance
+    let r_percent: f64 = conn
+        .query_row("SELECT current_r_percent FROM settings WHERE id = 1", [], |row| row.get(0))
         .map_err(|e| format!("Failed to load settings: {}", e))?;
+    let portfolio_value = load_account_balance(&conn)?.balance;
 
     // Parse position data
     let entry_price: f64 = position
@@ -297,6 +298,9 @@ async fn create_live_trade(
         effective_weighted_rr: None,
         total_pnl: None,
         pnl_in_r: None,
+        fees: parse_optional_amount(&position.deducted_fee).map(f64::abs),
+        funding: parse_optional_amount(&position.total_fee),
+        execution_score: None,
         notes: format!("Live trade - Auto-synced from Bitget (Credential: {})", credential_id),
         execution_portfolio: None,
         execution_r_percent: None,
@@ -312,6 +316,9 @@ async fn create_live_trade(
     };
 
     insert_trade(&conn, &trade).map_err(|e| format!("Failed to insert trade: {}", e))?;
+    refresh_trade_days(&conn, &trade.id, None)?;
+    record_revision(&conn, &trade.id, "CREATE", "LIVE_MIRROR", None)?;
+    reindex_trade(&conn, &trade.id, true)?;
 
     Ok(trade_id)
 }
@@ -335,12 +342,18 @@ async fn update_live_trade(
 
     let now = Utc::now().timestamp();
 
-    // Update trade with current PnL (still open)
+    let fees = parse_optional_amount(&position.deducted_fee).map(f64::abs);
+    let funding = parse_optional_amount(&position.total_fee);
+
+    // Update trade with current PnL and costs (still open)
+    let before = trade_snapshot(&conn, trade_id)?;
     conn.execute(
-        "UPDATE trades SET total_pnl = ?, updated_at = ? WHERE id = ?",
-        rusqlite::params![unrealized_pl, now, trade_id],
+        "UPDATE trades SET total_pnl = ?, fees = COALESCE(?, fees), funding = COALESCE(?, funding), updated_at = ? WHERE id = ?",
+        rusqlite::params![unrealized_pl, fees, funding, now, trade_id],
     )
     .map_err(|e| format!("Failed to update trade: {}", e))?;
+    refresh_trade_days(&conn, trade_id, None)?;
+    record_revision(&conn, trade_id, "UPDATE", "LIVE_MIRROR", before)?;
 
     Ok(())
 }
@@ -374,6 +387,8 @@ async fn close_live_trade(
 
     let exit_price = market_price;
     let total_pnl = achieved_profits;
+    let fees = parse_optional_amount(&position.deducted_fee).map(f64::abs);
+    let funding = parse_optional_amount(&position.total_fee);
 
     // Calculate PnL in R
     let pnl_in_r = if one_r > 0.0 {
@@ -414,6 +429,7 @@ async fn close_live_trade(
     };
 
     // Update trade
+    let before = trade_snapshot(&conn, trade_id)?;
     conn.execute(
         "UPDATE trades SET
             status = ?,
@@ -422,6 +438,8 @@ async fn close_live_trade(
             total_pnl = ?,
             pnl_in_r = ?,
             effective_weighted_rr = ?,
+            fees = COALESCE(?, fees),
+            funding = COALESCE(?, funding),
             updated_at = ?
          WHERE id = ?",
         rusqlite::params![
@@ -431,15 +449,24 @@ async fn close_live_trade(
             total_pnl,
             pnl_in_r,
             effective_weighted_rr,
+            fees,
+            funding,
             now,
             trade_id
         ],
     )
     .map_err(|e| format!("Failed to close trade: {}", e))?;
+    refresh_trade_days(&conn, trade_id, None)?;
+    record_revision(&conn, trade_id, "UPDATE", "LIVE_MIRROR", before)?;
 
     Ok(())
 }
 
+/// Parse an optional numeric string from position data, ignoring empty or malformed values
+fn parse_optional_amount(value: &Option<String>) -> Option<f64> {
+    value.as_deref().and_then(|v| v.parse::<f64>().ok())
+}
+
 /// Insert trade into database
 fn insert_trade(conn: &Connection, trade: &Trade) -> Result<(), rusqlite::Error> {
     conn.execute(
@@ -449,7 +476,7 @@ fn insert_trade(conn: &Connection, trade: &Trade) -> Result<(), rusqlite::Error>
             planned_pe, planned_sl, leverage, planned_tps, planned_entries,
             position_type, one_r, margin, position_size, quantity, planned_weighted_rr,
             effective_pe, effective_entries, close_date, exits,
-            effective_weighted_rr, total_pnl, pnl_in_r,
+            effective_weighted_rr, total_pnl, pnl_in_r, fees, funding,
             notes, import_fingerprint, import_source, created_at, updated_at
         ) VALUES (
             ?, ?, ?, ?, ?, ?,
@@ -457,7 +484,7 @@ fn insert_trade(conn: &Connection, trade: &Trade) -> Result<(), rusqlite::Error>
             ?, ?, ?, ?, ?,
             ?, ?, ?, ?, ?, ?,
             ?, ?, ?, ?,
-            ?, ?, ?,
+            ?, ?, ?, ?, ?,
             ?, ?, ?, ?, ?
         )",
         rusqlite::params![
@@ -488,6 +515,8 @@ fn insert_trade(conn: &Connection, trade: &Trade) -> Result<(), rusqlite::Error>
             trade.effective_weighted_rr,
             trade.total_pnl,
             trade.pnl_in_r,
+            trade.fees,
+            trade.funding,
             trade.notes,
             trade.import_fingerprint,
             trade.import_source,
@@ -0,0 +1,364 @@
+use tauri::State;
+use crate::commands::settings::load_timezone;
+use crate::commands::stats::{trade_filter, DateWindow, TagFilter};
+use crate::db::Database;
+use serde::{Deserialize, Serialize};
+
+/// Default tolerance (in % of price) within which an exit counts as hitting a planned TP
+const DEFAULT_EXIT_TOLERANCE_PERCENT: f64 = 0.1;
+
+/// Plan vs execution comparison for a single closed trade
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TradeAdherence {
+    pub trade_id: String,
+    pub pair: String,
+    pub position_type: String,
+    pub status: String,
+    pub trade_date: i64,
+    pub close_date: Option<i64>,
+    pub planned_pe: f64,
+    pub effective_pe: Option<f64>,
+    pub entry_slippage_percent: Option<f64>, // > 0 = filled worse than planned
+    pub entry_slippage_r: Option<f64>,       // same, in units of the planned SL distance
+    pub planned_exit_price: Option<f64>,     // weighted average of planned TPs
+    pub actual_exit_price: Option<f64>,      // weighted average of exits
+    pub exit_deviation_percent: Option<f64>, // weighted distance of exits from their nearest planned TP or SL
+    pub exits_matched_plan: Option<bool>,
+    pub planned_rr: f64,
+    pub realized_rr: Option<f64>,
+    pub rr_ratio: Option<f64>, // realized_rr / planned_rr
+    pub r_multiple: Option<f64>,
+    pub stop_violated: bool, // lost more than 1R
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AdherenceReport {
+    pub trade_count: i32,
+    pub avg_entry_slippage_percent: f64,
+    pub avg_entry_slippage_r: f64,
+    pub adverse_entry_count: i32,
+    pub exit_deviation_count: i32,
+    pub exit_deviation_rate: f64, // % of trades with planned TPs and exits
+    pub avg_exit_deviation_percent: f64,
+    pub avg_rr_ratio: f64,
+    pub stop_violation_count: i32,
+    pub stop_violation_rate: f64, // % of trades with a known R multiple
+    pub stop_violation_excess_r: f64, // total R lost beyond -1R
+    pub trades: Vec<TradeAdherence>,
+}
+
+/// Trade columns needed to compare plan and execution
+#[derive(Debug, Clone)]
+struct AdherenceTrade {
+    id: String,
+    pair: String,
+    position_type: String,
+    status: String,
+    trade_date: i64,
+    close_date: Option<i64>,
+    planned_pe: f64,
+    planned_sl: f64,
+    planned_tps: String,
+    planned_weighted_rr: f64,
+    effective_pe: Option<f64>,
+    exits: Option<String>,
+    effective_weighted_rr: Option<f64>,
+    total_pnl: Option<f64>,
+    pnl_in_r: Option<f64>,
+    one_r: f64,
+}
+
+/// Parse a JSON array of {price, percent} levels, skipping unset or zero-weight entries
+fn parse_price_levels(json: Option<&str>) -> Vec<(f64, f64)> {
+    let Some(json) = json else {
+        return Vec::new();
+    };
+    let Ok(serde_json::Value::Array(levels)) = serde_json::from_str::<serde_json::Value>(json) else {
+        return Vec::new();
+    };
+
+    levels
+        .iter()
+        .filter_map(|level| {
+            let price = level.get("price")?.as_f64()?;
+            let percent = level.get("percent")?.as_f64()?;
+            (price > 0.0 && percent > 0.0).then_some((price, percent))
+        })
+        .collect()
+}
+
+/// Weighted average price of {price, percent} levels. Percent units (0-1 or 0-100) cancel out.
+fn weighted_price(levels: &[(f64, f64)]) -> Option<f64> {
+    let total: f64 = levels.iter().map(|(_, percent)| percent).sum();
+    if total <= 0.0 {
+        return None;
+    }
+    Some(levels.iter().map(|(price, percent)| price * percent).sum::<f64>() / total)
+}
+
+fn analyze_trade(trade: &AdherenceTrade, exit_tolerance_percent: f64) -> TradeAdherence {
+    let is_long = trade.position_type == "LONG";
+    let sl_distance = (trade.planned_pe - trade.planned_sl).abs();
+
+    // Adverse slippage is positive: paying more on a long, receiving less on a short
+    let slippage = trade.effective_pe.filter(|_| trade.planned_pe > 0.0).map(|effective_pe| {
+        if is_long {
+            effective_pe - trade.planned_pe
+        } else {
+            trade.planned_pe - effective_pe
+        }
+    });
+    let entry_slippage_percent = slippage.map(|s| s / trade.planned_pe * 100.0);
+    let entry_slippage_r = slippage.filter(|_| sl_distance > 0.0).map(|s| s / sl_distance);
+
+    let planned_tps = parse_price_levels(Some(&trade.planned_tps));
+    let exits = parse_price_levels(trade.exits.as_deref());
+
+    // A stop-out at the planned SL follows the plan as much as a fill at a TP
+    let planned_exits: Vec<f64> = planned_tps
+        .iter()
+        .map(|(tp_price, _)| *tp_price)
+        .chain((trade.planned_sl > 0.0).then_some(trade.planned_sl))
+        .collect();
+    let exit_deviations: Vec<(f64, f64)> = if planned_tps.is_empty() {
+        Vec::new()
+    } else {
+        exits
+            .iter()
+            .map(|(exit_price, percent)| {
+                let nearest = planned_exits
+                    .iter()
+                    .map(|price| (exit_price - price).abs() / price * 100.0)
+                    .fold(f64::INFINITY, f64::min);
+                (nearest, *percent)
+            })
+            .collect()
+    };
+    let exit_deviation_percent = weighted_price(&exit_deviations);
+    let exits_matched_plan = (!exit_deviations.is_empty())
+        .then(|| exit_deviations.iter().all(|(deviation, _)| *deviation <= exit_tolerance_percent));
+
+    let rr_ratio = trade
+        .effective_weighted_rr
+        .filter(|_| trade.planned_weighted_rr > 0.0)
+        .map(|rr| rr / trade.planned_weighted_rr);
+
+    let r_multiple = trade.pnl_in_r.or_else(|| {
+        trade
+            .total_pnl
+            .filter(|_| trade.one_r > 0.0)
+            .map(|pnl| pnl / trade.one_r)
+    });
+
+    TradeAdherence {
+        trade_id: trade.id.clone(),
+        pair: trade.pair.clone(),
+        position_type: trade.position_type.clone(),
+        status: trade.status.clone(),
+        trade_date: trade.trade_date,
+        close_date: trade.close_date,
+        planned_pe: trade.planned_pe,
+        effective_pe: trade.effective_pe,
+        entry_slippage_percent,
+        entry_slippage_r,
+        planned_exit_price: weighted_price(&planned_tps),
+        actual_exit_price: weighted_price(&exits),
+        exit_deviation_percent,
+        exits_matched_plan,
+        planned_rr: trade.planned_weighted_rr,
+        realized_rr: trade.effective_weighted_rr,
+        rr_ratio,
+        r_multiple,
+        stop_violated: r_multiple.is_some_and(|r| r < -1.0),
+    }
+}
+
+fn compute_adherence(trades: &[AdherenceTrade], exit_tolerance_percent: f64) -> AdherenceReport {
+    let trades: Vec<TradeAdherence> = trades
+        .iter()
+        .map(|trade| analyze_trade(trade, exit_tolerance_percent))
+        .collect();
+
+    let average = |values: Vec<f64>| -> f64 {
+        if values.is_empty() {
+            0.0
+        } else {
+            values.iter().sum::<f64>() / values.len() as f64
+        }
+    };
+    let percent_of = |count: usize, total: usize| -> f64 {
+        if total > 0 {
+            count as f64 / total as f64 * 100.0
+        } else {
+            0.0
+        }
+    };
+
+    let slippage_percent: Vec<f64> = trades.iter().filter_map(|t| t.entry_slippage_percent).collect();
+    let adverse_entry_count = slippage_percent.iter().filter(|s| **s > 0.0).count();
+
+    let matched: Vec<bool> = trades.iter().filter_map(|t| t.exits_matched_plan).collect();
+    let exit_deviation_count = matched.iter().filter(|m| !**m).count();
+
+    let r_multiples: Vec<f64> = trades.iter().filter_map(|t| t.r_multiple).collect();
+    let violations: Vec<f64> = r_multiples.iter().copied().filter(|r| *r < -1.0).collect();
+
+    AdherenceReport {
+        trade_count: trades.len() as i32,
+        avg_entry_slippage_percent: average(slippage_percent),
+        avg_entry_slippage_r: average(trades.iter().filter_map(|t| t.entry_slippage_r).collect()),
+        adverse_entry_count: adverse_entry_count as i32,
+        exit_deviation_count: exit_deviation_count as i32,
+        exit_deviation_rate: percent_of(exit_deviation_count, matched.len()),
+        avg_exit_deviation_percent: average(trades.iter().filter_map(|t| t.exit_deviation_percent).collect()),
+        avg_rr_ratio: average(trades.iter().filter_map(|t| t.rr_ratio).collect()),
+        stop_violation_count: violations.len() as i32,
+        stop_violation_rate: percent_of(violations.len(), r_multiples.len()),
+        stop_violation_excess_r: violations.iter().map(|r| -(r + 1.0)).sum(),
+        trades,
+    }
+}
+
+/// Compare planned entry, TPs and RR with what was actually executed on closed trades.
+/// Imported trades are excluded by default since their plan is synthesized from the fills.
+#[tauri::command]
+pub async fn get_adherence_report(
+    db: State<'_, Database>,
+    date_range: Option<String>,
+    start_date: Option<i64>,
+    end_date: Option<i64>,
+    include_imported: Option<bool>,
+    exit_tolerance_percent: Option<f64>,
+    tag_ids: Option<Vec<String>>,
+) -> Result<AdherenceReport, String> {
+    let tolerance = exit_tolerance_percent.unwrap_or(DEFAULT_EXIT_TOLERANCE_PERCENT);
+    if !tolerance.is_finite() || tolerance < 0.0 {
+        return Err("exit_tolerance_percent must be a non-negative number".to_string());
+    }
+
+    let conn = db.conn.lock().map_err(|e| e.to_string())?;
+
+    let tz = load_timezone(&conn);
+    let window = DateWindow::resolve(date_range.as_deref(), start_date, end_date, &tz);
+    let (date_filter, date_params) = trade_filter(window, &TagFilter::new(tag_ids));
+
+    let source_filter = if include_imported.unwrap_or(false) {
+        ""
+    } else {
+        "AND import_source = 'USER_CREATED'"
+    };
+
+    let mut stmt = conn.prepare(&format!(
+        "SELECT id, pair, position_type, status, trade_date, close_date,
+                planned_pe, planned_sl, planned_tps, planned_weighted_rr,
+                effective_pe, exits, effective_weighted_rr, total_pnl, pnl_in_r, one_r
+         FROM trades
+         WHERE deleted_at IS NULL AND status IN ('WIN', 'LOSS', 'BE')
+         {} {}
+         ORDER BY close_date ASC",
+        source_filter, date_filter
+    )).map_err(|e| e.to_string())?;
+
+    let trades = stmt.query_map(rusqlite::params_from_iter(date_params.iter()), |row| {
+        Ok(AdherenceTrade {
+            id: row.get(0)?,
+            pair: row.get(1)?,
+            position_type: row.get(2)?,
+            status: row.get(3)?,
+            trade_date: row.get(4)?,
+            close_date: row.get(5)?,
+            planned_pe: row.get(6)?,
+            planned_sl: row.get(7)?,
+            planned_tps: row.get(8)?,
+            planned_weighted_rr: row.get(9)?,
+            effective_pe: row.get(10)?,
+            exits: row.get(11)?,
+            effective_weighted_rr: row.get(12)?,
+            total_pnl: row.get(13)?,
+            pnl_in_r: row.get(14)?,
+            one_r: row.get(15)?,
+        })
+    })
+    .map_err(|e| e.to_string())?
+    .collect::<Result<Vec<_>, _>>()
+    .map_err(|e| e.to_string())?;
+
+    Ok(compute_adherence(&trades, tolerance))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn trade(position_type: &str, effective_pe: f64, exits: &str, pnl_in_r: f64) -> AdherenceTrade {
+        AdherenceTrade {
+            id: "TRADE-1".to_string(),
+            pair: "BTC/USDT".to_string(),
+            position_type: position_type.to_string(),
+            status: if pnl_in_r > 0.0 { "WIN" } else { "LOSS" }.to_string(),
+            trade_date: 0,
+            close_date: Some(0),
+            planned_pe: 100.0,
+            planned_sl: if position_type == "LONG" { 95.0 } else { 105.0 },
+            planned_tps: r#"[{"price": 110.0, "percent": 50, "rr": 2.0}, {"price": 115.0, "percent": 50, "rr": 3.0}]"#
+                .to_string(),
+            planned_weighted_rr: 2.5,
+            effective_pe: Some(effective_pe),
+            exits: Some(exits.to_string()),
+            effective_weighted_rr: Some(pnl_in_r),
+            total_pnl: None,
+            pnl_in_r: Some(pnl_in_r),
+            one_r: 100.0,
+        }
+    }
+
+    #[test]
+    fn test_entry_slippage_sign_follows_direction() {
+        let long = analyze_trade(&trade("LONG", 101.0, "[]", 1.0), 0.1);
+        assert!((long.entry_slippage_percent.unwrap() - 1.0).abs() < 1e-9);
+        assert!((long.entry_slippage_r.unwrap() - 0.2).abs() < 1e-9);
+
+        // Filling a short higher than planned is favorable
+        let short = analyze_trade(&trade("SHORT", 101.0, "[]", 1.0), 0.1);
+        assert!((short.entry_slippage_percent.unwrap() + 1.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_exit_deviation_against_nearest_tp() {
+        let on_plan = analyze_trade(
+            &trade("LONG", 100.0, r#"[{"price": 110.0, "percent": 50}, {"price": 115.0, "percent": 50}]"#, 2.5),
+            0.1,
+        );
+        assert_eq!(on_plan.exits_matched_plan, Some(true));
+        assert!(on_plan.exit_deviation_percent.unwrap().abs() < 1e-9);
+        assert!((on_plan.planned_exit_price.unwrap() - 112.5).abs() < 1e-9);
+        assert!((on_plan.rr_ratio.unwrap() - 1.0).abs() < 1e-9);
+
+        // Cut early at 105: 5/110 away from the nearest TP
+        let cut_early = analyze_trade(&trade("LONG", 100.0, r#"[{"price": 105.0, "percent": 100}]"#, 1.0), 0.1);
+        assert_eq!(cut_early.exits_matched_plan, Some(false));
+        assert!((cut_early.exit_deviation_percent.unwrap() - 5.0 / 110.0 * 100.0).abs() < 1e-9);
+
+        let no_exits = analyze_trade(&trade("LONG", 100.0, "[]", 0.0), 0.1);
+        assert_eq!(no_exits.exits_matched_plan, None);
+    }
+
+    #[test]
+    fn test_report_counts_stop_violations() {
+        let trades = vec![
+            trade("LONG", 100.0, r#"[{"price": 95.0, "percent": 100}]"#, -1.0),
+            trade("LONG", 100.0, r#"[{"price": 93.0, "percent": 100}]"#, -1.4),
+            trade("LONG", 100.0, r#"[{"price": 110.0, "percent": 100}]"#, 2.0),
+        ];
+        let report = compute_adherence(&trades, 0.1);
+
+        assert_eq!(report.trade_count, 3);
+        assert_eq!(report.stop_violation_count, 1);
+        assert!((report.stop_violation_rate - 100.0 / 3.0).abs() < 1e-9);
+        assert!((report.stop_violation_excess_r - 0.4).abs() < 1e-9);
+        // The stop-out at the planned SL is on plan, the one past it is not
+        assert_eq!(report.exit_deviation_count, 1);
+        assert_eq!(report.trades[0].exits_matched_plan, Some(true));
+        assert!(report.trades.iter().filter(|t| t.stop_violated).count() == 1);
+    }
+}
@@ -1,4 +1,8 @@
 use tauri::State;
+use crate::commands::capital::load_account_balance;
+use crate::commands::daily_stats::refresh_daily_stats;
+use crate::commands::revisions::record_revision;
+use crate::commands::search::reindex_trade;
 use crate::db::Database;
 use crate::models::{
     ApiCredential, ApiCredentialInput, ApiCredentialSafe, ApiSyncHistory,
@@ -394,14 +398,11 @@ pub async fn sync_exchange_trades(
             )
             .map_err(|e| format!("Credential not found: {}", e))?;
 
-        // Get current settings for portfolio value and r_percent
-        let (portfolio, r): (f64, f64) = conn
-            .query_row(
-                "SELECT initial_capital, current_r_percent FROM settings WHERE id = 1",
-                [],
-                |row| Ok((row.get(0)?, row.get(1)?)),
-            )
+        // Get current account balance for portfolio value, and r_percent from settings
+        let r: f64 = conn
+            .query_row("SELECT current_r_percent FROM settings WHERE id = 1", [], |row| row.get(0))
             .map_err(|e| format!("Failed to load settings: {}", e))?;
+        let portfolio = load_account_balance(&conn)?.balance;
 
         // Retrieve credentials from system keychain
         let api_key = retrieve_api_key(&config.credential_id).map_err(|e| e.to_string())?;
@@ -444,6 +445,7 @@ pub async fn sync_exchange_trades(
     let mut duplicates = 0;
     let mut errors = Vec::new();
     let mut total_pnl = 0.0;
+    let mut close_dates = Vec::new();
 
     let mut conn = db.conn.lock().map_err(|e| e.to_string())?;
 
@@ -489,10 +491,13 @@ pub async fn sync_exchange_trades(
                     drop(tx); // Drop transaction to rollback
                     return Err(format!("Sync failed - no trades imported. Error: {}", errors.join("; ")));
                 } else {
+                    record_revision(&tx, &trade.id, "CREATE", "API_IMPORT", None)?;
+                    reindex_trade(&tx, &trade.id, true)?;
                     imported += 1;
                     if let Some(pnl) = trade.total_pnl {
                         total_pnl += pnl;
                     }
+                    close_dates.extend(trade.close_date);
                 }
             }
             Err(e) => {
@@ -536,6 +541,9 @@ pub async fn sync_exchange_trades(
     )
     .map_err(|e| e.to_string())?;
 
+    // Refresh daily aggregates for the days that received trades, inside the same transaction
+    refresh_daily_stats(&tx, &close_dates)?;
+
     // Commit the transaction
     tx.commit().map_err(|e| e.to_string())?;
 
@@ -667,6 +675,9 @@ fn map_raw_trade_to_trade(
         effective_weighted_rr: Some(planned_weighted_rr),
         total_pnl: Some(raw.pnl),
         pnl_in_r,
+        fees: Some(raw.fee),
+        funding: None,
+        execution_score: None,
         notes: format!("Imported from {} API", exchange),
         execution_portfolio: None,
         execution_r_percent: None,
@@ -692,7 +703,7 @@ fn insert_trade(conn: &rusqlite::Connection, trade: &Trade) -> Result<(), rusqli
             planned_pe, planned_sl, leverage, planned_tps, planned_entries,
             position_type, one_r, margin, position_size, quantity, planned_weighted_rr,
             effective_pe, effective_entries, close_date, exits,
-            effective_weighted_rr, total_pnl, pnl_in_r,
+            effective_weighted_rr, total_pnl, pnl_in_r, fees, funding,
             notes, execution_portfolio, execution_r_percent, execution_margin,
             execution_position_size, execution_quantity, execution_one_r, execution_potential_profit,
             import_fingerprint, import_source, created_at, updated_at
@@ -702,7 +713,7 @@ fn insert_trade(conn: &rusqlite::Connection, trade: &Trade) -> Result<(), rusqli
             ?, ?, ?, ?, ?,
             ?, ?, ?, ?, ?, ?,
             ?, ?, ?, ?,
-            ?, ?, ?,
+            ?, ?, ?, ?, ?,
             ?, ?, ?, ?, ?, ?, ?, ?,
             ?, ?, ?, ?
         )",
@@ -734,6 +745,8 @@ fn insert_trade(conn: &rusqlite::Connection, trade: &Trade) -> Result<(), rusqli
             trade.effective_weighted_rr,
             trade.total_pnl,
             trade.pnl_in_r,
+            trade.fees,
+            trade.funding,
             trade.notes,
             trade.execution_portfolio,
             trade.execution_r_percent,
@@ -760,7 +773,7 @@ fn insert_trade_in_tx(tx: &rusqlite::Transaction, trade: &Trade) -> Result<(), r
             planned_pe, planned_sl, leverage, planned_tps, planned_entries,
             position_type, one_r, margin, position_size, quantity, planned_weighted_rr,
             effective_pe, effective_entries, close_date, exits,
-            effective_weighted_rr, total_pnl, pnl_in_r,
+            effective_weighted_rr, total_pnl, pnl_in_r, fees, funding,
             notes, execution_portfolio, execution_r_percent, execution_margin,
             execution_position_size, execution_quantity, execution_one_r, execution_potential_profit,
             import_fingerprint, import_source, created_at, updated_at
@@ -770,7 +783,7 @@ fn insert_trade_in_tx(tx: &rusqlite::Transaction, trade: &Trade) -> Result<(), r
             ?, ?, ?, ?, ?,
             ?, ?, ?, ?, ?, ?,
             ?, ?, ?, ?,
-            ?, ?, ?,
+            ?, ?, ?, ?, ?,
             ?, ?, ?, ?, ?, ?, ?, ?,
             ?, ?, ?, ?
         )",
@@ -802,6 +815,8 @@ fn insert_trade_in_tx(tx: &rusqlite::Transaction, trade: &Trade) -> Result<(), r
             trade.effective_weighted_rr,
             trade.total_pnl,
             trade.pnl_in_r,
+            trade.fees,
+            trade.funding,
             trade.notes,
             trade.execution_portfolio,
             trade.execution_r_percent,
@@ -0,0 +1,512 @@
+use tauri::{AppHandle, Manager, State};
+use crate::db::Database;
+use crate::models::{AddAttachmentInput, Attachment, AttachmentFile, UpdateAttachmentInput};
+use base64::{Engine as _, engine::general_purpose::STANDARD as BASE64};
+use chrono::Utc;
+use sha2::{Digest, Sha256};
+use std::collections::HashSet;
+use std::path::{Path, PathBuf};
+
+const MAX_ATTACHMENT_BYTES: usize = 25 * 1024 * 1024;
+
+fn too_large_error(size_bytes: u64) -> String {
+    format!(
+        "Attachment is too large ({:.1} MB, max {} MB)",
+        size_bytes as f64 / (1024.0 * 1024.0),
+        MAX_ATTACHMENT_BYTES / (1024 * 1024)
+    )
+}
+
+/// Read a user-picked file, refusing anything that is not a regular file or is over the size
+/// limit before it is loaded into memory
+fn read_source_file(path: &str) -> Result<Vec<u8>, String> {
+    use std::io::Read;
+
+    let file = std::fs::File::open(path).map_err(|e| format!("Failed to read {}: {}", path, e))?;
+    let metadata = file.metadata().map_err(|e| format!("Failed to read {}: {}", path, e))?;
+    if !metadata.is_file() {
+        return Err(format!("Not a regular file: {}", path));
+    }
+    if metadata.len() > MAX_ATTACHMENT_BYTES as u64 {
+        return Err(too_large_error(metadata.len()));
+    }
+
+    // Bounded in case the file grows after the size check
+    let mut bytes = Vec::with_capacity(metadata.len() as usize);
+    file.take(MAX_ATTACHMENT_BYTES as u64 + 1)
+        .read_to_end(&mut bytes)
+        .map_err(|e| format!("Failed to read {}: {}", path, e))?;
+    Ok(bytes)
+}
+
+fn map_row_to_attachment(row: &rusqlite::Row) -> rusqlite::Result<Attachment> {
+    Ok(Attachment {
+        id: row.get("id")?,
+        trade_id: row.get("trade_id")?,
+        kind: row.get("kind")?,
+        file_name: row.get("file_name")?,
+        stored_name: row.get("stored_name")?,
+        content_hash: row.get("content_hash")?,
+        mime_type: row.get("mime_type")?,
+        size_bytes: row.get("size_bytes")?,
+        caption: row.get("caption")?,
+        sort_order: row.get("sort_order")?,
+        created_at: row.get("created_at")?,
+    })
+}
+
+/// Attachments folder next to trading_journal.db, created on first use
+pub(crate) fn attachments_dir(app: &AppHandle) -> Result<PathBuf, String> {
+    let dir = app
+        .path()
+        .app_data_dir()
+        .map_err(|e| e.to_string())?
+        .join("attachments");
+    std::fs::create_dir_all(&dir).map_err(|e| e.to_string())?;
+    Ok(dir)
+}
+
+/// Mime type and file extension from the file's magic bytes
+fn detect_file_type(bytes: &[u8]) -> Option<(&'static str, &'static str)> {
+    if bytes.starts_with(b"\x89PNG\r\n\x1a\n") {
+        Some(("image/png", "png"))
+    } else if bytes.starts_with(&[0xFF, 0xD8, 0xFF]) {
+        Some(("image/jpeg", "jpg"))
+    } else if bytes.starts_with(b"%PDF-") {
+        Some(("application/pdf", "pdf"))
+    } else {
+        None
+    }
+}
+
+fn content_hash(bytes: &[u8]) -> String {
+    format!("{:x}", Sha256::digest(bytes))
+}
+
+/// Stored names are always <sha256>.<ext>, anything else (e.g. a path from a tampered backup) is rejected
+fn is_valid_stored_name(name: &str) -> bool {
+    match name.split_once('.') {
+        Some((hash, ext)) => {
+            hash.len() == 64
+                && hash.bytes().all(|b| b.is_ascii_digit() || (b'a'..=b'f').contains(&b))
+                && matches!(ext, "png" | "jpg" | "pdf")
+        }
+        None => false,
+    }
+}
+
+/// Write the file unless a file with the same content is already stored
+fn store_file(dir: &Path, stored_name: &str, bytes: &[u8]) -> Result<(), String> {
+    let path = dir.join(stored_name);
+    if path.exists() {
+        return Ok(());
+    }
+    // Write then rename so an interrupted copy never leaves a truncated file under the final name
+    let tmp_path = dir.join(format!(".{}.tmp", stored_name));
+    std::fs::write(&tmp_path, bytes).map_err(|e| e.to_string())?;
+    std::fs::rename(&tmp_path, &path).map_err(|e| e.to_string())
+}
+
+/// Attach a file to a trade. The same content attached twice to one trade returns the existing attachment
+pub(crate) fn insert_attachment(
+    conn: &rusqlite::Connection,
+    dir: &Path,
+    trade_id: &str,
+    file_name: &str,
+    bytes: &[u8],
+    kind: &str,
+    caption: &str,
+) -> Result<Attachment, String> {
+    if !matches!(kind, "PRE_TRADE" | "POST_TRADE" | "OTHER") {
+        return Err(format!("Invalid attachment kind: {} (expected PRE_TRADE, POST_TRADE or OTHER)", kind));
+    }
+    if bytes.is_empty() {
+        return Err("Attachment is empty".to_string());
+    }
+    if bytes.len() > MAX_ATTACHMENT_BYTES {
+        return Err(too_large_error(bytes.len() as u64));
+    }
+    let (mime_type, extension) = detect_file_type(bytes)
+        .ok_or_else(|| "Unsupported file type (expected PNG, JPG or PDF)".to_string())?;
+
+    let trade_exists: bool = conn
+        .query_row("SELECT EXISTS(SELECT 1 FROM trades WHERE id = ?)", [trade_id], |row| row.get(0))
+        .map_err(|e| e.to_string())?;
+    if !trade_exists {
+        return Err(format!("Trade not found: {}", trade_id));
+    }
+
+    let hash = content_hash(bytes);
+    let existing = conn.query_row(
+        "SELECT * FROM attachments WHERE trade_id = ? AND content_hash = ?",
+        [trade_id, &hash],
+        map_row_to_attachment,
+    );
+    match existing {
+        Ok(attachment) => return Ok(attachment),
+        Err(rusqlite::Error::QueryReturnedNoRows) => {}
+        Err(e) => return Err(e.to_string()),
+    }
+
+    let stored_name = format!("{}.{}", hash, extension);
+    store_file(dir, &stored_name, bytes)?;
+
+    let id = format!("ATT-{}-{}", Utc::now().timestamp_millis(), uuid::Uuid::new_v4());
+    let file_name = match file_name.trim() {
+        "" => stored_name.clone(),
+        name => name.to_string(),
+    };
+    conn.execute(
+        "INSERT INTO attachments (id, trade_id, kind, file_name, stored_name, content_hash, mime_type, size_bytes, caption, sort_order, created_at)
+         VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, (SELECT COALESCE(MAX(sort_order) + 1, 0) FROM attachments WHERE trade_id = ?), ?)",
+        rusqlite::params![
+            id,
+            trade_id,
+            kind,
+            file_name,
+            stored_name,
+            hash,
+            mime_type,
+            bytes.len() as i64,
+            caption.trim(),
+            trade_id,
+            Utc::now().timestamp(),
+        ],
+    ).map_err(|e| e.to_string())?;
+
+    get_attachment_by_id(conn, &id)
+}
+
+fn get_attachment_by_id(conn: &rusqlite::Connection, id: &str) -> Result<Attachment, String> {
+    conn.query_row("SELECT * FROM attachments WHERE id = ?", [id], map_row_to_attachment)
+        .map_err(|e| match e {
+            rusqlite::Error::QueryReturnedNoRows => format!("Attachment not found: {}", id),
+            e => e.to_string(),
+        })
+}
+
+/// Attachments of one trade in display order, or every attachment (for backups)
+pub(crate) fn load_attachments(conn: &rusqlite::Connection, trade_id: Option<&str>) -> Result<Vec<Attachment>, String> {
+    let mut stmt = conn.prepare(
+        "SELECT * FROM attachments WHERE (?1 IS NULL OR trade_id = ?1) ORDER BY trade_id, sort_order, created_at"
+    ).map_err(|e| e.to_string())?;
+
+    let attachments = stmt
+        .query_map([trade_id], map_row_to_attachment)
+        .map_err(|e| e.to_string())?
+        .collect::<Result<Vec<_>, _>>()
+        .map_err(|e| e.to_string())?;
+
+    Ok(attachments)
+}
+
+/// Remove an attachment, and its file once no other trade uses the same content
+fn remove_attachment(conn: &rusqlite::Connection, dir: &Path, id: &str) -> Result<(), String> {
+    let attachment = get_attachment_by_id(conn, id)?;
+    conn.execute("DELETE FROM attachments WHERE id = ?", [id])
+        .map_err(|e| e.to_string())?;
+
+    let still_used: bool = conn
+        .query_row(
+            "SELECT EXISTS(SELECT 1 FROM attachments WHERE stored_name = ?)",
+            [&attachment.stored_name],
+            |row| row.get(0),
+        )
+        .map_err(|e| e.to_string())?;
+    if !still_used {
+        let path = dir.join(&attachment.stored_name);
+        if path.exists() {
+            std::fs::remove_file(path).map_err(|e| e.to_string())?;
+        }
+    }
+    Ok(())
+}
+
+/// Delete stored files no attachment points to anymore, e.g. after trades were permanently deleted
+pub(crate) fn prune_attachment_files(conn: &rusqlite::Connection, dir: &Path) -> Result<usize, String> {
+    if !dir.exists() {
+        return Ok(0);
+    }
+
+    let mut stmt = conn
+        .prepare("SELECT DISTINCT stored_name FROM attachments")
+        .map_err(|e| e.to_string())?;
+    let used = stmt
+        .query_map([], |row| row.get::<_, String>(0))
+        .map_err(|e| e.to_string())?
+        .collect::<Result<HashSet<_>, _>>()
+        .map_err(|e| e.to_string())?;
+
+    let mut removed = 0;
+    for entry in std::fs::read_dir(dir).map_err(|e| e.to_string())? {
+        let path = entry.map_err(|e| e.to_string())?.path();
+        let name = path.file_name().and_then(|n| n.to_str()).unwrap_or_default().to_string();
+        if path.is_file() && !used.contains(&name) {
+            std::fs::remove_file(&path).map_err(|e| e.to_string())?;
+            removed += 1;
+        }
+    }
+
+    if removed > 0 {
+        log::info!("Removed {} unused attachment files", removed);
+    }
+    Ok(removed)
+}
+
+/// Contents of every stored file, for backups
+pub(crate) fn load_attachment_files(conn: &rusqlite::Connection, dir: &Path) -> Result<Vec<AttachmentFile>, String> {
+    let mut stmt = conn
+        .prepare("SELECT DISTINCT stored_name FROM attachments ORDER BY stored_name")
+        .map_err(|e| e.to_string())?;
+    let names = stmt
+        .query_map([], |row| row.get::<_, String>(0))
+        .map_err(|e| e.to_string())?
+        .collect::<Result<Vec<_>, _>>()
+        .map_err(|e| e.to_string())?;
+
+    let mut files = Vec::with_capacity(names.len());
+    for stored_name in names {
+        match std::fs::read(dir.join(&stored_name)) {
+            Ok(bytes) => files.push(AttachmentFile {
+                stored_name,
+                data_base64: BASE64.encode(bytes),
+            }),
+            Err(e) => log::warn!("Attachment file {} missing from backup: {}", stored_name, e),
+        }
+    }
+    Ok(files)
+}
+
+/// Write the files of a backup, checking each one against the hash in its name
+pub(crate) fn restore_attachment_files(dir: &Path, files: &[AttachmentFile]) -> Result<usize, String> {
+    let mut restored = 0;
+    for file in files {
+        if !is_valid_stored_name(&file.stored_name) {
+            return Err(format!("Invalid attachment file name in backup: {}", file.stored_name));
+        }
+        let bytes = BASE64.decode(&file.data_base64).map_err(|e| e.to_string())?;
+        if !file.stored_name.starts_with(&content_hash(&bytes)) {
+            return Err(format!("Attachment file {} is corrupted", file.stored_name));
+        }
+        store_file(dir, &file.stored_name, &bytes)?;
+        restored += 1;
+    }
+    Ok(restored)
+}
+
+#[tauri::command]
+pub async fn get_attachments(db: State<'_, Database>, trade_id: String) -> Result<Vec<Attachment>, String> {
+    let conn = db.conn.lock().map_err(|e| e.to_string())?;
+    load_attachments(&conn, Some(&trade_id))
+}
+
+/// Copy a file (or pasted image data) into the journal and attach it to a trade
+#[tauri::command]
+pub async fn add_attachment(
+    app: AppHandle,
+    db: State<'_, Database>,
+    attachment: AddAttachmentInput,
+) -> Result<Attachment, String> {
+    let (bytes, file_name) = match (&attachment.source_path, &attachment.data_base64) {
+        (Some(path), _) => {
+            let bytes = read_source_file(path)?;
+            let name = Path::new(path)
+                .file_name()
+                .and_then(|n| n.to_str())
+                .unwrap_or_default()
+                .to_string();
+            (bytes, attachment.file_name.clone().unwrap_or(name))
+        }
+        (None, Some(data)) => {
+            // Every 4 base64 characters decode to at most 3 bytes
+            let decoded_len = (data.len() / 4 * 3) as u64;
+            if decoded_len > MAX_ATTACHMENT_BYTES as u64 + 2 {
+                return Err(too_large_error(decoded_len));
+            }
+            (
+                BASE64.decode(data).map_err(|e| e.to_string())?,
+                attachment.file_name.clone().unwrap_or_default(),
+            )
+        }
+        (None, None) => return Err("Either source_path or data_base64 is required".to_string()),
+    };
+
+    let dir = attachments_dir(&app)?;
+    let conn = db.conn.lock().map_err(|e| e.to_string())?;
+    insert_attachment(
+        &conn,
+        &dir,
+        &attachment.trade_id,
+        &file_name,
+        &bytes,
+        &attachment.kind,
+        &attachment.caption,
+    )
+}
+
+#[tauri::command]
+pub async fn update_attachment(
+    db: State<'_, Database>,
+    id: String,
+    attachment: UpdateAttachmentInput,
+) -> Result<Attachment, String> {
+    let conn = db.conn.lock().map_err(|e| e.to_string())?;
+    let existing = get_attachment_by_id(&conn, &id)?;
+
+    let kind = attachment.kind.unwrap_or(existing.kind);
+    if !matches!(kind.as_str(), "PRE_TRADE" | "POST_TRADE" | "OTHER") {
+        return Err(format!("Invalid attachment kind: {} (expected PRE_TRADE, POST_TRADE or OTHER)", kind));
+    }
+    let caption = attachment.caption.unwrap_or(existing.caption);
+
+    conn.execute(
+        "UPDATE attachments SET kind = ?, caption = ? WHERE id = ?",
+        rusqlite::params![kind, caption.trim(), id],
+    ).map_err(|e| e.to_string())?;
+
+    get_attachment_by_id(&conn, &id)
+}
+
+#[tauri::command]
+pub async fn delete_attachment(app: AppHandle, db: State<'_, Database>, id: String) -> Result<(), String> {
+    let dir = attachments_dir(&app)?;
+    let conn = db.conn.lock().map_err(|e| e.to_string())?;
+    remove_attachment(&conn, &dir, &id)
+}
+
+/// Set the display order of a trade's attachments, `attachment_ids` must list all of them
+#[tauri::command]
+pub async fn reorder_attachments(
+    db: State<'_, Database>,
+    trade_id: String,
+    attachment_ids: Vec<String>,
+) -> Result<Vec<Attachment>, String> {
+    let mut conn = db.conn.lock().map_err(|e| e.to_string())?;
+
+    let current: HashSet<String> = load_attachments(&conn, Some(&trade_id))?
+        .into_iter()
+        .map(|a| a.id)
+        .collect();
+    let requested: HashSet<String> = attachment_ids.iter().cloned().collect();
+    if requested != current || requested.len() != attachment_ids.len() {
+        return Err("Attachment order must list each attachment of the trade exactly once".to_string());
+    }
+
+    let tx = conn.transaction().map_err(|e| e.to_string())?;
+    for (position, id) in attachment_ids.iter().enumerate() {
+        tx.execute(
+            "UPDATE attachments SET sort_order = ? WHERE id = ?",
+            rusqlite::params![position as i64, id],
+        ).map_err(|e| e.to_string())?;
+    }
+    tx.commit().map_err(|e| e.to_string())?;
+
+    load_attachments(&conn, Some(&trade_id))
+}
+
+/// File contents as base64, for previews
+#[tauri::command]
+pub async fn get_attachment_data(app: AppHandle, db: State<'_, Database>, id: String) -> Result<String, String> {
+    let stored_name = {
+        let conn = db.conn.lock().map_err(|e| e.to_string())?;
+        get_attachment_by_id(&conn, &id)?.stored_name
+    };
+    let bytes = std::fs::read(attachments_dir(&app)?.join(&stored_name))
+        .map_err(|e| format!("Attachment file {} is missing: {}", stored_name, e))?;
+    Ok(BASE64.encode(bytes))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::db::{insert_test_trade, test_connection};
+    use rusqlite::Connection;
+
+    const PNG: &[u8] = b"\x89PNG\r\n\x1a\nfake chart";
+
+    fn setup() -> Connection {
+        let conn = test_connection();
+        insert_test_trade(&conn, "a", &[]);
+        insert_test_trade(&conn, "b", &[]);
+        conn
+    }
+
+    fn stored_files(dir: &Path) -> usize {
+        std::fs::read_dir(dir).unwrap().count()
+    }
+
+    #[test]
+    fn test_same_content_is_stored_once() {
+        let conn = setup();
+        let dir = tempfile::tempdir().unwrap();
+
+        let first = insert_attachment(&conn, dir.path(), "a", "entry.png", PNG, "PRE_TRADE", "").unwrap();
+        let again = insert_attachment(&conn, dir.path(), "a", "copy.png", PNG, "OTHER", "").unwrap();
+        assert_eq!(again.id, first.id);
+
+        let other = insert_attachment(&conn, dir.path(), "b", "", PNG, "POST_TRADE", " exit ").unwrap();
+        assert_ne!(other.id, first.id);
+        assert_eq!(other.stored_name, first.stored_name);
+        assert_eq!(other.caption, "exit");
+        assert_eq!(stored_files(dir.path()), 1);
+
+        let pdf = insert_attachment(&conn, dir.path(), "a", "plan.pdf", b"%PDF-1.7 plan", "OTHER", "").unwrap();
+        assert_eq!((pdf.mime_type.as_str(), pdf.sort_order), ("application/pdf", 1));
+        assert_eq!(stored_files(dir.path()), 2);
+    }
+
+    #[test]
+    fn test_files_are_removed_with_their_last_attachment() {
+        let conn = setup();
+        let dir = tempfile::tempdir().unwrap();
+        let on_a = insert_attachment(&conn, dir.path(), "a", "chart.png", PNG, "OTHER", "").unwrap();
+        insert_attachment(&conn, dir.path(), "b", "chart.png", PNG, "OTHER", "").unwrap();
+        insert_attachment(&conn, dir.path(), "b", "plan.pdf", b"%PDF-1.7 plan", "OTHER", "").unwrap();
+
+        remove_attachment(&conn, dir.path(), &on_a.id).unwrap();
+        assert_eq!(stored_files(dir.path()), 2);
+
+        // Permanent deletion cascades to the rows, pruning then drops the files
+        conn.execute("DELETE FROM trades WHERE id = 'b'", []).unwrap();
+        assert!(load_attachments(&conn, None).unwrap().is_empty());
+        assert_eq!(prune_attachment_files(&conn, dir.path()).unwrap(), 2);
+        assert_eq!(stored_files(dir.path()), 0);
+    }
+
+    #[test]
+    fn test_rejects_unsupported_files_and_bad_backups() {
+        let conn = setup();
+        let dir = tempfile::tempdir().unwrap();
+
+        assert!(insert_attachment(&conn, dir.path(), "a", "notes.txt", b"hello", "OTHER", "").is_err());
+        assert!(insert_attachment(&conn, dir.path(), "a", "chart.png", PNG, "DURING", "").is_err());
+        assert!(insert_attachment(&conn, dir.path(), "missing", "chart.png", PNG, "OTHER", "").is_err());
+
+        let traversal = AttachmentFile {
+            stored_name: "../trading_journal.db".to_string(),
+            data_base64: BASE64.encode(PNG),
+        };
+        assert!(restore_attachment_files(dir.path(), &[traversal]).is_err());
+
+        let tampered = AttachmentFile {
+            stored_name: format!("{}.png", content_hash(b"something else")),
+            data_base64: BASE64.encode(PNG),
+        };
+        assert!(restore_attachment_files(dir.path(), &[tampered]).is_err());
+        assert_eq!(stored_files(dir.path()), 0);
+    }
+
+    #[test]
+    fn test_source_file_checked_before_reading() {
+        let dir = tempfile::tempdir().unwrap();
+        let chart = dir.path().join("chart.png");
+        std::fs::write(&chart, PNG).unwrap();
+        assert_eq!(read_source_file(chart.to_str().unwrap()).unwrap(), PNG);
+
+        // Sparse, so the test does not write the bytes it refuses to read
+        let huge = dir.path().join("huge.png");
+        std::fs::File::create(&huge).unwrap().set_len(4 * 1024 * 1024 * 1024).unwrap();
+        assert!(read_source_file(huge.to_str().unwrap()).unwrap_err().contains("too large"));
+        assert!(read_source_file(dir.path().to_str().unwrap()).unwrap_err().contains("Not a regular file"));
+    }
+}
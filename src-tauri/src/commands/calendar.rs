@@ -0,0 +1,252 @@
+use tauri::State;
+use crate::commands::settings::load_timezone;
+use crate::commands::stats::{local_date, local_midnight, period_start, TagFilter};
+use crate::db::Database;
+use chrono::Datelike;
+use chrono_tz::Tz;
+use rusqlite::types::Value;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct CalendarBucket {
+    pub period: String, // first day of the day/week/month, YYYY-MM-DD in the user's timezone
+    pub trades_opened: i32,
+    pub trade_count: i32, // trades closed in the period
+    pub wins: i32,
+    pub losses: i32,
+    pub breakevens: i32,
+    pub win_rate: f64,
+    pub total_pnl: f64,
+    pub net_pnl: f64,
+    pub total_r: f64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CalendarData {
+    pub year: i32,
+    pub timezone: String,
+    pub days: Vec<CalendarBucket>,
+    pub weeks: Vec<CalendarBucket>, // weeks start on Monday, the first may start in the previous year
+    pub months: Vec<CalendarBucket>,
+    pub available_years: Vec<i32>, // years with any trade activity, most recent first
+}
+
+/// Trade columns needed to build calendar buckets
+#[derive(Debug, Clone)]
+struct CalendarTrade {
+    trade_date: i64,
+    close_date: Option<i64>,
+    status: String,
+    total_pnl: Option<f64>,
+    fees: Option<f64>,
+    funding: Option<f64>,
+    pnl_in_r: Option<f64>,
+}
+
+/// Aggregate trades into day/week/month buckets for one calendar year in `tz`.
+/// Openings are bucketed by trade_date, realized results by close_date.
+fn aggregate_calendar(trades: &[CalendarTrade], year: i32, tz: &Tz) -> Result<CalendarData, String> {
+    let mut intervals: [(&str, BTreeMap<String, CalendarBucket>); 3] = [
+        ("day", BTreeMap::new()),
+        ("week", BTreeMap::new()),
+        ("month", BTreeMap::new()),
+    ];
+
+    for trade in trades {
+        if local_date(trade.trade_date, tz)?.year() == year {
+            for (interval, buckets) in intervals.iter_mut() {
+                let period = period_start(trade.trade_date, tz, interval)?.format("%Y-%m-%d").to_string();
+                buckets
+                    .entry(period.clone())
+                    .or_insert_with(|| CalendarBucket { period, ..Default::default() })
+                    .trades_opened += 1;
+            }
+        }
+
+        let Some(close_date) = trade.close_date else {
+            continue;
+        };
+        if !matches!(trade.status.as_str(), "WIN" | "LOSS" | "BE") || local_date(close_date, tz)?.year() != year {
+            continue;
+        }
+
+        let pnl = trade.total_pnl.unwrap_or(0.0);
+        for (interval, buckets) in intervals.iter_mut() {
+            let period = period_start(close_date, tz, interval)?.format("%Y-%m-%d").to_string();
+            let bucket = buckets.entry(period.clone()).or_insert_with(|| CalendarBucket { period, ..Default::default() });
+            bucket.trade_count += 1;
+            match trade.status.as_str() {
+                "WIN" => bucket.wins += 1,
+                "LOSS" => bucket.losses += 1,
+                _ => bucket.breakevens += 1,
+            }
+            bucket.total_pnl += pnl;
+            bucket.net_pnl += pnl - trade.fees.unwrap_or(0.0) + trade.funding.unwrap_or(0.0);
+            bucket.total_r += trade.pnl_in_r.unwrap_or(0.0);
+        }
+    }
+
+    let [days, weeks, months] = intervals.map(|(_, buckets)| {
+        buckets
+            .into_values()
+            .map(|mut bucket| {
+                let decided = bucket.wins + bucket.losses;
+                if decided > 0 {
+                    bucket.win_rate = bucket.wins as f64 / decided as f64 * 100.0;
+                }
+                bucket
+            })
+            .collect::<Vec<_>>()
+    });
+
+    Ok(CalendarData {
+        year,
+        timezone: tz.name().to_string(),
+        days,
+        weeks,
+        months,
+        available_years: Vec::new(),
+    })
+}
+
+/// Years (in `tz`) with any trade opened or closed, most recent first
+fn active_years(dates: &[(i64, Option<i64>)], tz: &Tz) -> Result<Vec<i32>, String> {
+    let mut years: Vec<i32> = Vec::new();
+    for (trade_date, close_date) in dates {
+        for timestamp in std::iter::once(*trade_date).chain(*close_date) {
+            let year = local_date(timestamp, tz)?.year();
+            if !years.contains(&year) {
+                years.push(year);
+            }
+        }
+    }
+    years.sort_unstable_by(|a, b| b.cmp(a));
+    Ok(years)
+}
+
+/// PnL, R, trade count and win rate per day, week and month of a year, in the user's timezone
+#[tauri::command]
+pub async fn get_calendar_data(
+    db: State<'_, Database>,
+    year: Option<i32>,
+    tag_ids: Option<Vec<String>>,
+) -> Result<CalendarData, String> {
+    let conn = db.conn.lock().map_err(|e| e.to_string())?;
+
+    let tz = load_timezone(&conn);
+    let year = year.unwrap_or_else(|| chrono::Utc::now().with_timezone(&tz).year());
+
+    let year_start = chrono::NaiveDate::from_ymd_opt(year, 1, 1).ok_or(format!("Invalid year: {}", year))?;
+    let next_year_start = chrono::NaiveDate::from_ymd_opt(year + 1, 1, 1).ok_or(format!("Invalid year: {}", year))?;
+    let (start, end) = (local_midnight(year_start, &tz), local_midnight(next_year_start, &tz));
+
+    let (tag_filter, tag_params) = TagFilter::new(tag_ids).filter();
+    let mut params: Vec<Value> = vec![start.into(), end.into()];
+    params.extend(tag_params.iter().cloned().map(Value::from));
+
+    // Tag placeholders are unnumbered and follow ?1 and ?2
+    let mut stmt = conn.prepare(&format!(
+        "SELECT trade_date, close_date, status, total_pnl, fees, funding, pnl_in_r
+         FROM trades
+         WHERE deleted_at IS NULL
+           AND ((trade_date >= ?1 AND trade_date < ?2) OR (close_date >= ?1 AND close_date < ?2)) {}",
+        tag_filter
+    )).map_err(|e| e.to_string())?;
+
+    let trades = stmt.query_map(rusqlite::params_from_iter(params.iter()), |row| {
+        Ok(CalendarTrade {
+            trade_date: row.get(0)?,
+            close_date: row.get(1)?,
+            status: row.get(2)?,
+            total_pnl: row.get(3)?,
+            fees: row.get(4)?,
+            funding: row.get(5)?,
+            pnl_in_r: row.get(6)?,
+        })
+    })
+    .map_err(|e| e.to_string())?
+    .collect::<Result<Vec<_>, _>>()
+    .map_err(|e| e.to_string())?;
+
+    let mut stmt = conn.prepare(&format!(
+        "SELECT trade_date, close_date FROM trades WHERE deleted_at IS NULL {}",
+        tag_filter
+    )).map_err(|e| e.to_string())?;
+
+    let dates = stmt.query_map(rusqlite::params_from_iter(tag_params.iter()), |row| Ok((row.get(0)?, row.get(1)?)))
+        .map_err(|e| e.to_string())?
+        .collect::<Result<Vec<_>, _>>()
+        .map_err(|e| e.to_string())?;
+
+    let mut data = aggregate_calendar(&trades, year, &tz)?;
+    data.available_years = active_years(&dates, &tz)?;
+
+    Ok(data)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn closed(trade_date: i64, close_date: i64, status: &str, pnl: f64, r: f64) -> CalendarTrade {
+        CalendarTrade {
+            trade_date,
+            close_date: Some(close_date),
+            status: status.to_string(),
+            total_pnl: Some(pnl),
+            fees: Some(1.0),
+            funding: None,
+            pnl_in_r: Some(r),
+        }
+    }
+
+    #[test]
+    fn test_calendar_buckets_by_close_date() {
+        // 2024-01-01 10:00 UTC (Monday) and 2024-01-03 10:00 UTC (Wednesday)
+        let monday = 1704103200;
+        let wednesday = monday + 2 * 86400;
+        let trades = vec![
+            closed(monday, monday, "WIN", 200.0, 2.0),
+            closed(monday, wednesday, "LOSS", -100.0, -1.0),
+        ];
+
+        let data = aggregate_calendar(&trades, 2024, &Tz::UTC).unwrap();
+
+        assert_eq!(data.days.len(), 2);
+        assert_eq!(data.days[0].period, "2024-01-01");
+        assert_eq!(data.days[0].trades_opened, 2);
+        assert_eq!(data.days[0].trade_count, 1);
+        assert_eq!(data.days[1].period, "2024-01-03");
+        assert_eq!(data.days[1].trades_opened, 0);
+
+        assert_eq!(data.weeks.len(), 1);
+        assert_eq!(data.weeks[0].trade_count, 2);
+        assert!((data.weeks[0].win_rate - 50.0).abs() < 1e-9);
+        assert!((data.weeks[0].total_pnl - 100.0).abs() < 1e-9);
+        assert!((data.weeks[0].net_pnl - 98.0).abs() < 1e-9);
+        assert!((data.weeks[0].total_r - 1.0).abs() < 1e-9);
+
+        assert_eq!(data.months.len(), 1);
+        assert_eq!(data.months[0].period, "2024-01-01");
+    }
+
+    #[test]
+    fn test_calendar_respects_timezone_at_year_boundary() {
+        // 2023-12-31 20:00 UTC is already 2024-01-01 in UTC+8
+        let new_years_eve = 1704052800;
+        let trades = vec![closed(new_years_eve, new_years_eve, "WIN", 50.0, 0.5)];
+
+        let utc = aggregate_calendar(&trades, 2024, &Tz::UTC).unwrap();
+        assert!(utc.days.is_empty());
+
+        let singapore: Tz = "Asia/Singapore".parse().unwrap();
+        let local = aggregate_calendar(&trades, 2024, &singapore).unwrap();
+        assert_eq!(local.days.len(), 1);
+        assert_eq!(local.days[0].period, "2024-01-01");
+
+        let dates = [(new_years_eve, Some(new_years_eve + 86400 * 400))];
+        assert_eq!(active_years(&dates, &Tz::UTC).unwrap(), vec![2025, 2023]);
+        assert_eq!(active_years(&dates, &singapore).unwrap(), vec![2025, 2024]);
+    }
+}
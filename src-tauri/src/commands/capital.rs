@@ -0,0 +1,402 @@
+use tauri::State;
+use crate::commands::settings::load_timezone;
+use crate::commands::stats::local_date;
+use crate::db::Database;
+use crate::models::{CapitalFlow, CreateCapitalFlowInput, UpdateCapitalFlowInput};
+use chrono::Utc;
+use chrono_tz::Tz;
+use serde::{Deserialize, Serialize};
+
+const SECONDS_PER_YEAR: f64 = 365.25 * 24.0 * 60.0 * 60.0;
+
+/// Ledger-aware account balance: initial capital + net deposits + realized net PnL
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AccountBalance {
+    pub initial_capital: f64,
+    pub total_deposits: f64,
+    pub total_withdrawals: f64,
+    pub contributed_capital: f64, // initial_capital + deposits - withdrawals
+    pub realized_pnl: f64,        // closed trades, net of fees and funding
+    pub balance: f64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LedgerEquityPoint {
+    pub date: String,
+    pub pnl: f64,
+    pub net_flow: f64,
+    pub balance: f64,
+    pub time_weighted_return: f64, // cumulative %, flows excluded
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CapitalPerformance {
+    pub account: AccountBalance,
+    pub series: Vec<LedgerEquityPoint>,
+    pub time_weighted_return: f64,                    // %
+    pub money_weighted_return: Option<f64>,           // % over the whole period (IRR based)
+    pub money_weighted_return_annualized: Option<f64>, // % per year (IRR)
+}
+
+/// Cash movement affecting the account balance: a closed trade's PnL or a capital flow
+#[derive(Debug, Clone)]
+struct LedgerEvent {
+    timestamp: i64,
+    pnl: f64,
+    flow: f64,
+}
+
+fn map_row_to_capital_flow(row: &rusqlite::Row) -> rusqlite::Result<CapitalFlow> {
+    Ok(CapitalFlow {
+        id: row.get("id")?,
+        flow_date: row.get("flow_date")?,
+        flow_type: row.get("flow_type")?,
+        amount: row.get("amount")?,
+        note: row.get("note")?,
+        created_at: row.get("created_at")?,
+        updated_at: row.get("updated_at")?,
+    })
+}
+
+fn validate_flow(flow_type: &str, amount: f64) -> Result<(), String> {
+    if flow_type != "DEPOSIT" && flow_type != "WITHDRAWAL" {
+        return Err(format!("Invalid flow type: {} (expected DEPOSIT or WITHDRAWAL)", flow_type));
+    }
+    if !amount.is_finite() || amount <= 0.0 {
+        return Err("Amount must be a positive number".to_string());
+    }
+    Ok(())
+}
+
+pub(crate) fn load_capital_flows(conn: &rusqlite::Connection) -> Result<Vec<CapitalFlow>, String> {
+    let mut stmt = conn
+        .prepare("SELECT * FROM capital_flows ORDER BY flow_date ASC, created_at ASC")
+        .map_err(|e| e.to_string())?;
+
+    let flows = stmt
+        .query_map([], map_row_to_capital_flow)
+        .map_err(|e| e.to_string())?
+        .collect::<Result<Vec<_>, _>>()
+        .map_err(|e| e.to_string())?;
+
+    Ok(flows)
+}
+
+fn get_capital_flow_by_id(conn: &rusqlite::Connection, id: &str) -> Result<CapitalFlow, String> {
+    conn.query_row("SELECT * FROM capital_flows WHERE id = ?", [id], map_row_to_capital_flow)
+        .map_err(|e| e.to_string())
+}
+
+/// Current account balance from settings, the capital ledger and closed trades.
+/// Used wherever a portfolio value snapshot is needed instead of settings.initial_capital.
+pub(crate) fn load_account_balance(conn: &rusqlite::Connection) -> Result<AccountBalance, String> {
+    let initial_capital: f64 = conn
+        .query_row("SELECT initial_capital FROM settings WHERE id = 1", [], |row| row.get(0))
+        .map_err(|e| e.to_string())?;
+
+    let (total_deposits, total_withdrawals): (f64, f64) = conn
+        .query_row(
+            "SELECT COALESCE(SUM(CASE WHEN flow_type = 'DEPOSIT' THEN amount END), 0.0),
+                    COALESCE(SUM(CASE WHEN flow_type = 'WITHDRAWAL' THEN amount END), 0.0)
+             FROM capital_flows",
+            [],
+            |row| Ok((row.get(0)?, row.get(1)?)),
+        )
+        .map_err(|e| e.to_string())?;
+
+    let realized_pnl: f64 = conn
+        .query_row(
+            "SELECT COALESCE(SUM(total_pnl - COALESCE(fees, 0.0) + COALESCE(funding, 0.0)), 0.0)
+             FROM trades
+             WHERE deleted_at IS NULL AND status IN ('WIN', 'LOSS', 'BE') AND total_pnl IS NOT NULL",
+            [],
+            |row| row.get(0),
+        )
+        .map_err(|e| e.to_string())?;
+
+    let contributed_capital = initial_capital + total_deposits - total_withdrawals;
+
+    Ok(AccountBalance {
+        initial_capital,
+        total_deposits,
+        total_withdrawals,
+        contributed_capital,
+        realized_pnl,
+        balance: contributed_capital + realized_pnl,
+    })
+}
+
+/// Daily balance series with chain-linked time-weighted return.
+/// Flows on a day are treated as arriving at the start of that day.
+fn compute_ledger_series(initial_capital: f64, events: &[LedgerEvent], tz: &Tz) -> Result<Vec<LedgerEquityPoint>, String> {
+    let mut daily: std::collections::BTreeMap<String, (f64, f64)> = std::collections::BTreeMap::new();
+    for event in events {
+        let date = local_date(event.timestamp, tz)?.format("%Y-%m-%d").to_string();
+        let entry = daily.entry(date).or_insert((0.0, 0.0));
+        entry.0 += event.pnl;
+        entry.1 += event.flow;
+    }
+
+    let mut balance = initial_capital;
+    let mut growth = 1.0;
+    let points = daily
+        .into_iter()
+        .map(|(date, (pnl, net_flow))| {
+            let invested = balance + net_flow;
+            balance = invested + pnl;
+            if invested > 0.0 {
+                growth *= balance / invested;
+            }
+            LedgerEquityPoint {
+                date,
+                pnl,
+                net_flow,
+                balance,
+                time_weighted_return: (growth - 1.0) * 100.0,
+            }
+        })
+        .collect();
+
+    Ok(points)
+}
+
+/// Annualized internal rate of return of (timestamp, amount) cash flows, amounts from the
+/// investor's point of view (money in negative, money out positive). None if it can't be bracketed.
+fn internal_rate_of_return(cash_flows: &[(i64, f64)]) -> Option<f64> {
+    let start = cash_flows.iter().map(|(ts, _)| *ts).min()?;
+    let npv = |rate: f64| -> f64 {
+        cash_flows
+            .iter()
+            .map(|(ts, amount)| amount / (1.0 + rate).powf((ts - start) as f64 / SECONDS_PER_YEAR))
+            .sum()
+    };
+
+    let mut low = -0.9999;
+    let mut high = 1.0;
+    while npv(low).signum() == npv(high).signum() {
+        high *= 2.0;
+        if high > 1e6 {
+            return None;
+        }
+    }
+
+    for _ in 0..200 {
+        let mid = (low + high) / 2.0;
+        if npv(mid).signum() == npv(low).signum() {
+            low = mid;
+        } else {
+            high = mid;
+        }
+    }
+
+    Some((low + high) / 2.0)
+}
+
+/// Money-weighted return: initial capital and deposits are invested, withdrawals and the
+/// final balance are returned. Returns (period %, annualized %).
+fn compute_money_weighted_return(
+    initial_capital: f64,
+    events: &[LedgerEvent],
+    final_balance: f64,
+) -> Option<(f64, f64)> {
+    let start = events.iter().map(|e| e.timestamp).min()?;
+    let end = events.iter().map(|e| e.timestamp).max()?;
+    if end <= start {
+        return None;
+    }
+
+    let mut cash_flows = vec![(start, -initial_capital)];
+    cash_flows.extend(events.iter().filter(|e| e.flow != 0.0).map(|e| (e.timestamp, -e.flow)));
+    cash_flows.push((end, final_balance));
+
+    let annual = internal_rate_of_return(&cash_flows)?;
+    let years = (end - start) as f64 / SECONDS_PER_YEAR;
+    Some((((1.0 + annual).powf(years) - 1.0) * 100.0, annual * 100.0))
+}
+
+#[tauri::command]
+pub async fn get_capital_flows(db: State<'_, Database>) -> Result<Vec<CapitalFlow>, String> {
+    let conn = db.conn.lock().map_err(|e| e.to_string())?;
+    let mut flows = load_capital_flows(&conn)?;
+    flows.reverse(); // Most recent first
+    Ok(flows)
+}
+
+#[tauri::command]
+pub async fn create_capital_flow(
+    db: State<'_, Database>,
+    flow: CreateCapitalFlowInput,
+) -> Result<CapitalFlow, String> {
+    validate_flow(&flow.flow_type, flow.amount)?;
+
+    let conn = db.conn.lock().map_err(|e| e.to_string())?;
+    let id = format!("FLOW-{}-{}", Utc::now().timestamp_millis(), uuid::Uuid::new_v4());
+    let now = Utc::now().timestamp();
+
+    conn.execute(
+        "INSERT INTO capital_flows (id, flow_date, flow_type, amount, note, created_at, updated_at)
+         VALUES (?, ?, ?, ?, ?, ?, ?)",
+        rusqlite::params![id, flow.flow_date, flow.flow_type, flow.amount, flow.note, now, now],
+    ).map_err(|e| e.to_string())?;
+
+    get_capital_flow_by_id(&conn, &id)
+}
+
+#[tauri::command]
+pub async fn update_capital_flow(
+    db: State<'_, Database>,
+    id: String,
+    flow: UpdateCapitalFlowInput,
+) -> Result<CapitalFlow, String> {
+    let conn = db.conn.lock().map_err(|e| e.to_string())?;
+    let existing = get_capital_flow_by_id(&conn, &id)?;
+
+    let flow_type = flow.flow_type.unwrap_or(existing.flow_type);
+    let amount = flow.amount.unwrap_or(existing.amount);
+    validate_flow(&flow_type, amount)?;
+
+    conn.execute(
+        "UPDATE capital_flows SET flow_date = ?, flow_type = ?, amount = ?, note = ?, updated_at = ? WHERE id = ?",
+        rusqlite::params![
+            flow.flow_date.unwrap_or(existing.flow_date),
+            flow_type,
+            amount,
+            flow.note.unwrap_or(existing.note),
+            Utc::now().timestamp(),
+            id,
+        ],
+    ).map_err(|e| e.to_string())?;
+
+    get_capital_flow_by_id(&conn, &id)
+}
+
+#[tauri::command]
+pub async fn delete_capital_flow(db: State<'_, Database>, id: String) -> Result<(), String> {
+    let conn = db.conn.lock().map_err(|e| e.to_string())?;
+    let deleted = conn
+        .execute("DELETE FROM capital_flows WHERE id = ?", [&id])
+        .map_err(|e| e.to_string())?;
+    if deleted == 0 {
+        return Err(format!("Capital flow not found: {}", id));
+    }
+    Ok(())
+}
+
+#[tauri::command]
+pub async fn get_account_balance(db: State<'_, Database>) -> Result<AccountBalance, String> {
+    let conn = db.conn.lock().map_err(|e| e.to_string())?;
+    load_account_balance(&conn)
+}
+
+/// Flow-aware equity series with time-weighted and money-weighted returns over the full history
+#[tauri::command]
+pub async fn get_capital_performance(db: State<'_, Database>) -> Result<CapitalPerformance, String> {
+    let conn = db.conn.lock().map_err(|e| e.to_string())?;
+
+    let tz = load_timezone(&conn);
+    let account = load_account_balance(&conn)?;
+
+    let mut stmt = conn.prepare(
+        "SELECT close_date, total_pnl - COALESCE(fees, 0.0) + COALESCE(funding, 0.0)
+         FROM trades
+         WHERE deleted_at IS NULL AND status IN ('WIN', 'LOSS', 'BE')
+           AND total_pnl IS NOT NULL AND close_date IS NOT NULL"
+    ).map_err(|e| e.to_string())?;
+
+    let mut events = stmt
+        .query_map([], |row| {
+            Ok(LedgerEvent {
+                timestamp: row.get(0)?,
+                pnl: row.get(1)?,
+                flow: 0.0,
+            })
+        })
+        .map_err(|e| e.to_string())?
+        .collect::<Result<Vec<_>, _>>()
+        .map_err(|e| e.to_string())?;
+
+    events.extend(load_capital_flows(&conn)?.iter().map(|flow| LedgerEvent {
+        timestamp: flow.flow_date,
+        pnl: 0.0,
+        flow: flow.signed_amount(),
+    }));
+    events.sort_by_key(|e| e.timestamp);
+
+    let series = compute_ledger_series(account.initial_capital, &events, &tz)?;
+    let time_weighted_return = series.last().map(|p| p.time_weighted_return).unwrap_or(0.0);
+    let money_weighted = compute_money_weighted_return(account.initial_capital, &events, account.balance);
+
+    Ok(CapitalPerformance {
+        account,
+        series,
+        time_weighted_return,
+        money_weighted_return: money_weighted.map(|(period, _)| period),
+        money_weighted_return_annualized: money_weighted.map(|(_, annual)| annual),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const DAY: i64 = 86400;
+
+    fn pnl(timestamp: i64, pnl: f64) -> LedgerEvent {
+        LedgerEvent { timestamp, pnl, flow: 0.0 }
+    }
+
+    fn flow(timestamp: i64, flow: f64) -> LedgerEvent {
+        LedgerEvent { timestamp, pnl: 0.0, flow }
+    }
+
+    #[test]
+    fn test_time_weighted_return_ignores_deposits() {
+        // +10% on 1000, then a 1100 deposit, then +10% on 2200
+        let events = vec![pnl(0, 100.0), flow(DAY, 1100.0), pnl(DAY, 220.0)];
+        let series = compute_ledger_series(1000.0, &events, &Tz::UTC).unwrap();
+
+        assert_eq!(series.len(), 2);
+        assert!((series[1].balance - 2420.0).abs() < 1e-9);
+        assert!((series[1].net_flow - 1100.0).abs() < 1e-9);
+        assert!((series[1].time_weighted_return - 21.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_withdrawal_does_not_count_as_loss() {
+        let events = vec![flow(0, -500.0), pnl(DAY, 50.0)];
+        let series = compute_ledger_series(1000.0, &events, &Tz::UTC).unwrap();
+
+        assert!((series[0].balance - 500.0).abs() < 1e-9);
+        assert!(series[0].time_weighted_return.abs() < 1e-9);
+        assert!((series[1].time_weighted_return - 10.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_money_weighted_return_without_flows_matches_simple_return() {
+        let year = SECONDS_PER_YEAR as i64;
+        let events = vec![pnl(0, 0.0), pnl(year, 100.0)];
+        let (period, annual) = compute_money_weighted_return(1000.0, &events, 1100.0).unwrap();
+
+        assert!((period - 10.0).abs() < 1e-6);
+        assert!((annual - 10.0).abs() < 1e-6);
+    }
+
+    #[test]
+    fn test_money_weighted_return_weights_late_deposit() {
+        // Deposit arrives halfway through a year that ends with 10% gain on the original capital only
+        let year = SECONDS_PER_YEAR as i64;
+        let events = vec![pnl(0, 0.0), flow(year / 2, 1000.0), pnl(year, 100.0)];
+        let (_, annual) = compute_money_weighted_return(1000.0, &events, 2100.0).unwrap();
+
+        // Less than 10% because half the money was only invested for half the year
+        assert!(annual > 0.0 && annual < 10.0);
+        assert!(compute_money_weighted_return(1000.0, &[], 1000.0).is_none());
+    }
+
+    #[test]
+    fn test_validate_flow() {
+        assert!(validate_flow("DEPOSIT", 100.0).is_ok());
+        assert!(validate_flow("WITHDRAWAL", 0.0).is_err());
+        assert!(validate_flow("TRANSFER", 100.0).is_err());
+    }
+}
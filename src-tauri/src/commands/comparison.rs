@@ -0,0 +1,151 @@
+use tauri::State;
+use crate::commands::settings::load_timezone;
+use crate::commands::stats::{load_window_stats, DashboardStats, DateWindow, TagFilter};
+use crate::db::Database;
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MetricDelta {
+    pub metric: String,
+    pub current: f64,
+    pub baseline: f64, // per-period average for additive metrics when baseline_periods > 1
+    pub absolute_delta: Option<f64>, // None when either side is not finite (e.g. profit factor with no losses)
+    pub percent_delta: Option<f64>,  // None when the baseline is zero
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PeriodComparison {
+    pub current_start: i64,
+    pub current_end: i64,
+    pub baseline_start: i64,
+    pub baseline_end: i64,
+    pub baseline_periods: u32,
+    pub current: DashboardStats,
+    pub baseline: DashboardStats, // totals over the whole baseline range
+    pub deltas: Vec<MetricDelta>,
+}
+
+fn delta(metric: &str, current: f64, baseline: f64) -> MetricDelta {
+    let comparable = current.is_finite() && baseline.is_finite();
+    MetricDelta {
+        metric: metric.to_string(),
+        current,
+        baseline,
+        absolute_delta: comparable.then_some(current - baseline),
+        percent_delta: (comparable && baseline != 0.0).then(|| (current - baseline) / baseline.abs() * 100.0),
+    }
+}
+
+/// Deltas between two periods. Counts and sums of the baseline are divided by `baseline_periods`
+/// so "this week vs the 4-week average" compares like with like. Ratios are taken over the whole baseline.
+fn compare_stats(current: &DashboardStats, baseline: &DashboardStats, baseline_periods: u32) -> Vec<MetricDelta> {
+    let n = baseline_periods.max(1) as f64;
+    vec![
+        delta("total_trades", current.total_trades as f64, baseline.total_trades as f64 / n),
+        delta("wins", current.wins as f64, baseline.wins as f64 / n),
+        delta("losses", current.losses as f64, baseline.losses as f64 / n),
+        delta("breakevens", current.breakevens as f64, baseline.breakevens as f64 / n),
+        delta("win_rate", current.win_rate, baseline.win_rate),
+        delta("total_pnl", current.total_pnl, baseline.total_pnl / n),
+        delta("total_fees", current.total_fees, baseline.total_fees / n),
+        delta("total_funding", current.total_funding, baseline.total_funding / n),
+        delta("net_pnl", current.net_pnl, baseline.net_pnl / n),
+        delta("gross_profit", current.gross_profit, baseline.gross_profit / n),
+        delta("gross_loss", current.gross_loss, baseline.gross_loss / n),
+        delta("profit_factor", current.profit_factor, baseline.profit_factor),
+        delta("avg_effective_rr", current.avg_effective_rr, baseline.avg_effective_rr),
+        delta("best_trade", current.best_trade, baseline.best_trade),
+        delta("worst_trade", current.worst_trade, baseline.worst_trade),
+    ]
+}
+
+/// Dashboard stats for two close_date ranges (inclusive timestamps) with absolute and percent deltas.
+/// baseline_periods > 1 averages the baseline, e.g. the 4 weeks before this week with baseline_periods = 4.
+#[tauri::command]
+pub async fn compare_periods(
+    db: State<'_, Database>,
+    current_start: i64,
+    current_end: i64,
+    baseline_start: i64,
+    baseline_end: i64,
+    baseline_periods: Option<u32>,
+    tag_ids: Option<Vec<String>>,
+) -> Result<PeriodComparison, String> {
+    if current_end < current_start || baseline_end < baseline_start {
+        return Err("Each period must end after it starts".to_string());
+    }
+    let baseline_periods = baseline_periods.unwrap_or(1);
+    if baseline_periods == 0 {
+        return Err("baseline_periods must be at least 1".to_string());
+    }
+
+    let conn = db.conn.lock().map_err(|e| e.to_string())?;
+    let tz = load_timezone(&conn);
+    let tags = TagFilter::new(tag_ids);
+
+    let current = load_window_stats(&conn, DateWindow { start: Some(current_start), end: Some(current_end) }, &tags, &tz)?;
+    let baseline = load_window_stats(&conn, DateWindow { start: Some(baseline_start), end: Some(baseline_end) }, &tags, &tz)?;
+    let deltas = compare_stats(&current, &baseline, baseline_periods);
+
+    Ok(PeriodComparison {
+        current_start,
+        current_end,
+        baseline_start,
+        baseline_end,
+        baseline_periods,
+        current,
+        baseline,
+        deltas,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::commands::daily_stats::TradeAggregate;
+
+    fn stats(trades: &[(&str, f64)]) -> DashboardStats {
+        let mut aggregate = TradeAggregate::default();
+        for (status, pnl) in trades {
+            aggregate.add(status, Some(*pnl), None, None, None);
+        }
+        aggregate.to_dashboard_stats()
+    }
+
+    fn find<'a>(deltas: &'a [MetricDelta], metric: &str) -> &'a MetricDelta {
+        deltas.iter().find(|d| d.metric == metric).unwrap()
+    }
+
+    #[test]
+    fn test_deltas_against_single_period() {
+        let current = stats(&[("WIN", 300.0), ("LOSS", -100.0)]);
+        let baseline = stats(&[("WIN", 100.0), ("LOSS", -100.0), ("LOSS", -100.0)]);
+        let deltas = compare_stats(&current, &baseline, 1);
+
+        let pnl = find(&deltas, "total_pnl");
+        assert_eq!(pnl.absolute_delta, Some(300.0));
+        assert_eq!(pnl.percent_delta, Some(300.0)); // from -100 to +200
+
+        let win_rate = find(&deltas, "win_rate");
+        assert!((win_rate.absolute_delta.unwrap() - (50.0 - 100.0 / 3.0)).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_baseline_average_scales_sums_not_ratios() {
+        let current = stats(&[("WIN", 100.0)]);
+        let baseline = stats(&[("WIN", 100.0), ("WIN", 100.0), ("LOSS", -100.0), ("LOSS", -100.0)]);
+        let deltas = compare_stats(&current, &baseline, 4);
+
+        assert_eq!(find(&deltas, "total_trades").baseline, 1.0);
+        assert_eq!(find(&deltas, "total_trades").percent_delta, Some(0.0));
+        assert_eq!(find(&deltas, "win_rate").baseline, 50.0);
+        assert_eq!(find(&deltas, "gross_profit").baseline, 50.0);
+
+        // No losses this period: infinite profit factor is not comparable
+        let profit_factor = find(&deltas, "profit_factor");
+        assert!(profit_factor.absolute_delta.is_none());
+
+        let empty = compare_stats(&current, &stats(&[]), 1);
+        assert!(find(&empty, "total_pnl").percent_delta.is_none());
+    }
+}
@@ -0,0 +1,546 @@
+use tauri::State;
+use crate::commands::settings::load_timezone;
+use crate::commands::stats::{local_date, local_midnight, DashboardStats, DateWindow};
+use crate::db::Database;
+use chrono::{NaiveDate, Utc};
+use chrono_tz::Tz;
+use rusqlite::OptionalExtension;
+use std::collections::BTreeMap;
+
+/// Mergeable running totals behind DashboardStats. One row of daily_stats is one of these
+/// for the trades closed on that local day.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub(crate) struct TradeAggregate {
+    pub trade_count: i32,
+    pub wins: i32,
+    pub losses: i32,
+    pub breakevens: i32,
+    pub open_trades: i32,
+    pub total_pnl: f64,
+    pub gross_profit: f64,
+    pub gross_loss: f64,
+    pub best_trade: Option<f64>,
+    pub worst_trade: Option<f64>,
+    pub total_fees: f64,
+    pub total_funding: f64,
+    pub rr_sum: f64,
+    pub rr_count: i32,
+    pub closed_count: i32, // WIN/LOSS/BE trades with a PnL, the equity curve input
+    pub closed_pnl: f64,
+}
+
+impl TradeAggregate {
+    pub(crate) fn add(
+        &mut self,
+        status: &str,
+        total_pnl: Option<f64>,
+        fees: Option<f64>,
+        funding: Option<f64>,
+        effective_weighted_rr: Option<f64>,
+    ) {
+        self.trade_count += 1;
+        match status {
+            "WIN" => self.wins += 1,
+            "LOSS" => self.losses += 1,
+            "BE" => self.breakevens += 1,
+            "OPEN" => self.open_trades += 1,
+            _ => {}
+        }
+        if let Some(pnl) = total_pnl {
+            self.total_pnl += pnl;
+            if pnl > 0.0 {
+                self.gross_profit += pnl;
+            } else if pnl < 0.0 {
+                self.gross_loss += -pnl;
+            }
+            self.best_trade = Some(self.best_trade.map_or(pnl, |b| b.max(pnl)));
+            self.worst_trade = Some(self.worst_trade.map_or(pnl, |w| w.min(pnl)));
+            if matches!(status, "WIN" | "LOSS" | "BE") {
+                self.closed_count += 1;
+                self.closed_pnl += pnl;
+            }
+        }
+        self.total_fees += fees.unwrap_or(0.0);
+        self.total_funding += funding.unwrap_or(0.0);
+        if let Some(rr) = effective_weighted_rr {
+            self.rr_sum += rr;
+            self.rr_count += 1;
+        }
+    }
+
+    pub(crate) fn merge(&mut self, other: &TradeAggregate) {
+        self.trade_count += other.trade_count;
+        self.wins += other.wins;
+        self.losses += other.losses;
+        self.breakevens += other.breakevens;
+        self.open_trades += other.open_trades;
+        self.total_pnl += other.total_pnl;
+        self.gross_profit += other.gross_profit;
+        self.gross_loss += other.gross_loss;
+        self.best_trade = match (self.best_trade, other.best_trade) {
+            (Some(a), Some(b)) => Some(a.max(b)),
+            (a, b) => a.or(b),
+        };
+        self.worst_trade = match (self.worst_trade, other.worst_trade) {
+            (Some(a), Some(b)) => Some(a.min(b)),
+            (a, b) => a.or(b),
+        };
+        self.total_fees += other.total_fees;
+        self.total_funding += other.total_funding;
+        self.rr_sum += other.rr_sum;
+        self.rr_count += other.rr_count;
+        self.closed_count += other.closed_count;
+        self.closed_pnl += other.closed_pnl;
+    }
+
+    pub(crate) fn to_dashboard_stats(&self) -> DashboardStats {
+        let closed_trades = self.wins + self.losses;
+        let win_rate = if closed_trades > 0 {
+            (self.wins as f64 / closed_trades as f64) * 100.0
+        } else {
+            0.0
+        };
+        let profit_factor = if self.gross_loss > 0.0 {
+            self.gross_profit / self.gross_loss
+        } else if self.gross_profit > 0.0 {
+            f64::INFINITY
+        } else {
+            0.0
+        };
+        let avg_effective_rr = if self.rr_count > 0 {
+            self.rr_sum / self.rr_count as f64
+        } else {
+            0.0
+        };
+
+        DashboardStats {
+            total_trades: self.trade_count,
+            wins: self.wins,
+            losses: self.losses,
+            breakevens: self.breakevens,
+            open_trades: self.open_trades,
+            win_rate,
+            total_pnl: self.total_pnl,
+            total_fees: self.total_fees,
+            total_funding: self.total_funding,
+            net_pnl: self.total_pnl - self.total_fees + self.total_funding,
+            gross_profit: self.gross_profit,
+            gross_loss: self.gross_loss,
+            profit_factor,
+            avg_effective_rr,
+            best_trade: self.best_trade.unwrap_or(0.0),
+            worst_trade: self.worst_trade.unwrap_or(0.0),
+        }
+    }
+}
+
+/// [start, end) timestamp range
+type TimeRange = (i64, i64);
+
+/// Start and (exclusive) end timestamps of a local day in `tz`
+fn day_bounds(date: NaiveDate, tz: &Tz) -> TimeRange {
+    let next = date.succ_opt().unwrap_or(date);
+    (local_midnight(date, tz), local_midnight(next, tz))
+}
+
+/// Split a close_date window into whole local days, answered from daily_stats, and the
+/// partial-day edges that must be read from trades
+fn split_window(window: DateWindow, tz: &Tz) -> Result<(Option<TimeRange>, Vec<TimeRange>), String> {
+    let low = window.start.unwrap_or(i64::MIN);
+    let high = window.end.map(|end| end.saturating_add(1)).unwrap_or(i64::MAX);
+
+    let covered_start = match window.start {
+        None => i64::MIN,
+        Some(start) => {
+            let date = local_date(start, tz)?;
+            let (day_start, day_end) = day_bounds(date, tz);
+            if day_start >= start { day_start } else { day_end }
+        }
+    };
+    let covered_end = match window.end {
+        None => i64::MAX,
+        Some(_) => {
+            let date = local_date(high, tz)?;
+            let day_start = local_midnight(date, tz);
+            if day_start <= high {
+                day_start
+            } else {
+                local_midnight(date.pred_opt().unwrap_or(date), tz)
+            }
+        }
+    };
+
+    if covered_start >= covered_end {
+        return Ok((None, vec![(low, high)]));
+    }
+
+    let edges = [(low, covered_start), (covered_end, high)]
+        .into_iter()
+        .filter(|(start, end)| start < end)
+        .collect();
+    Ok((Some((covered_start, covered_end)), edges))
+}
+
+/// Aggregate trades closed in [start, end) per local day, straight from the trades table
+fn aggregate_trades_by_day(
+    conn: &rusqlite::Connection,
+    tz: &Tz,
+    start: i64,
+    end: i64,
+) -> Result<BTreeMap<NaiveDate, TradeAggregate>, String> {
+    let mut stmt = conn.prepare(
+        "SELECT close_date, status, total_pnl, fees, funding, effective_weighted_rr
+         FROM trades
+         WHERE deleted_at IS NULL AND close_date >= ? AND close_date < ?"
+    ).map_err(|e| e.to_string())?;
+
+    let rows = stmt.query_map([start, end], |row| {
+        Ok((
+            row.get::<_, i64>(0)?,
+            row.get::<_, String>(1)?,
+            row.get::<_, Option<f64>>(2)?,
+            row.get::<_, Option<f64>>(3)?,
+            row.get::<_, Option<f64>>(4)?,
+            row.get::<_, Option<f64>>(5)?,
+        ))
+    }).map_err(|e| e.to_string())?;
+
+    let mut days: BTreeMap<NaiveDate, TradeAggregate> = BTreeMap::new();
+    for row in rows {
+        let (close_date, status, total_pnl, fees, funding, rr) = row.map_err(|e| e.to_string())?;
+        days.entry(local_date(close_date, tz)?)
+            .or_default()
+            .add(&status, total_pnl, fees, funding, rr);
+    }
+
+    Ok(days)
+}
+
+fn map_row_to_aggregate(row: &rusqlite::Row) -> rusqlite::Result<TradeAggregate> {
+    Ok(TradeAggregate {
+        trade_count: row.get("trade_count")?,
+        wins: row.get("wins")?,
+        losses: row.get("losses")?,
+        breakevens: row.get("breakevens")?,
+        open_trades: row.get("open_trades")?,
+        total_pnl: row.get("total_pnl")?,
+        gross_profit: row.get("gross_profit")?,
+        gross_loss: row.get("gross_loss")?,
+        best_trade: row.get("best_trade")?,
+        worst_trade: row.get("worst_trade")?,
+        total_fees: row.get("total_fees")?,
+        total_funding: row.get("total_funding")?,
+        rr_sum: row.get("rr_sum")?,
+        rr_count: row.get("rr_count")?,
+        closed_count: row.get("closed_count")?,
+        closed_pnl: row.get("closed_pnl")?,
+    })
+}
+
+/// Store (or remove, when empty) the daily_stats row for one local day
+fn write_day(
+    conn: &rusqlite::Connection,
+    date: NaiveDate,
+    tz: &Tz,
+    aggregate: Option<&TradeAggregate>,
+) -> Result<(), String> {
+    let day = date.format("%Y-%m-%d").to_string();
+    let Some(agg) = aggregate.filter(|agg| agg.trade_count > 0) else {
+        conn.execute("DELETE FROM daily_stats WHERE day = ?", [&day])
+            .map_err(|e| e.to_string())?;
+        return Ok(());
+    };
+
+    let (day_start, day_end) = day_bounds(date, tz);
+    conn.execute(
+        "REPLACE INTO daily_stats (
+            day, day_start, day_end, trade_count, wins, losses, breakevens, open_trades,
+            total_pnl, gross_profit, gross_loss, best_trade, worst_trade,
+            total_fees, total_funding, rr_sum, rr_count, closed_count, closed_pnl
+        ) VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?)",
+        rusqlite::params![
+            day, day_start, day_end, agg.trade_count, agg.wins, agg.losses, agg.breakevens, agg.open_trades,
+            agg.total_pnl, agg.gross_profit, agg.gross_loss, agg.best_trade, agg.worst_trade,
+            agg.total_fees, agg.total_funding, agg.rr_sum, agg.rr_count, agg.closed_count, agg.closed_pnl
+        ],
+    ).map_err(|e| e.to_string())?;
+
+    Ok(())
+}
+
+/// Whether daily_stats is up to date and bucketed in `tz`
+fn is_current(conn: &rusqlite::Connection, tz: &Tz) -> Result<bool, String> {
+    let timezone: Option<String> = conn
+        .query_row("SELECT timezone FROM daily_stats_state WHERE id = 1", [], |row| row.get(0))
+        .optional()
+        .map_err(|e| e.to_string())?
+        .flatten();
+    Ok(timezone.as_deref() == Some(tz.name()))
+}
+
+/// Recompute every daily_stats row from trades. Returns the number of days stored.
+fn rebuild(conn: &rusqlite::Connection, tz: &Tz) -> Result<usize, String> {
+    let tx = conn.unchecked_transaction().map_err(|e| e.to_string())?;
+
+    tx.execute("DELETE FROM daily_stats", []).map_err(|e| e.to_string())?;
+    let days = aggregate_trades_by_day(&tx, tz, i64::MIN, i64::MAX)?;
+    for (date, aggregate) in &days {
+        write_day(&tx, *date, tz, Some(aggregate))?;
+    }
+    tx.execute(
+        "REPLACE INTO daily_stats_state (id, timezone, rebuilt_at) VALUES (1, ?, ?)",
+        rusqlite::params![tz.name(), Utc::now().timestamp()],
+    ).map_err(|e| e.to_string())?;
+
+    tx.commit().map_err(|e| e.to_string())?;
+    Ok(days.len())
+}
+
+/// Rebuild daily_stats if it is stale or was bucketed in another timezone
+fn ensure_daily_stats(conn: &rusqlite::Connection, tz: &Tz) -> Result<(), String> {
+    if !is_current(conn, tz)? {
+        rebuild(conn, tz)?;
+    }
+    Ok(())
+}
+
+/// Recompute the days containing `close_dates` after trades closed on them were written.
+/// Stale aggregates are left alone, the next read rebuilds them.
+pub(crate) fn refresh_daily_stats(conn: &rusqlite::Connection, close_dates: &[i64]) -> Result<(), String> {
+    let tz = load_timezone(conn);
+    if !is_current(conn, &tz)? {
+        return Ok(());
+    }
+
+    let mut dates = close_dates
+        .iter()
+        .map(|ts| local_date(*ts, &tz))
+        .collect::<Result<Vec<_>, _>>()?;
+    dates.sort_unstable();
+    dates.dedup();
+
+    for date in dates {
+        let (start, end) = day_bounds(date, &tz);
+        let days = aggregate_trades_by_day(conn, &tz, start, end)?;
+        write_day(conn, date, &tz, days.get(&date))?;
+    }
+
+    Ok(())
+}
+
+/// Current close_date of a trade, None if it has none or no longer exists
+pub(crate) fn trade_close_date(conn: &rusqlite::Connection, trade_id: &str) -> Result<Option<i64>, String> {
+    conn.query_row("SELECT close_date FROM trades WHERE id = ?", [trade_id], |row| row.get(0))
+        .optional()
+        .map(|date| date.flatten())
+        .map_err(|e| e.to_string())
+}
+
+/// Refresh the days affected by a single-trade write, given its close_date before the write
+pub(crate) fn refresh_trade_days(
+    conn: &rusqlite::Connection,
+    trade_id: &str,
+    previous_close_date: Option<i64>,
+) -> Result<(), String> {
+    let close_dates: Vec<i64> = [previous_close_date, trade_close_date(conn, trade_id)?]
+        .into_iter()
+        .flatten()
+        .collect();
+    refresh_daily_stats(conn, &close_dates)
+}
+
+/// Mark daily_stats stale after bulk writes (imports, bulk deletes), the next read rebuilds it
+pub(crate) fn invalidate_daily_stats(conn: &rusqlite::Connection) -> Result<(), String> {
+    conn.execute("UPDATE daily_stats_state SET timezone = NULL WHERE id = 1", [])
+        .map_err(|e| e.to_string())?;
+    Ok(())
+}
+
+/// Dashboard aggregate over a close_date window: whole days from daily_stats, partial days from trades.
+/// Without any window, trades that have no close_date are included too (matching the unfiltered dashboard).
+pub(crate) fn load_window_aggregate(
+    conn: &rusqlite::Connection,
+    window: DateWindow,
+    tz: &Tz,
+) -> Result<TradeAggregate, String> {
+    ensure_daily_stats(conn, tz)?;
+
+    let mut total = TradeAggregate::default();
+    let (covered, edges) = split_window(window, tz)?;
+
+    if let Some((start, end)) = covered {
+        let mut stmt = conn
+            .prepare("SELECT * FROM daily_stats WHERE day_start >= ? AND day_end <= ?")
+            .map_err(|e| e.to_string())?;
+        let rows = stmt.query_map([start, end], map_row_to_aggregate).map_err(|e| e.to_string())?;
+        for row in rows {
+            total.merge(&row.map_err(|e| e.to_string())?);
+        }
+    }
+
+    for (start, end) in edges {
+        for day in aggregate_trades_by_day(conn, tz, start, end)?.values() {
+            total.merge(day);
+        }
+    }
+
+    if window.start.is_none() && window.end.is_none() {
+        let mut stmt = conn.prepare(
+            "SELECT status, total_pnl, fees, funding, effective_weighted_rr
+             FROM trades WHERE deleted_at IS NULL AND close_date IS NULL"
+        ).map_err(|e| e.to_string())?;
+        let rows = stmt.query_map([], |row| {
+            Ok((row.get::<_, String>(0)?, row.get(1)?, row.get(2)?, row.get(3)?, row.get(4)?))
+        }).map_err(|e| e.to_string())?;
+        for row in rows {
+            let (status, total_pnl, fees, funding, rr) = row.map_err(|e| e.to_string())?;
+            total.add(&status, total_pnl, fees, funding, rr);
+        }
+    }
+
+    Ok(total)
+}
+
+/// Realized PnL and closed trade count per local day within a close_date window
+pub(crate) fn load_daily_closed_pnl(
+    conn: &rusqlite::Connection,
+    window: DateWindow,
+    tz: &Tz,
+) -> Result<BTreeMap<NaiveDate, (f64, i32)>, String> {
+    ensure_daily_stats(conn, tz)?;
+
+    let mut days: BTreeMap<NaiveDate, (f64, i32)> = BTreeMap::new();
+    let (covered, edges) = split_window(window, tz)?;
+
+    if let Some((start, end)) = covered {
+        let mut stmt = conn.prepare(
+            "SELECT day, closed_pnl, closed_count FROM daily_stats
+             WHERE day_start >= ? AND day_end <= ? AND closed_count > 0"
+        ).map_err(|e| e.to_string())?;
+        let rows = stmt.query_map([start, end], |row| {
+            Ok((row.get::<_, String>(0)?, row.get::<_, f64>(1)?, row.get::<_, i32>(2)?))
+        }).map_err(|e| e.to_string())?;
+        for row in rows {
+            let (day, pnl, count) = row.map_err(|e| e.to_string())?;
+            let date = NaiveDate::parse_from_str(&day, "%Y-%m-%d").map_err(|e| e.to_string())?;
+            let entry = days.entry(date).or_insert((0.0, 0));
+            entry.0 += pnl;
+            entry.1 += count;
+        }
+    }
+
+    for (start, end) in edges {
+        for (date, aggregate) in aggregate_trades_by_day(conn, tz, start, end)? {
+            if aggregate.closed_count > 0 {
+                let entry = days.entry(date).or_insert((0.0, 0));
+                entry.0 += aggregate.closed_pnl;
+                entry.1 += aggregate.closed_count;
+            }
+        }
+    }
+
+    Ok(days)
+}
+
+/// Recompute all daily aggregates from the trades table. Returns the number of days stored.
+#[tauri::command]
+pub async fn rebuild_daily_stats(db: State<'_, Database>) -> Result<usize, String> {
+    let conn = db.conn.lock().map_err(|e| e.to_string())?;
+    let tz = load_timezone(&conn);
+    rebuild(&conn, &tz)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::db::{insert_test_trade, test_connection};
+    use rusqlite::Connection;
+
+    fn insert(conn: &Connection, id: &str, close_date: Option<i64>, status: &str, pnl: f64) {
+        insert_test_trade(conn, id, &[
+            ("close_date", &close_date),
+            ("status", &status),
+            ("total_pnl", &pnl),
+            ("fees", &1.0),
+            ("effective_weighted_rr", &2.0),
+        ]);
+    }
+
+    const DAY: i64 = 86400;
+    const JAN_1: i64 = 1704067200; // 2024-01-01 00:00 UTC
+
+    #[test]
+    fn test_split_window_into_whole_days_and_edges() {
+        let tz = Tz::UTC;
+        let window = DateWindow { start: Some(JAN_1 + 3600), end: Some(JAN_1 + 3 * DAY + 60) };
+        let (covered, edges) = split_window(window, &tz).unwrap();
+
+        assert_eq!(covered, Some((JAN_1 + DAY, JAN_1 + 3 * DAY)));
+        assert_eq!(edges, vec![(JAN_1 + 3600, JAN_1 + DAY), (JAN_1 + 3 * DAY, JAN_1 + 3 * DAY + 61)]);
+
+        // Window inside a single day has no whole days
+        let window = DateWindow { start: Some(JAN_1 + 60), end: Some(JAN_1 + 120) };
+        assert_eq!(split_window(window, &tz).unwrap(), (None, vec![(JAN_1 + 60, JAN_1 + 121)]));
+
+        // Unbounded window is entirely covered
+        let (covered, edges) = split_window(DateWindow::default(), &tz).unwrap();
+        assert_eq!(covered, Some((i64::MIN, i64::MAX)));
+        assert!(edges.is_empty());
+    }
+
+    #[test]
+    fn test_window_aggregate_matches_raw_trades() {
+        let conn = test_connection();
+        insert(&conn, "a", Some(JAN_1 + 600), "WIN", 300.0);
+        insert(&conn, "b", Some(JAN_1 + DAY + 600), "LOSS", -100.0);
+        insert(&conn, "c", Some(JAN_1 + 2 * DAY + 600), "BE", 0.0);
+        insert(&conn, "d", None, "OPEN", 0.0);
+
+        let all = load_window_aggregate(&conn, DateWindow::default(), &Tz::UTC).unwrap();
+        assert_eq!(all.trade_count, 4);
+        assert_eq!(all.open_trades, 1);
+        assert_eq!(all.closed_count, 3);
+
+        let window = DateWindow { start: Some(JAN_1 + 300), end: Some(JAN_1 + DAY + 900) };
+        let partial = load_window_aggregate(&conn, window, &Tz::UTC).unwrap();
+        let mut expected = TradeAggregate::default();
+        expected.add("WIN", Some(300.0), Some(1.0), None, Some(2.0));
+        expected.add("LOSS", Some(-100.0), Some(1.0), None, Some(2.0));
+        assert_eq!(partial, expected);
+
+        let stats = partial.to_dashboard_stats();
+        assert!((stats.profit_factor - 3.0).abs() < 1e-9);
+        assert!((stats.net_pnl - 198.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_refresh_and_invalidate_keep_aggregates_in_sync() {
+        let conn = test_connection();
+        insert(&conn, "a", Some(JAN_1 + 600), "WIN", 300.0);
+        let before = load_window_aggregate(&conn, DateWindow::default(), &Tz::UTC).unwrap();
+        assert_eq!(before.wins, 1);
+
+        // Single-trade write: move the close date and refresh both days
+        let previous = trade_close_date(&conn, "a").unwrap();
+        conn.execute("UPDATE trades SET close_date = ?, status = 'LOSS', total_pnl = -50 WHERE id = 'a'", [JAN_1 + DAY]).unwrap();
+        refresh_trade_days(&conn, "a", previous).unwrap();
+
+        let days = load_daily_closed_pnl(&conn, DateWindow::default(), &Tz::UTC).unwrap();
+        assert_eq!(days.len(), 1);
+        assert_eq!(days.values().next(), Some(&(-50.0, 1)));
+
+        // Bulk write: invalidated aggregates are rebuilt on the next read
+        insert(&conn, "b", Some(JAN_1 + 600), "WIN", 10.0);
+        invalidate_daily_stats(&conn).unwrap();
+        let after = load_window_aggregate(&conn, DateWindow::default(), &Tz::UTC).unwrap();
+        assert_eq!((after.wins, after.losses), (1, 1));
+
+        // A timezone change also triggers a rebuild with the new day boundaries
+        conn.execute("UPDATE settings SET timezone = 'Asia/Tokyo'", []).unwrap();
+        let tokyo: Tz = "Asia/Tokyo".parse().unwrap();
+        let days = load_daily_closed_pnl(&conn, DateWindow::default(), &tokyo).unwrap();
+        assert_eq!(days.len(), 2);
+        assert!(is_current(&conn, &tokyo).unwrap());
+        assert!(!is_current(&conn, &Tz::UTC).unwrap());
+    }
+}
@@ -1,4 +1,6 @@
 use tauri::State;
+use crate::commands::daily_stats::invalidate_daily_stats;
+use crate::commands::revisions::{record_revision, trade_snapshot};
 use crate::db::Database;
 
 #[tauri::command]
@@ -43,9 +45,27 @@ pub async fn restore_all_trades(
 ) -> Result<i64, String> {
     let conn = db.conn.lock().map_err(|e| e.to_string())?;
 
+    let mut stmt = conn
+        .prepare("SELECT id FROM trades WHERE deleted_at IS NOT NULL")
+        .map_err(|e| e.to_string())?;
+    let ids = stmt
+        .query_map([], |row| row.get::<_, String>(0))
+        .map_err(|e| e.to_string())?
+        .collect::<Result<Vec<_>, _>>()
+        .map_err(|e| e.to_string())?;
+    let mut snapshots = Vec::with_capacity(ids.len());
+    for id in ids {
+        let before = trade_snapshot(&conn, &id)?;
+        snapshots.push((id, before));
+    }
+
     let count = conn
         .execute("UPDATE trades SET deleted_at = NULL WHERE deleted_at IS NOT NULL", [])
         .map_err(|e| e.to_string())?;
+    invalidate_daily_stats(&conn)?;
+    for (id, before) in snapshots {
+        record_revision(&conn, &id, "RESTORE", "USER", before)?;
+    }
 
     Ok(count as i64)
 }
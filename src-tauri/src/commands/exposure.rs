@@ -0,0 +1,473 @@
+use tauri::State;
+use crate::commands::daily_stats::TradeAggregate;
+use crate::commands::settings::load_timezone;
+use crate::commands::stats::{DashboardStats, DateWindow, TagFilter};
+use crate::db::Database;
+use chrono::Utc;
+use rusqlite::types::Value;
+use serde::{Deserialize, Serialize};
+use std::cmp::Reverse;
+use std::collections::{BTreeMap, BinaryHeap};
+
+/// Trades opened with this many or more positions already open share one group
+const MAX_CONCURRENCY_GROUP: i32 = 3;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ExposurePoint {
+    pub timestamp: i64, // state right after all opens/closes at this instant
+    pub open_positions: i32,
+    pub open_risk_amount: f64,  // sum of execution_one_r (or one_r) of open positions
+    pub open_risk_percent: f64, // sum of execution_r_percent (or r_percent), % of equity
+    pub open_risk_r: f64,       // open_risk_percent in units of settings.current_r_percent
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ConcurrencyTime {
+    pub open_positions: i32,
+    pub seconds: i64,
+    pub percent: f64, // of the time with at least one open position
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ConcurrencyGroup {
+    pub label: String,
+    pub sort_order: i64, // positions already open at entry (MAX_CONCURRENCY_GROUP means "or more")
+    pub stats: DashboardStats,
+    pub avg_r: Option<f64>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ExposureAnalysis {
+    pub current_r_percent: f64,
+    pub max_concurrent_positions: i32,
+    pub max_concurrent_at: Option<i64>,
+    pub peak_open_risk_r: f64,
+    pub peak_open_risk_percent: f64,
+    pub peak_open_risk_at: Option<i64>,
+    pub avg_concurrent_positions: f64, // time-weighted, while at least one position is open
+    pub overlap_time_percent: f64,     // % of exposed time with two or more positions open
+    pub time_at_concurrency: Vec<ConcurrencyTime>,
+    pub opened_alone: ConcurrencyGroup,
+    pub opened_while_open: ConcurrencyGroup, // trades opened while at least one other was open
+    pub by_concurrency_at_entry: Vec<ConcurrencyGroup>,
+    pub series: Vec<ExposurePoint>,
+}
+
+/// Trade columns needed for exposure analytics
+#[derive(Debug, Clone)]
+struct ExposureTrade {
+    trade_date: i64,
+    close_date: Option<i64>,
+    status: String,
+    risk_fraction: f64, // execution_r_percent, falling back to r_percent
+    risk_amount: f64,   // execution_one_r, falling back to one_r
+    total_pnl: Option<f64>,
+    fees: Option<f64>,
+    funding: Option<f64>,
+    effective_weighted_rr: Option<f64>,
+    pnl_in_r: Option<f64>,
+}
+
+impl ExposureTrade {
+    fn is_closed(&self) -> bool {
+        matches!(self.status.as_str(), "WIN" | "LOSS" | "BE")
+    }
+
+    /// [trade_date, close) interval, open trades run until `now`. None for closed trades without a close_date.
+    fn interval(&self, now: i64) -> Option<(i64, i64)> {
+        let end = match self.close_date {
+            Some(close_date) => close_date,
+            None if self.status == "OPEN" => now.max(self.trade_date),
+            None => return None,
+        };
+        (end >= self.trade_date).then_some((self.trade_date, end))
+    }
+}
+
+/// Sweep open/close events into an exposure series. Closes sort before opens at the same
+/// instant, so a position opened as another closes does not count as overlapping.
+fn exposure_series(trades: &[ExposureTrade], current_r_percent: f64, now: i64) -> Vec<ExposurePoint> {
+    let mut events: Vec<(i64, i32, &ExposureTrade)> = Vec::new();
+    for trade in trades {
+        if let Some((start, end)) = trade.interval(now) {
+            events.push((start, 1, trade));
+            events.push((end, -1, trade));
+        }
+    }
+    events.sort_by_key(|(timestamp, delta, _)| (*timestamp, *delta));
+
+    let mut points: Vec<ExposurePoint> = Vec::new();
+    let (mut open_positions, mut risk_amount, mut risk_fraction) = (0, 0.0, 0.0);
+    for (i, (timestamp, delta, trade)) in events.iter().enumerate() {
+        open_positions += delta;
+        risk_amount += *delta as f64 * trade.risk_amount;
+        risk_fraction += *delta as f64 * trade.risk_fraction;
+        if open_positions == 0 {
+            // Running sums return to exactly zero when flat
+            risk_amount = 0.0;
+            risk_fraction = 0.0;
+        }
+
+        if events.get(i + 1).is_some_and(|(next, _, _)| next == timestamp) {
+            continue;
+        }
+        points.push(ExposurePoint {
+            timestamp: *timestamp,
+            open_positions,
+            open_risk_amount: risk_amount,
+            open_risk_percent: risk_fraction * 100.0,
+            open_risk_r: if current_r_percent > 0.0 { risk_fraction / current_r_percent } else { 0.0 },
+        });
+    }
+    points
+}
+
+/// Positions already open when each trade was opened (opened strictly earlier, not yet closed).
+/// Walks trades by entry time, keeping the close times of positions opened before it in a min-heap.
+fn concurrency_at_entry(trades: &[ExposureTrade], now: i64) -> Vec<i32> {
+    let mut intervals: Vec<(i64, i64)> = trades.iter().filter_map(|t| t.interval(now)).collect();
+    intervals.sort_unstable();
+    let mut order: Vec<usize> = (0..trades.len()).collect();
+    order.sort_by_key(|&i| trades[i].trade_date);
+
+    let mut counts = vec![0; trades.len()];
+    let mut open_ends: BinaryHeap<Reverse<i64>> = BinaryHeap::new();
+    let mut next_interval = 0;
+    for i in order {
+        let entry = trades[i].trade_date;
+        while let Some(&(_, end)) = intervals.get(next_interval).filter(|(start, _)| *start < entry) {
+            open_ends.push(Reverse(end));
+            next_interval += 1;
+        }
+        while open_ends.peek().is_some_and(|Reverse(end)| *end <= entry) {
+            open_ends.pop();
+        }
+        counts[i] = open_ends.len() as i32;
+    }
+    counts
+}
+
+fn concurrency_group(label: String, sort_order: i64, members: &[&ExposureTrade]) -> ConcurrencyGroup {
+    let mut aggregate = TradeAggregate::default();
+    for trade in members {
+        aggregate.add(&trade.status, trade.total_pnl, trade.fees, trade.funding, trade.effective_weighted_rr);
+    }
+    let r_values: Vec<f64> = members.iter().filter_map(|t| t.pnl_in_r).collect();
+    ConcurrencyGroup {
+        label,
+        sort_order,
+        stats: aggregate.to_dashboard_stats(),
+        avg_r: (!r_values.is_empty()).then(|| r_values.iter().sum::<f64>() / r_values.len() as f64),
+    }
+}
+
+fn compute_exposure(trades: &[ExposureTrade], current_r_percent: f64, window: DateWindow, now: i64) -> ExposureAnalysis {
+    let points = exposure_series(trades, current_r_percent, now);
+
+    // Clip the series to the window, carrying the state at its start
+    let mut series: Vec<ExposurePoint> = Vec::new();
+    if let Some(start) = window.start
+        && let Some(before) = points.iter().rev().find(|p| p.timestamp < start)
+    {
+        series.push(ExposurePoint { timestamp: start, ..before.clone() });
+    }
+    series.extend(
+        points
+            .iter()
+            .filter(|p| window.start.is_none_or(|s| p.timestamp >= s) && window.end.is_none_or(|e| p.timestamp <= e))
+            .cloned(),
+    );
+
+    let series_end = window.end.unwrap_or(now).min(now);
+    let mut seconds_at: BTreeMap<i32, i64> = BTreeMap::new();
+    for (i, point) in series.iter().enumerate() {
+        let until = series.get(i + 1).map_or(series_end, |next| next.timestamp);
+        if point.open_positions > 0 && until > point.timestamp {
+            *seconds_at.entry(point.open_positions).or_default() += until - point.timestamp;
+        }
+    }
+    let exposed_seconds: i64 = seconds_at.values().sum();
+    let percent_of_exposed = |seconds: i64| {
+        if exposed_seconds > 0 { seconds as f64 / exposed_seconds as f64 * 100.0 } else { 0.0 }
+    };
+
+    let max_concurrent = series.iter().max_by_key(|p| (p.open_positions, std::cmp::Reverse(p.timestamp)));
+    let peak_risk = series
+        .iter()
+        .filter(|p| p.open_positions > 0)
+        .max_by(|a, b| a.open_risk_r.total_cmp(&b.open_risk_r).then_with(|| b.timestamp.cmp(&a.timestamp)));
+
+    // Performance by positions already open at entry, for trades closed inside the window
+    let at_entry = concurrency_at_entry(trades, now);
+    let mut alone = Vec::new();
+    let mut overlapping = Vec::new();
+    let mut by_count: BTreeMap<i32, Vec<&ExposureTrade>> = BTreeMap::new();
+    for (trade, concurrent) in trades.iter().zip(at_entry) {
+        let in_window = trade.close_date.is_some_and(|ts| {
+            window.start.is_none_or(|s| ts >= s) && window.end.is_none_or(|e| ts <= e)
+        });
+        if !trade.is_closed() || !in_window {
+            continue;
+        }
+        if concurrent == 0 { alone.push(trade) } else { overlapping.push(trade) }
+        by_count.entry(concurrent.min(MAX_CONCURRENCY_GROUP)).or_default().push(trade);
+    }
+
+    let count_label = |count: i32| match count {
+        0 => "Alone".to_string(),
+        MAX_CONCURRENCY_GROUP => format!("{}+ open", MAX_CONCURRENCY_GROUP),
+        n => format!("{} open", n),
+    };
+
+    ExposureAnalysis {
+        current_r_percent,
+        max_concurrent_positions: max_concurrent.map_or(0, |p| p.open_positions),
+        max_concurrent_at: max_concurrent.filter(|p| p.open_positions > 0).map(|p| p.timestamp),
+        peak_open_risk_r: peak_risk.map_or(0.0, |p| p.open_risk_r),
+        peak_open_risk_percent: peak_risk.map_or(0.0, |p| p.open_risk_percent),
+        peak_open_risk_at: peak_risk.map(|p| p.timestamp),
+        avg_concurrent_positions: if exposed_seconds > 0 {
+            seconds_at.iter().map(|(n, s)| *n as f64 * *s as f64).sum::<f64>() / exposed_seconds as f64
+        } else {
+            0.0
+        },
+        overlap_time_percent: percent_of_exposed(seconds_at.range(2..).map(|(_, s)| *s).sum()),
+        time_at_concurrency: seconds_at
+            .iter()
+            .map(|(n, s)| ConcurrencyTime { open_positions: *n, seconds: *s, percent: percent_of_exposed(*s) })
+            .collect(),
+        opened_alone: concurrency_group(count_label(0), 0, &alone),
+        opened_while_open: concurrency_group("While others open".to_string(), 1, &overlapping),
+        by_concurrency_at_entry: by_count
+            .into_iter()
+            .map(|(count, members)| concurrency_group(count_label(count), count as i64, &members))
+            .collect(),
+        series,
+    }
+}
+
+/// Trades that can overlap the window: open at some point during it, or while a trade closed in it was open
+fn load_exposure_trades(
+    conn: &rusqlite::Connection,
+    window: DateWindow,
+    tags: &TagFilter,
+) -> Result<Vec<ExposureTrade>, String> {
+    // With a tag filter, only positions of the selected tags or strategies overlap
+    let (tag_filter, tag_params) = tags.filter();
+
+    // Concurrency at entry of trades closed in the window reaches back to the earliest of their entries
+    let mut from = window.start;
+    if let Some(start) = window.start {
+        let (closed_filter, closed_params) = window.filter();
+        let earliest_entry: Option<i64> = conn.query_row(
+            &format!(
+                "SELECT MIN(trade_date) FROM trades
+                 WHERE deleted_at IS NULL AND status IN ('WIN', 'LOSS', 'BE') {} {}",
+                closed_filter, tag_filter
+            ),
+            rusqlite::params_from_iter(
+                closed_params.into_iter().map(Value::from).chain(tag_params.iter().cloned().map(Value::from)),
+            ),
+            |row| row.get(0),
+        ).map_err(|e| e.to_string())?;
+        from = Some(earliest_entry.map_or(start, |entry| entry.min(start)));
+    }
+
+    let mut bounds = String::new();
+    let mut params: Vec<Value> = Vec::new();
+    if let Some(from) = from {
+        bounds.push_str("AND (close_date IS NULL OR close_date >= ?) ");
+        params.push(from.into());
+    }
+    if let Some(end) = window.end {
+        bounds.push_str("AND trade_date <= ?");
+        params.push(end.into());
+    }
+    params.extend(tag_params.into_iter().map(Value::from));
+
+    let mut stmt = conn.prepare(&format!(
+        "SELECT trade_date, close_date, status, COALESCE(execution_r_percent, r_percent),
+                COALESCE(execution_one_r, one_r), total_pnl, fees, funding, effective_weighted_rr, pnl_in_r
+         FROM trades
+         WHERE deleted_at IS NULL {} {}
+         ORDER BY trade_date ASC",
+        bounds, tag_filter
+    )).map_err(|e| e.to_string())?;
+
+    stmt.query_map(rusqlite::params_from_iter(params.iter()), |row| {
+        Ok(ExposureTrade {
+            trade_date: row.get(0)?,
+            close_date: row.get(1)?,
+            status: row.get(2)?,
+            risk_fraction: row.get(3)?,
+            risk_amount: row.get(4)?,
+            total_pnl: row.get(5)?,
+            fees: row.get(6)?,
+            funding: row.get(7)?,
+            effective_weighted_rr: row.get(8)?,
+            pnl_in_r: row.get(9)?,
+        })
+    })
+    .map_err(|e| e.to_string())?
+    .collect::<Result<Vec<_>, _>>()
+    .map_err(|e| e.to_string())
+}
+
+/// Concurrent open positions and open risk over time from trade_date/close_date intervals
+/// (open trades run until now), and the performance of trades opened while others were open
+#[tauri::command]
+pub async fn get_exposure_analysis(
+    db: State<'_, Database>,
+    date_range: Option<String>,
+    start_date: Option<i64>,
+    end_date: Option<i64>,
+    tag_ids: Option<Vec<String>>,
+) -> Result<ExposureAnalysis, String> {
+    let conn = db.conn.lock().map_err(|e| e.to_string())?;
+
+    let current_r_percent: f64 = conn
+        .query_row("SELECT current_r_percent FROM settings WHERE id = 1", [], |row| row.get(0))
+        .map_err(|e| format!("Failed to load settings: {}", e))?;
+
+    let tz = load_timezone(&conn);
+    let window = DateWindow::resolve(date_range.as_deref(), start_date, end_date, &tz);
+
+    let trades = load_exposure_trades(&conn, window, &TagFilter::new(tag_ids))?;
+
+    Ok(compute_exposure(&trades, current_r_percent, window, Utc::now().timestamp()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::db::{insert_test_trade, test_connection};
+
+    const HOUR: i64 = 3600;
+
+    fn trade(trade_date: i64, close_date: Option<i64>, status: &str, pnl: f64) -> ExposureTrade {
+        ExposureTrade {
+            trade_date,
+            close_date,
+            status: status.to_string(),
+            risk_fraction: 0.01,
+            risk_amount: 100.0,
+            total_pnl: close_date.map(|_| pnl),
+            fees: None,
+            funding: None,
+            effective_weighted_rr: None,
+            pnl_in_r: close_date.map(|_| pnl / 100.0),
+        }
+    }
+
+    #[test]
+    fn test_series_counts_overlaps_and_open_risk() {
+        let trades = vec![
+            trade(0, Some(4 * HOUR), "WIN", 200.0),
+            trade(HOUR, Some(2 * HOUR), "LOSS", -100.0),
+            // Opens exactly when the previous one closes: not concurrent with it
+            trade(2 * HOUR, Some(3 * HOUR), "LOSS", -100.0),
+        ];
+        let series = exposure_series(&trades, 0.01, 10 * HOUR);
+
+        let counts: Vec<(i64, i32)> = series.iter().map(|p| (p.timestamp, p.open_positions)).collect();
+        assert_eq!(counts, [(0, 1), (HOUR, 2), (2 * HOUR, 2), (3 * HOUR, 1), (4 * HOUR, 0)]);
+        assert!((series[1].open_risk_r - 2.0).abs() < 1e-9);
+        assert!((series[1].open_risk_percent - 2.0).abs() < 1e-9);
+        assert_eq!(series[4].open_risk_amount, 0.0);
+    }
+
+    #[test]
+    fn test_exposure_summary_and_performance_when_overlapping() {
+        let trades = vec![
+            trade(0, Some(4 * HOUR), "WIN", 200.0),
+            trade(HOUR, Some(2 * HOUR), "LOSS", -100.0),
+            trade(2 * HOUR, Some(3 * HOUR), "LOSS", -100.0),
+            trade(5 * HOUR, None, "OPEN", 0.0), // open until now
+        ];
+        let analysis = compute_exposure(&trades, 0.02, DateWindow::default(), 6 * HOUR);
+
+        assert_eq!(analysis.max_concurrent_positions, 2);
+        assert_eq!(analysis.max_concurrent_at, Some(HOUR));
+        assert!((analysis.peak_open_risk_r - 1.0).abs() < 1e-9);
+
+        // 3h with one position (0-1h, 3-4h, 5-6h) and 2h with two
+        assert_eq!(analysis.time_at_concurrency[0].seconds, 3 * HOUR);
+        assert_eq!(analysis.time_at_concurrency[1].seconds, 2 * HOUR);
+        assert!((analysis.overlap_time_percent - 40.0).abs() < 1e-9);
+        assert!((analysis.avg_concurrent_positions - 1.4).abs() < 1e-9);
+
+        assert_eq!(analysis.opened_alone.stats.total_trades, 1);
+        assert_eq!(analysis.opened_while_open.stats.total_trades, 2);
+        assert_eq!(analysis.opened_while_open.stats.win_rate, 0.0);
+        assert_eq!(analysis.opened_while_open.avg_r, Some(-1.0));
+        assert_eq!(analysis.by_concurrency_at_entry[1].label, "1 open");
+    }
+
+    #[test]
+    fn test_concurrency_at_entry_matches_pairwise_count() {
+        // Ties, zero-length trades, an open trade and a closed trade without close_date
+        let mut trades: Vec<ExposureTrade> = (0..40)
+            .map(|i| trade((i * 7 % 13) * HOUR, Some((i * 7 % 13 + i % 4) * HOUR), "WIN", 10.0))
+            .collect();
+        trades.push(trade(3 * HOUR, None, "OPEN", 0.0));
+        trades.push(trade(2 * HOUR, None, "LOSS", -10.0));
+        let now = 20 * HOUR;
+
+        let pairwise: Vec<i32> = trades
+            .iter()
+            .map(|t| {
+                trades
+                    .iter()
+                    .filter_map(|other| other.interval(now))
+                    .filter(|(start, end)| *start < t.trade_date && *end > t.trade_date)
+                    .count() as i32
+            })
+            .collect();
+        assert_eq!(concurrency_at_entry(&trades, now), pairwise);
+    }
+
+    #[test]
+    fn test_loaded_trades_are_bounded_by_window() {
+        let conn = test_connection();
+        for (id, trade_date, close_date, status) in [
+            ("before", 0, Some(HOUR), "WIN"),
+            ("carried", 2 * HOUR, Some(12 * HOUR), "WIN"),
+            ("closed_in", 4 * HOUR, Some(11 * HOUR), "LOSS"),
+            ("inside", 11 * HOUR, Some(13 * HOUR), "WIN"),
+            ("open", 12 * HOUR, None, "OPEN"),
+            ("after", 30 * HOUR, Some(31 * HOUR), "WIN"),
+        ] {
+            insert_test_trade(&conn, id, &[
+                ("trade_date", &trade_date),
+                ("close_date", &close_date),
+                ("status", &status),
+            ]);
+        }
+
+        let window = DateWindow { start: Some(10 * HOUR), end: Some(20 * HOUR) };
+        let entries: Vec<i64> = load_exposure_trades(&conn, window, &TagFilter::new(None))
+            .unwrap()
+            .iter()
+            .map(|t| t.trade_date)
+            .collect();
+        assert_eq!(entries, [2 * HOUR, 4 * HOUR, 11 * HOUR, 12 * HOUR]);
+
+        let all = load_exposure_trades(&conn, DateWindow::default(), &TagFilter::new(None)).unwrap();
+        assert_eq!(all.len(), 6);
+    }
+
+    #[test]
+    fn test_window_carries_state_at_start() {
+        let trades = vec![trade(0, Some(10 * HOUR), "WIN", 100.0), trade(5 * HOUR, Some(6 * HOUR), "WIN", 100.0)];
+        let window = DateWindow { start: Some(4 * HOUR), end: Some(8 * HOUR) };
+        let analysis = compute_exposure(&trades, 0.01, window, 20 * HOUR);
+
+        assert_eq!(analysis.series[0].timestamp, 4 * HOUR);
+        assert_eq!(analysis.series[0].open_positions, 1);
+        assert_eq!(analysis.series.len(), 3);
+        // Only the trade closed inside the window is grouped
+        assert_eq!(analysis.by_concurrency_at_entry.len(), 1);
+        assert_eq!(analysis.opened_while_open.stats.total_trades, 1);
+        assert_eq!(analysis.time_at_concurrency.iter().map(|t| t.seconds).sum::<i64>(), 4 * HOUR);
+    }
+}
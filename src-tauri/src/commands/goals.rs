@@ -0,0 +1,443 @@
+use tauri::State;
+use crate::commands::settings::load_timezone;
+use crate::commands::stats::{local_midnight, period_start, TagFilter};
+use crate::db::Database;
+use crate::models::{CreateGoalInput, Goal, UpdateGoalInput};
+use chrono::Utc;
+use chrono_tz::Tz;
+use rusqlite::types::Value;
+use serde::{Deserialize, Serialize};
+
+const GOAL_METRICS: [&str; 5] = ["PNL", "R", "MAX_DRAWDOWN", "MAX_TRADES", "JOURNAL_COMPLETENESS"];
+const GOAL_PERIODS: [&str; 3] = ["WEEK", "MONTH", "CUSTOM"];
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct GoalProgress {
+    pub goal: Goal,
+    pub period_start: i64,
+    pub period_end: i64, // inclusive
+    pub current_value: f64,
+    pub progress_percent: Option<f64>, // current / target, None for a zero target
+    pub time_elapsed_percent: f64,
+    pub expected_value: Option<f64>, // where a cumulative metric should be at an even pace
+    pub pace_percent: Option<f64>,   // current / expected
+    pub projected_value: f64,        // linear extrapolation to period end for cumulative metrics, else current
+    pub period_complete: bool,
+    pub status: String, // ACHIEVED | ON_TRACK | OFF_TRACK | FAILED | BREACHED
+}
+
+/// Trade columns needed to evaluate goals
+#[derive(Debug, Clone)]
+struct GoalTrade {
+    trade_date: i64,
+    close_date: Option<i64>,
+    status: String,
+    net_pnl: Option<f64>,
+    pnl_in_r: Option<f64>,
+    notes: String,
+    effective_pe: Option<f64>,
+    exits: Option<String>,
+}
+
+impl GoalTrade {
+    fn closed_within(&self, start: i64, end: i64) -> bool {
+        matches!(self.status.as_str(), "WIN" | "LOSS" | "BE")
+            && self.close_date.is_some_and(|ts| ts >= start && ts <= end)
+    }
+
+    /// A journal entry is complete with notes, an actual entry and recorded exits
+    fn is_journaled(&self) -> bool {
+        !self.notes.trim().is_empty()
+            && self.effective_pe.is_some()
+            && self.exits.as_deref().is_some_and(|exits| !exits.trim().is_empty() && exits.trim() != "[]")
+    }
+}
+
+fn map_row_to_goal(row: &rusqlite::Row) -> rusqlite::Result<Goal> {
+    Ok(Goal {
+        id: row.get("id")?,
+        name: row.get("name")?,
+        metric: row.get("metric")?,
+        period: row.get("period")?,
+        target: row.get("target")?,
+        start_date: row.get("start_date")?,
+        end_date: row.get("end_date")?,
+        active: row.get::<_, i32>("active")? == 1,
+        created_at: row.get("created_at")?,
+        updated_at: row.get("updated_at")?,
+    })
+}
+
+fn validate_goal(metric: &str, period: &str, target: f64, start_date: Option<i64>, end_date: Option<i64>) -> Result<(), String> {
+    if !GOAL_METRICS.contains(&metric) {
+        return Err(format!("Invalid goal metric: {} (expected one of {})", metric, GOAL_METRICS.join(", ")));
+    }
+    if !GOAL_PERIODS.contains(&period) {
+        return Err(format!("Invalid goal period: {} (expected one of {})", period, GOAL_PERIODS.join(", ")));
+    }
+    if !target.is_finite() || target < 0.0 {
+        return Err("Target must be a non-negative number".to_string());
+    }
+    if metric == "JOURNAL_COMPLETENESS" && target > 100.0 {
+        return Err("Journal completeness target is a percentage between 0 and 100".to_string());
+    }
+    if period == "CUSTOM" {
+        match (start_date, end_date) {
+            (Some(start), Some(end)) if start < end => {}
+            _ => return Err("Custom goals need a start_date before their end_date".to_string()),
+        }
+    }
+    Ok(())
+}
+
+pub(crate) fn load_goals(conn: &rusqlite::Connection) -> Result<Vec<Goal>, String> {
+    let mut stmt = conn
+        .prepare("SELECT * FROM goals ORDER BY created_at ASC")
+        .map_err(|e| e.to_string())?;
+
+    let goals = stmt
+        .query_map([], map_row_to_goal)
+        .map_err(|e| e.to_string())?
+        .collect::<Result<Vec<_>, _>>()
+        .map_err(|e| e.to_string())?;
+
+    Ok(goals)
+}
+
+fn get_goal_by_id(conn: &rusqlite::Connection, id: &str) -> Result<Goal, String> {
+    conn.query_row("SELECT * FROM goals WHERE id = ?", [id], map_row_to_goal)
+        .map_err(|e| e.to_string())
+}
+
+/// Inclusive bounds of the goal's period containing `as_of`. Weeks (Monday start) and months
+/// follow the user's timezone, custom goals use their own dates.
+fn goal_period(goal: &Goal, as_of: i64, tz: &Tz) -> Result<(i64, i64), String> {
+    let (start, next) = match goal.period.as_str() {
+        "CUSTOM" => {
+            return match (goal.start_date, goal.end_date) {
+                (Some(start), Some(end)) => Ok((start, end)),
+                _ => Err(format!("Custom goal {} has no start and end date", goal.id)),
+            };
+        }
+        "WEEK" => {
+            let start = period_start(as_of, tz, "week")?;
+            (start, start + chrono::Duration::days(7))
+        }
+        "MONTH" => {
+            let start = period_start(as_of, tz, "month")?;
+            (start, start.checked_add_months(chrono::Months::new(1)).ok_or("Date out of range")?)
+        }
+        _ => return Err(format!("Invalid goal period: {}", goal.period)),
+    };
+    Ok((local_midnight(start, tz), local_midnight(next, tz) - 1))
+}
+
+/// Value of a goal metric over [start, end]: net PnL and R of trades closed in the period, the
+/// largest peak-to-trough drop of the period's running net PnL, trades opened in the period, or
+/// the % of trades closed in the period with a complete journal entry (100 without trades)
+fn measure_goal(metric: &str, trades: &[GoalTrade], start: i64, end: i64) -> f64 {
+    let mut closed: Vec<&GoalTrade> = trades.iter().filter(|t| t.closed_within(start, end)).collect();
+
+    match metric {
+        "PNL" => closed.iter().filter_map(|t| t.net_pnl).sum(),
+        "R" => closed.iter().filter_map(|t| t.pnl_in_r).sum(),
+        "MAX_DRAWDOWN" => {
+            closed.sort_by_key(|t| t.close_date);
+            let (mut equity, mut peak, mut max_drawdown) = (0.0_f64, 0.0_f64, 0.0_f64);
+            for trade in closed {
+                equity += trade.net_pnl.unwrap_or(0.0);
+                peak = peak.max(equity);
+                max_drawdown = max_drawdown.max(peak - equity);
+            }
+            max_drawdown
+        }
+        "MAX_TRADES" => trades.iter().filter(|t| t.trade_date >= start && t.trade_date <= end).count() as f64,
+        _ => {
+            if closed.is_empty() {
+                100.0
+            } else {
+                closed.iter().filter(|t| t.is_journaled()).count() as f64 / closed.len() as f64 * 100.0
+            }
+        }
+    }
+}
+
+fn compute_goal_progress(goal: Goal, current_value: f64, period_start: i64, period_end: i64, now: i64) -> GoalProgress {
+    let duration = (period_end - period_start + 1) as f64;
+    let elapsed = ((now - period_start) as f64 / duration).clamp(0.0, 1.0);
+    let period_complete = now > period_end;
+
+    let expected_value = goal.is_cumulative().then_some(goal.target * elapsed);
+    let projected_value = if goal.is_cumulative() && elapsed > 0.0 { current_value / elapsed } else { current_value };
+
+    let status = if goal.is_limit() {
+        if current_value > goal.target {
+            "BREACHED"
+        } else if period_complete {
+            "ACHIEVED"
+        } else if projected_value <= goal.target {
+            "ON_TRACK"
+        } else {
+            "OFF_TRACK"
+        }
+    } else if current_value >= goal.target {
+        "ACHIEVED"
+    } else if period_complete {
+        "FAILED"
+    } else if projected_value >= goal.target {
+        "ON_TRACK"
+    } else {
+        "OFF_TRACK"
+    };
+
+    GoalProgress {
+        progress_percent: (goal.target != 0.0).then(|| current_value / goal.target * 100.0),
+        time_elapsed_percent: elapsed * 100.0,
+        pace_percent: expected_value.filter(|e| *e > 0.0).map(|e| current_value / e * 100.0),
+        expected_value,
+        projected_value,
+        period_complete,
+        status: status.to_string(),
+        period_start,
+        period_end,
+        current_value,
+        goal,
+    }
+}
+
+#[tauri::command]
+pub async fn get_goals(db: State<'_, Database>) -> Result<Vec<Goal>, String> {
+    let conn = db.conn.lock().map_err(|e| e.to_string())?;
+    load_goals(&conn)
+}
+
+#[tauri::command]
+pub async fn create_goal(db: State<'_, Database>, goal: CreateGoalInput) -> Result<Goal, String> {
+    validate_goal(&goal.metric, &goal.period, goal.target, goal.start_date, goal.end_date)?;
+
+    let conn = db.conn.lock().map_err(|e| e.to_string())?;
+    let id = format!("GOAL-{}-{}", Utc::now().timestamp_millis(), uuid::Uuid::new_v4());
+    let now = Utc::now().timestamp();
+    let custom = goal.period == "CUSTOM";
+
+    conn.execute(
+        "INSERT INTO goals (id, name, metric, period, target, start_date, end_date, active, created_at, updated_at)
+         VALUES (?, ?, ?, ?, ?, ?, ?, 1, ?, ?)",
+        rusqlite::params![
+            id,
+            goal.name,
+            goal.metric,
+            goal.period,
+            goal.target,
+            goal.start_date.filter(|_| custom),
+            goal.end_date.filter(|_| custom),
+            now,
+            now,
+        ],
+    ).map_err(|e| e.to_string())?;
+
+    get_goal_by_id(&conn, &id)
+}
+
+#[tauri::command]
+pub async fn update_goal(db: State<'_, Database>, id: String, goal: UpdateGoalInput) -> Result<Goal, String> {
+    let conn = db.conn.lock().map_err(|e| e.to_string())?;
+    let existing = get_goal_by_id(&conn, &id)?;
+
+    let metric = goal.metric.unwrap_or(existing.metric);
+    let period = goal.period.unwrap_or(existing.period);
+    let target = goal.target.unwrap_or(existing.target);
+    let custom = period == "CUSTOM";
+    let start_date = goal.start_date.or(existing.start_date).filter(|_| custom);
+    let end_date = goal.end_date.or(existing.end_date).filter(|_| custom);
+    validate_goal(&metric, &period, target, start_date, end_date)?;
+
+    conn.execute(
+        "UPDATE goals SET name = ?, metric = ?, period = ?, target = ?, start_date = ?, end_date = ?, active = ?, updated_at = ?
+         WHERE id = ?",
+        rusqlite::params![
+            goal.name.unwrap_or(existing.name),
+            metric,
+            period,
+            target,
+            start_date,
+            end_date,
+            goal.active.unwrap_or(existing.active) as i32,
+            Utc::now().timestamp(),
+            id,
+        ],
+    ).map_err(|e| e.to_string())?;
+
+    get_goal_by_id(&conn, &id)
+}
+
+#[tauri::command]
+pub async fn delete_goal(db: State<'_, Database>, id: String) -> Result<(), String> {
+    let conn = db.conn.lock().map_err(|e| e.to_string())?;
+    let deleted = conn
+        .execute("DELETE FROM goals WHERE id = ?", [&id])
+        .map_err(|e| e.to_string())?;
+    if deleted == 0 {
+        return Err(format!("Goal not found: {}", id));
+    }
+    Ok(())
+}
+
+/// Progress of every active goal for the period containing `as_of` (default now),
+/// optionally measured on the trades of some tags or strategies only
+#[tauri::command]
+pub async fn get_goal_progress(
+    db: State<'_, Database>,
+    as_of: Option<i64>,
+    tag_ids: Option<Vec<String>>,
+) -> Result<Vec<GoalProgress>, String> {
+    let conn = db.conn.lock().map_err(|e| e.to_string())?;
+    let tz = load_timezone(&conn);
+    let now = as_of.unwrap_or_else(|| Utc::now().timestamp());
+
+    let goals: Vec<Goal> = load_goals(&conn)?.into_iter().filter(|g| g.active).collect();
+    let periods = goals
+        .iter()
+        .map(|goal| goal_period(goal, now, &tz))
+        .collect::<Result<Vec<_>, _>>()?;
+    let (Some(earliest), Some(latest)) = (
+        periods.iter().map(|(start, _)| *start).min(),
+        periods.iter().map(|(_, end)| *end).max(),
+    ) else {
+        return Ok(Vec::new());
+    };
+
+    let (tag_filter, tag_params) = TagFilter::new(tag_ids).filter();
+    let mut params: Vec<Value> = vec![earliest.into(), latest.into()];
+    params.extend(tag_params.into_iter().map(Value::from));
+
+    // Tag placeholders are unnumbered and follow ?1 and ?2
+    let mut stmt = conn.prepare(&format!(
+        "SELECT trade_date, close_date, status, total_pnl - COALESCE(fees, 0.0) + COALESCE(funding, 0.0),
+                pnl_in_r, notes, effective_pe, exits
+         FROM trades
+         WHERE deleted_at IS NULL
+           AND ((trade_date >= ?1 AND trade_date <= ?2) OR (close_date >= ?1 AND close_date <= ?2)) {}",
+        tag_filter
+    )).map_err(|e| e.to_string())?;
+
+    let trades = stmt.query_map(rusqlite::params_from_iter(params.iter()), |row| {
+        Ok(GoalTrade {
+            trade_date: row.get(0)?,
+            close_date: row.get(1)?,
+            status: row.get(2)?,
+            net_pnl: row.get(3)?,
+            pnl_in_r: row.get(4)?,
+            notes: row.get(5)?,
+            effective_pe: row.get(6)?,
+            exits: row.get(7)?,
+        })
+    })
+    .map_err(|e| e.to_string())?
+    .collect::<Result<Vec<_>, _>>()
+    .map_err(|e| e.to_string())?;
+
+    Ok(goals
+        .into_iter()
+        .zip(periods)
+        .map(|(goal, (start, end))| {
+            let current_value = measure_goal(&goal.metric, &trades, start, end);
+            compute_goal_progress(goal, current_value, start, end, now)
+        })
+        .collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const DAY: i64 = 86400;
+    const JAN_1: i64 = 1704067200; // 2024-01-01 00:00 UTC, a Monday
+
+    fn goal(metric: &str, period: &str, target: f64) -> Goal {
+        Goal {
+            id: "GOAL-1".to_string(),
+            name: String::new(),
+            metric: metric.to_string(),
+            period: period.to_string(),
+            target,
+            start_date: None,
+            end_date: None,
+            active: true,
+            created_at: 0,
+            updated_at: 0,
+        }
+    }
+
+    fn closed(close_date: i64, pnl: f64, journaled: bool) -> GoalTrade {
+        GoalTrade {
+            trade_date: close_date - 3600,
+            close_date: Some(close_date),
+            status: if pnl > 0.0 { "WIN" } else { "LOSS" }.to_string(),
+            net_pnl: Some(pnl),
+            pnl_in_r: Some(pnl / 100.0),
+            notes: if journaled { "followed the plan".to_string() } else { String::new() },
+            effective_pe: Some(100.0),
+            exits: Some(r#"[{"price":110,"percent":100}]"#.to_string()),
+        }
+    }
+
+    #[test]
+    fn test_goal_periods_follow_calendar() {
+        let week = goal_period(&goal("PNL", "WEEK", 1.0), JAN_1 + 3 * DAY, &Tz::UTC).unwrap();
+        assert_eq!(week, (JAN_1, JAN_1 + 7 * DAY - 1));
+
+        let month = goal_period(&goal("PNL", "MONTH", 1.0), JAN_1 + 40 * DAY, &Tz::UTC).unwrap();
+        assert_eq!(month, (JAN_1 + 31 * DAY, JAN_1 + 60 * DAY - 1)); // February 2024 has 29 days
+    }
+
+    #[test]
+    fn test_measure_goal_metrics() {
+        let trades = vec![
+            closed(JAN_1 + DAY, 300.0, true),
+            closed(JAN_1 + 2 * DAY, -100.0, false),
+            closed(JAN_1 + 3 * DAY, -150.0, true),
+            closed(JAN_1 + 30 * DAY, 1000.0, true), // outside the week
+        ];
+        let (start, end) = (JAN_1, JAN_1 + 7 * DAY - 1);
+
+        assert!((measure_goal("PNL", &trades, start, end) - 50.0).abs() < 1e-9);
+        assert!((measure_goal("R", &trades, start, end) - 0.5).abs() < 1e-9);
+        assert!((measure_goal("MAX_DRAWDOWN", &trades, start, end) - 250.0).abs() < 1e-9);
+        assert_eq!(measure_goal("MAX_TRADES", &trades, start, end), 3.0);
+        assert!((measure_goal("JOURNAL_COMPLETENESS", &trades, start, end) - 200.0 / 3.0).abs() < 1e-9);
+        assert_eq!(measure_goal("JOURNAL_COMPLETENESS", &[], start, end), 100.0);
+    }
+
+    #[test]
+    fn test_progress_pace_and_projection() {
+        let (start, end) = (JAN_1, JAN_1 + 10 * DAY - 1);
+        let midway = JAN_1 + 5 * DAY;
+
+        let pnl = compute_goal_progress(goal("PNL", "CUSTOM", 1000.0), 400.0, start, end, midway);
+        assert!((pnl.time_elapsed_percent - 50.0).abs() < 1e-9);
+        assert_eq!(pnl.expected_value, Some(500.0));
+        assert!((pnl.pace_percent.unwrap() - 80.0).abs() < 1e-9);
+        assert!((pnl.projected_value - 800.0).abs() < 1e-9);
+        assert_eq!(pnl.status, "OFF_TRACK");
+
+        let trades = compute_goal_progress(goal("MAX_TRADES", "CUSTOM", 20.0), 8.0, start, end, midway);
+        assert_eq!(trades.status, "ON_TRACK");
+        let breached = compute_goal_progress(goal("MAX_DRAWDOWN", "CUSTOM", 200.0), 250.0, start, end, midway);
+        assert_eq!(breached.status, "BREACHED");
+        assert_eq!(breached.projected_value, 250.0);
+
+        let failed = compute_goal_progress(goal("R", "CUSTOM", 10.0), 4.0, start, end, end + 1);
+        assert!(failed.period_complete);
+        assert_eq!(failed.status, "FAILED");
+    }
+
+    #[test]
+    fn test_validate_goal() {
+        assert!(validate_goal("PNL", "MONTH", 500.0, None, None).is_ok());
+        assert!(validate_goal("WINRATE", "MONTH", 50.0, None, None).is_err());
+        assert!(validate_goal("JOURNAL_COMPLETENESS", "WEEK", 120.0, None, None).is_err());
+        assert!(validate_goal("R", "CUSTOM", 10.0, Some(200), Some(100)).is_err());
+        assert!(validate_goal("R", "CUSTOM", 10.0, Some(100), Some(200)).is_ok());
+    }
+}
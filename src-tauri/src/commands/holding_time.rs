@@ -0,0 +1,298 @@
+use tauri::State;
+use crate::commands::monte_carlo::percentile;
+use crate::commands::settings::load_timezone;
+use crate::commands::stats::{trade_filter, DateWindow, TagFilter};
+use crate::db::Database;
+use serde::{Deserialize, Serialize};
+
+const MINUTE: i64 = 60;
+const HOUR: i64 = 60 * MINUTE;
+const DAY: i64 = 24 * HOUR;
+
+/// Holding time buckets: [low, high) in seconds
+const HOLDING_TIME_BUCKETS: [(i64, i64, &str); 8] = [
+    (0, 15 * MINUTE, "<15m"),
+    (15 * MINUTE, HOUR, "15m-1h"),
+    (HOUR, 4 * HOUR, "1-4h"),
+    (4 * HOUR, DAY, "4-24h"),
+    (DAY, 3 * DAY, "1-3d"),
+    (3 * DAY, 7 * DAY, "3-7d"),
+    (7 * DAY, 30 * DAY, "7-30d"),
+    (30 * DAY, i64::MAX, "30d+"),
+];
+
+/// A trade counts as held far longer than usual above Q3 + multiplier * IQR of holding times
+const DEFAULT_OUTLIER_IQR_MULTIPLIER: f64 = 3.0;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct HoldingTimeBucket {
+    pub label: String,
+    pub sort_order: i64, // lower bound in seconds
+    pub trade_count: i32,
+    pub wins: i32,
+    pub losses: i32,
+    pub breakevens: i32,
+    pub win_rate: f64,
+    pub total_pnl: f64,
+    pub avg_pnl: f64,
+    pub total_r: f64,
+    pub avg_r: f64, // over trades with a known R multiple
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct HoldingTimeOutlier {
+    pub trade_id: String,
+    pub pair: String,
+    pub status: String,
+    pub trade_date: i64,
+    pub close_date: i64,
+    pub holding_seconds: i64,
+    pub total_pnl: Option<f64>,
+    pub pnl_in_r: Option<f64>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct HoldingTimeAnalysis {
+    pub trade_count: i32,
+    pub avg_holding_seconds: f64,
+    pub median_holding_seconds: f64,
+    pub avg_winner_holding_seconds: f64,
+    pub avg_loser_holding_seconds: f64,
+    pub avg_breakeven_holding_seconds: f64,
+    pub buckets: Vec<HoldingTimeBucket>, // only non-empty buckets, shortest first
+    pub outlier_threshold_seconds: f64,
+    pub outliers: Vec<HoldingTimeOutlier>, // longest first
+}
+
+/// Closed trade columns needed for holding time analytics
+#[derive(Debug, Clone)]
+struct HoldingTrade {
+    id: String,
+    pair: String,
+    status: String,
+    trade_date: i64,
+    close_date: i64,
+    total_pnl: Option<f64>,
+    pnl_in_r: Option<f64>,
+}
+
+impl HoldingTrade {
+    fn holding_seconds(&self) -> i64 {
+        self.close_date - self.trade_date
+    }
+}
+
+fn holding_time_bucket(seconds: i64) -> (&'static str, i64) {
+    HOLDING_TIME_BUCKETS
+        .iter()
+        .find(|(low, high, _)| seconds >= *low && seconds < *high)
+        .map(|(low, _, label)| (*label, *low))
+        .unwrap_or(("<15m", 0))
+}
+
+fn compute_holding_time(trades: &[HoldingTrade], outlier_iqr_multiplier: f64) -> HoldingTimeAnalysis {
+    let average = |status: Option<&str>| -> f64 {
+        let held: Vec<i64> = trades
+            .iter()
+            .filter(|t| status.is_none_or(|s| t.status == s))
+            .map(HoldingTrade::holding_seconds)
+            .collect();
+        if held.is_empty() { 0.0 } else { held.iter().sum::<i64>() as f64 / held.len() as f64 }
+    };
+
+    let mut sorted: Vec<f64> = trades.iter().map(|t| t.holding_seconds() as f64).collect();
+    sorted.sort_by(|a, b| a.total_cmp(b));
+    let q1 = percentile(&sorted, 25.0);
+    let q3 = percentile(&sorted, 75.0);
+    let outlier_threshold_seconds = q3 + outlier_iqr_multiplier * (q3 - q1);
+
+    let mut buckets: Vec<HoldingTimeBucket> = Vec::new();
+    let mut r_counts: Vec<i32> = Vec::new();
+    for trade in trades {
+        let (label, sort_order) = holding_time_bucket(trade.holding_seconds());
+        let index = match buckets.iter().position(|b| b.sort_order == sort_order) {
+            Some(index) => index,
+            None => {
+                buckets.push(HoldingTimeBucket {
+                    label: label.to_string(),
+                    sort_order,
+                    trade_count: 0,
+                    wins: 0,
+                    losses: 0,
+                    breakevens: 0,
+                    win_rate: 0.0,
+                    total_pnl: 0.0,
+                    avg_pnl: 0.0,
+                    total_r: 0.0,
+                    avg_r: 0.0,
+                });
+                r_counts.push(0);
+                buckets.len() - 1
+            }
+        };
+
+        let bucket = &mut buckets[index];
+        bucket.trade_count += 1;
+        match trade.status.as_str() {
+            "WIN" => bucket.wins += 1,
+            "LOSS" => bucket.losses += 1,
+            _ => bucket.breakevens += 1,
+        }
+        bucket.total_pnl += trade.total_pnl.unwrap_or(0.0);
+        if let Some(r) = trade.pnl_in_r {
+            bucket.total_r += r;
+            r_counts[index] += 1;
+        }
+    }
+
+    for (bucket, r_count) in buckets.iter_mut().zip(&r_counts) {
+        let decided = bucket.wins + bucket.losses;
+        if decided > 0 {
+            bucket.win_rate = bucket.wins as f64 / decided as f64 * 100.0;
+        }
+        bucket.avg_pnl = bucket.total_pnl / bucket.trade_count as f64;
+        if *r_count > 0 {
+            bucket.avg_r = bucket.total_r / *r_count as f64;
+        }
+    }
+    buckets.sort_by_key(|b| b.sort_order);
+
+    let mut outliers: Vec<HoldingTimeOutlier> = trades
+        .iter()
+        .filter(|t| t.holding_seconds() as f64 > outlier_threshold_seconds)
+        .map(|t| HoldingTimeOutlier {
+            trade_id: t.id.clone(),
+            pair: t.pair.clone(),
+            status: t.status.clone(),
+            trade_date: t.trade_date,
+            close_date: t.close_date,
+            holding_seconds: t.holding_seconds(),
+            total_pnl: t.total_pnl,
+            pnl_in_r: t.pnl_in_r,
+        })
+        .collect();
+    outliers.sort_by_key(|o| std::cmp::Reverse(o.holding_seconds));
+
+    HoldingTimeAnalysis {
+        trade_count: trades.len() as i32,
+        avg_holding_seconds: average(None),
+        median_holding_seconds: percentile(&sorted, 50.0),
+        avg_winner_holding_seconds: average(Some("WIN")),
+        avg_loser_holding_seconds: average(Some("LOSS")),
+        avg_breakeven_holding_seconds: average(Some("BE")),
+        buckets,
+        outlier_threshold_seconds,
+        outliers,
+    }
+}
+
+/// Holding time (trade_date to close_date) distribution, winners vs losers and PnL/R per bucket.
+/// Trades closed before they were opened (bad data) are skipped.
+#[tauri::command]
+pub async fn get_holding_time_analysis(
+    db: State<'_, Database>,
+    date_range: Option<String>,
+    start_date: Option<i64>,
+    end_date: Option<i64>,
+    outlier_iqr_multiplier: Option<f64>,
+    tag_ids: Option<Vec<String>>,
+) -> Result<HoldingTimeAnalysis, String> {
+    let multiplier = outlier_iqr_multiplier.unwrap_or(DEFAULT_OUTLIER_IQR_MULTIPLIER);
+    if !multiplier.is_finite() || multiplier < 0.0 {
+        return Err("outlier_iqr_multiplier must be a non-negative number".to_string());
+    }
+
+    let conn = db.conn.lock().map_err(|e| e.to_string())?;
+
+    let tz = load_timezone(&conn);
+    let window = DateWindow::resolve(date_range.as_deref(), start_date, end_date, &tz);
+    let (date_filter, date_params) = trade_filter(window, &TagFilter::new(tag_ids));
+
+    let mut stmt = conn.prepare(&format!(
+        "SELECT id, pair, status, trade_date, close_date, total_pnl, pnl_in_r
+         FROM trades
+         WHERE deleted_at IS NULL AND status IN ('WIN', 'LOSS', 'BE')
+           AND close_date IS NOT NULL AND close_date >= trade_date {}",
+        date_filter
+    )).map_err(|e| e.to_string())?;
+
+    let trades = stmt.query_map(rusqlite::params_from_iter(date_params.iter()), |row| {
+        Ok(HoldingTrade {
+            id: row.get(0)?,
+            pair: row.get(1)?,
+            status: row.get(2)?,
+            trade_date: row.get(3)?,
+            close_date: row.get(4)?,
+            total_pnl: row.get(5)?,
+            pnl_in_r: row.get(6)?,
+        })
+    })
+    .map_err(|e| e.to_string())?
+    .collect::<Result<Vec<_>, _>>()
+    .map_err(|e| e.to_string())?;
+
+    Ok(compute_holding_time(&trades, multiplier))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn trade(id: &str, status: &str, holding_seconds: i64, pnl: f64, r: Option<f64>) -> HoldingTrade {
+        HoldingTrade {
+            id: id.to_string(),
+            pair: "BTC/USDT".to_string(),
+            status: status.to_string(),
+            trade_date: 1_700_000_000,
+            close_date: 1_700_000_000 + holding_seconds,
+            total_pnl: Some(pnl),
+            pnl_in_r: r,
+        }
+    }
+
+    #[test]
+    fn test_holding_time_buckets() {
+        assert_eq!(holding_time_bucket(0), ("<15m", 0));
+        assert_eq!(holding_time_bucket(15 * MINUTE), ("15m-1h", 15 * MINUTE));
+        assert_eq!(holding_time_bucket(5 * HOUR), ("4-24h", 4 * HOUR));
+        assert_eq!(holding_time_bucket(90 * DAY), ("30d+", 30 * DAY));
+    }
+
+    #[test]
+    fn test_winners_vs_losers_and_bucket_stats() {
+        let trades = vec![
+            trade("a", "WIN", 10 * MINUTE, 100.0, Some(1.0)),
+            trade("b", "LOSS", 5 * MINUTE, -50.0, Some(-0.5)),
+            trade("c", "WIN", 2 * DAY, 300.0, None),
+        ];
+        let analysis = compute_holding_time(&trades, DEFAULT_OUTLIER_IQR_MULTIPLIER);
+
+        assert_eq!(analysis.trade_count, 3);
+        assert!((analysis.avg_winner_holding_seconds - (10 * MINUTE + 2 * DAY) as f64 / 2.0).abs() < 1e-9);
+        assert!((analysis.avg_loser_holding_seconds - (5 * MINUTE) as f64).abs() < 1e-9);
+        assert_eq!(analysis.avg_breakeven_holding_seconds, 0.0);
+
+        assert_eq!(analysis.buckets.len(), 2);
+        let scalps = &analysis.buckets[0];
+        assert_eq!(scalps.label, "<15m");
+        assert_eq!(scalps.trade_count, 2);
+        assert!((scalps.win_rate - 50.0).abs() < 1e-9);
+        assert!((scalps.avg_pnl - 25.0).abs() < 1e-9);
+        assert!((scalps.avg_r - 0.25).abs() < 1e-9);
+        assert_eq!(analysis.buckets[1].label, "1-3d");
+        assert_eq!(analysis.buckets[1].avg_r, 0.0);
+    }
+
+    #[test]
+    fn test_flags_trades_held_far_longer_than_usual() {
+        let mut trades: Vec<HoldingTrade> = (0..10)
+            .map(|i| trade(&i.to_string(), "WIN", HOUR + i * MINUTE, 10.0, Some(0.1)))
+            .collect();
+        trades.push(trade("forgotten", "LOSS", 20 * DAY, -200.0, Some(-2.0)));
+
+        let analysis = compute_holding_time(&trades, DEFAULT_OUTLIER_IQR_MULTIPLIER);
+        assert_eq!(analysis.outliers.len(), 1);
+        assert_eq!(analysis.outliers[0].trade_id, "forgotten");
+        assert!(analysis.outlier_threshold_seconds < (20 * DAY) as f64);
+    }
+}
@@ -1,7 +1,20 @@
-use tauri::State;
+use tauri::{AppHandle, State};
+use crate::commands::attachments::{
+    attachments_dir, load_attachment_files, load_attachments, prune_attachment_files, restore_attachment_files,
+};
+use crate::commands::capital::load_capital_flows;
+use crate::commands::goals::load_goals;
+use crate::commands::review::load_all_review_labels;
+use crate::commands::revisions::{record_revision, trade_snapshot, TradeSnapshot};
+use crate::commands::search::{prune_search_index, rebuild_trade_search, reindex_trade};
+use crate::commands::tags::load_all_tags;
+use crate::commands::daily_stats::invalidate_daily_stats;
 use crate::db::Database;
-use crate::models::{Trade, Settings};
+use crate::models::{
+    Attachment, AttachmentFile, CapitalFlow, Goal, ReviewLabel, Tag, Trade, TradeReviewLabel, TradeTag, Settings,
+};
 use chrono::Utc;
+use rusqlite::OptionalExtension;
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 use calamine::{open_workbook, Data, Reader, Xlsx};
@@ -84,6 +97,9 @@ pub async fn import_bitget_csv(
     {
         let conn = db.conn.lock().map_err(|e| e.to_string())?;
 
+        // Bulk insert: let the next stats read rebuild the daily aggregates
+        invalidate_daily_stats(&conn)?;
+
         // Skip header
         for (line_num, line) in lines.iter().enumerate().skip(1) {
             if line.trim().is_empty() {
@@ -177,9 +193,9 @@ pub async fn import_bitget_csv(
                             portfolio_value, r_percent, min_rr,
                             planned_pe, planned_sl, leverage, planned_tps, planned_entries,
                             position_type, one_r, margin, position_size, quantity,
-                            planned_weighted_rr, effective_pe, effective_entries, exits, total_pnl,
+                            planned_weighted_rr, effective_pe, effective_entries, exits, total_pnl, fees,
                             notes, import_fingerprint, import_source, created_at, updated_at
-                        ) VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?)",
+                        ) VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?)",
                         rusqlite::params![
                             id,
                             trade_data.pair,
@@ -206,6 +222,7 @@ pub async fn import_bitget_csv(
                             serde_json::to_string(&vec![serde_json::json!({"price": trade_data.entry_price, "percent": 100})]).ok(),
                             exits,
                             trade_data.realized_pnl,
+                            trade_data.total_fees,
                             notes,
                             fingerprint,
                             "CSV_IMPORT",
@@ -214,6 +231,8 @@ pub async fn import_bitget_csv(
                         ],
                     )
                     .map_err(|e| e.to_string())?;
+                    record_revision(&conn, &id, "CREATE", "CSV_IMPORT", None)?;
+                    reindex_trade(&conn, &id, true)?;
 
                     imported += 1;
                 }
@@ -233,7 +252,7 @@ pub async fn import_bitget_csv(
 
 /// Delete all BitGet imported trades (both CSV and API imports)
 #[tauri::command]
-pub async fn delete_bitget_trades(db: State<'_, Database>) -> Result<usize, String> {
+pub async fn delete_bitget_trades(app: AppHandle, db: State<'_, Database>) -> Result<usize, String> {
     let conn = db.conn.lock().map_err(|e| e.to_string())?;
     let count = conn
         .execute(
@@ -241,6 +260,9 @@ pub async fn delete_bitget_trades(db: State<'_, Database>) -> Result<usize, Stri
             [],
         )
         .map_err(|e| e.to_string())?;
+    invalidate_daily_stats(&conn)?;
+    prune_attachment_files(&conn, &attachments_dir(&app)?)?;
+    prune_search_index(&conn)?;
     Ok(count)
 }
 
@@ -675,6 +697,9 @@ pub async fn import_blofin_csv(
     {
         let conn = db.conn.lock().map_err(|e| e.to_string())?;
 
+        // Bulk insert: let the next stats read rebuild the daily aggregates
+        invalidate_daily_stats(&conn)?;
+
         for pos in positions {
             let fingerprint = generate_blofin_fingerprint(&pos);
 
@@ -760,9 +785,9 @@ pub async fn import_blofin_csv(
                     portfolio_value, r_percent, min_rr,
                     planned_pe, planned_sl, leverage, planned_tps, planned_entries,
                     position_type, one_r, margin, position_size, quantity,
-                    planned_weighted_rr, effective_pe, effective_entries, exits, total_pnl,
+                    planned_weighted_rr, effective_pe, effective_entries, exits, total_pnl, fees,
                     notes, import_fingerprint, import_source, created_at, updated_at
-                ) VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?)",
+                ) VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?)",
                 rusqlite::params![
                     id,
                     pos.pair,
@@ -789,6 +814,7 @@ pub async fn import_blofin_csv(
                     pos.entries_json,
                     pos.exits_json,
                     pos.realized_pnl,
+                    pos.total_fees,
                     notes,
                     fingerprint,
                     "CSV_IMPORT",
@@ -796,7 +822,11 @@ pub async fn import_blofin_csv(
                     now,
                 ],
             ) {
-                Ok(_) => imported += 1,
+                Ok(_) => {
+                    record_revision(&conn, &id, "CREATE", "CSV_IMPORT", None)?;
+                    reindex_trade(&conn, &id, true)?;
+                    imported += 1;
+                }
                 Err(e) => errors.push(format!("Failed to import {}: {}", pos.pair, e)),
             }
         }
@@ -811,7 +841,7 @@ pub async fn import_blofin_csv(
 
 /// Delete all BloFin CSV-imported trades
 #[tauri::command]
-pub async fn delete_blofin_trades(db: State<'_, Database>) -> Result<usize, String> {
+pub async fn delete_blofin_trades(app: AppHandle, db: State<'_, Database>) -> Result<usize, String> {
     let conn = db.conn.lock().map_err(|e| e.to_string())?;
     let count = conn
         .execute(
@@ -819,6 +849,9 @@ pub async fn delete_blofin_trades(db: State<'_, Database>) -> Result<usize, Stri
             [],
         )
         .map_err(|e| e.to_string())?;
+    invalidate_daily_stats(&conn)?;
+    prune_attachment_files(&conn, &attachments_dir(&app)?)?;
+    prune_search_index(&conn)?;
     Ok(count)
 }
 
@@ -1134,6 +1167,9 @@ pub async fn import_bingx_file(
     {
         let conn = db.conn.lock().map_err(|e| e.to_string())?;
 
+        // Bulk insert: let the next stats read rebuild the daily aggregates
+        invalidate_daily_stats(&conn)?;
+
         for pos in positions {
             let fingerprint = generate_bingx_fingerprint(&pos);
 
@@ -1208,9 +1244,9 @@ pub async fn import_bingx_file(
                     portfolio_value, r_percent, min_rr,
                     planned_pe, planned_sl, leverage, planned_tps, planned_entries,
                     position_type, one_r, margin, position_size, quantity,
-                    planned_weighted_rr, effective_pe, effective_entries, exits, total_pnl,
+                    planned_weighted_rr, effective_pe, effective_entries, exits, total_pnl, fees,
                     notes, import_fingerprint, import_source, created_at, updated_at
-                ) VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?)",
+                ) VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?)",
                 rusqlite::params![
                     id, pos.pair, "BingX",
                     opening_ts, opening_ts, closing_ts,
@@ -1221,11 +1257,15 @@ pub async fn import_bingx_file(
                     one_r, margin, position_size, pos.quantity,
                     0.0,
                     pos.entry_price, pos.entries_json, pos.exits_json,
-                    pos.realized_pnl, notes, fingerprint, "CSV_IMPORT",
+                    pos.realized_pnl, pos.total_fees, notes, fingerprint, "CSV_IMPORT",
                     now, now,
                 ],
             ) {
-                Ok(_) => imported += 1,
+                Ok(_) => {
+                    record_revision(&conn, &id, "CREATE", "CSV_IMPORT", None)?;
+                    reindex_trade(&conn, &id, true)?;
+                    imported += 1;
+                }
                 Err(e) => errors.push(format!("Failed to import {}: {}", pos.pair, e)),
             }
         }
@@ -1236,11 +1276,14 @@ pub async fn import_bingx_file(
 
 /// Delete all BingX imported trades
 #[tauri::command]
-pub async fn delete_bingx_trades(db: State<'_, Database>) -> Result<usize, String> {
+pub async fn delete_bingx_trades(app: AppHandle, db: State<'_, Database>) -> Result<usize, String> {
     let conn = db.conn.lock().map_err(|e| e.to_string())?;
     let count = conn
         .execute("DELETE FROM trades WHERE import_fingerprint LIKE 'xlsx|bingx|%'", [])
         .map_err(|e| e.to_string())?;
+    invalidate_daily_stats(&conn)?;
+    prune_attachment_files(&conn, &attachments_dir(&app)?)?;
+    prune_search_index(&conn)?;
     Ok(count)
 }
 
@@ -1250,19 +1293,35 @@ pub async fn delete_bingx_trades(db: State<'_, Database>) -> Result<usize, Strin
 pub struct BackupData {
     pub settings: Settings,
     pub trades: Vec<Trade>,
+    #[serde(default)] // Backups made before the capital ledger have no flows
+    pub capital_flows: Vec<CapitalFlow>,
+    #[serde(default)] // Same for backups made before goals
+    pub goals: Vec<Goal>,
+    #[serde(default)] // ... and before tags
+    pub tags: Vec<Tag>,
+    #[serde(default)]
+    pub trade_tags: Vec<TradeTag>,
+    #[serde(default)] // ... and before attachments
+    pub attachments: Vec<Attachment>,
+    #[serde(default)]
+    pub attachment_files: Vec<AttachmentFile>,
+    #[serde(default)] // ... and before mistakes and emotions
+    pub review_labels: Vec<ReviewLabel>,
+    #[serde(default)]
+    pub trade_review_labels: Vec<TradeReviewLabel>,
     pub export_date: String,
     pub version: String,
 }
 
-/// Export all data to JSON
+/// Export all data to JSON, attachment files included
 #[tauri::command]
-pub async fn export_all_data(db: State<'_, Database>) -> Result<String, String> {
+pub async fn export_all_data(app: AppHandle, db: State<'_, Database>) -> Result<String, String> {
     let conn = db.conn.lock().map_err(|e| e.to_string())?;
 
     // Get settings
     let settings = conn
         .query_row(
-            "SELECT id, initial_capital, current_r_percent, default_min_rr, default_leverage, currency, enable_position_monitor, enable_api_connections, created_at, updated_at FROM settings WHERE id = 1",
+            "SELECT id, initial_capital, current_r_percent, default_min_rr, default_leverage, currency, enable_position_monitor, enable_api_connections, timezone, created_at, updated_at FROM settings WHERE id = 1",
             [],
             |row| {
                 Ok(Settings {
@@ -1274,8 +1333,9 @@ pub async fn export_all_data(db: State<'_, Database>) -> Result<String, String>
                     currency: row.get(5)?,
                     enable_position_monitor: row.get::<_, i32>(6)? == 1,
                     enable_api_connections: row.get::<_, i32>(7)? == 1,
-                    created_at: row.get(8)?,
-                    updated_at: row.get(9)?,
+                    timezone: row.get(8)?,
+                    created_at: row.get(9)?,
+                    updated_at: row.get(10)?,
                 })
             },
         )
@@ -1316,6 +1376,9 @@ pub async fn export_all_data(db: State<'_, Database>) -> Result<String, String>
                 effective_weighted_rr: row.get("effective_weighted_rr").ok(),
                 total_pnl: row.get("total_pnl").ok(),
                 pnl_in_r: row.get("pnl_in_r").ok(),
+                fees: row.get("fees").ok(),
+                funding: row.get("funding").ok(),
+                execution_score: row.get("execution_score").ok(),
                 notes: row.get("notes")?,
                 execution_portfolio: row.get("execution_portfolio").ok(),
                 execution_r_percent: row.get("execution_r_percent").ok(),
@@ -1334,9 +1397,24 @@ pub async fn export_all_data(db: State<'_, Database>) -> Result<String, String>
         .collect::<Result<Vec<Trade>, _>>()
         .map_err(|e| e.to_string())?;
 
+    let capital_flows = load_capital_flows(&conn)?;
+    let goals = load_goals(&conn)?;
+    let (tags, trade_tags) = load_all_tags(&conn)?;
+    let attachments = load_attachments(&conn, None)?;
+    let attachment_files = load_attachment_files(&conn, &attachments_dir(&app)?)?;
+    let (review_labels, trade_review_labels) = load_all_review_labels(&conn)?;
+
     let backup = BackupData {
         settings,
         trades,
+        capital_flows,
+        goals,
+        tags,
+        trade_tags,
+        attachments,
+        attachment_files,
+        review_labels,
+        trade_review_labels,
         export_date: Utc::now().to_rfc3339(),
         version: "1.0.0".to_string(),
     };
@@ -1344,25 +1422,148 @@ pub async fn export_all_data(db: State<'_, Database>) -> Result<String, String>
     serde_json::to_string_pretty(&backup).map_err(|e| e.to_string())
 }
 
+/// Trade columns restored from a backup, in the order of the import parameters
+const BACKUP_TRADE_COLUMNS: [&str; 43] = [
+    "id", "pair", "exchange", "analysis_date", "trade_date", "close_date", "status",
+    "portfolio_value", "r_percent", "min_rr", "planned_pe", "planned_sl", "leverage",
+    "planned_tps", "planned_entries", "position_type", "one_r", "margin", "position_size",
+    "quantity", "planned_weighted_rr", "effective_pe", "effective_entries", "exits",
+    "effective_weighted_rr", "total_pnl", "pnl_in_r", "notes", "import_fingerprint",
+    "import_source", "execution_portfolio", "execution_r_percent", "execution_margin",
+    "execution_position_size", "execution_quantity", "execution_one_r",
+    "execution_potential_profit", "fees", "funding", "execution_score", "created_at", "updated_at",
+    "deleted_at",
+];
+
+/// Restore tags and their trade links. Existing tags are updated in place: REPLACE would delete
+/// them first and cascade to the links of trades that are not in the backup. A backup tag named
+/// like a local tag of the same kind is merged into the local one.
+fn restore_tags(conn: &rusqlite::Connection, tags: &[Tag], links: &[TradeTag]) -> Result<(), String> {
+    let mut merged: HashMap<&str, String> = HashMap::new();
+
+    for tag in tags {
+        let local_id: Option<String> = conn
+            .query_row(
+                "SELECT id FROM tags WHERE kind = ? AND name = ? AND id != ?",
+                rusqlite::params![tag.kind, tag.name, tag.id],
+                |row| row.get(0),
+            )
+            .optional()
+            .map_err(|e| e.to_string())?;
+        if let Some(local_id) = local_id {
+            merged.insert(&tag.id, local_id);
+            continue;
+        }
+
+        conn.execute(
+            "INSERT INTO tags (id, name, kind, color, description, created_at, updated_at) VALUES (?, ?, ?, ?, ?, ?, ?)
+             ON CONFLICT(id) DO UPDATE SET name = excluded.name, kind = excluded.kind, color = excluded.color,
+                 description = excluded.description, updated_at = excluded.updated_at",
+            rusqlite::params![
+                tag.id,
+                tag.name,
+                tag.kind,
+                tag.color,
+                tag.description,
+                tag.created_at,
+                tag.updated_at,
+            ],
+        )
+        .map_err(|e| e.to_string())?;
+    }
+
+    for link in links {
+        let tag_id = merged.get(link.tag_id.as_str()).unwrap_or(&link.tag_id);
+        conn.execute(
+            "INSERT OR IGNORE INTO trade_tags (trade_id, tag_id, created_at) VALUES (?, ?, ?)",
+            rusqlite::params![link.trade_id, tag_id, link.created_at],
+        )
+        .map_err(|e| e.to_string())?;
+    }
+
+    Ok(())
+}
+
+/// Restore review labels and their trade links the same way as tags. The default catalogue is
+/// seeded in every database, so the backup always carries labels that already exist.
+fn restore_review_labels(
+    conn: &rusqlite::Connection,
+    labels: &[ReviewLabel],
+    links: &[TradeReviewLabel],
+) -> Result<(), String> {
+    let mut merged: HashMap<&str, String> = HashMap::new();
+
+    for label in labels {
+        let local_id: Option<String> = conn
+            .query_row(
+                "SELECT id FROM review_labels WHERE category = ? AND name = ? AND id != ?",
+                rusqlite::params![label.category, label.name, label.id],
+                |row| row.get(0),
+            )
+            .optional()
+            .map_err(|e| e.to_string())?;
+        if let Some(local_id) = local_id {
+            merged.insert(&label.id, local_id);
+            continue;
+        }
+
+        conn.execute(
+            "INSERT INTO review_labels (id, name, category, description, active, created_at, updated_at) VALUES (?, ?, ?, ?, ?, ?, ?)
+             ON CONFLICT(id) DO UPDATE SET name = excluded.name, category = excluded.category,
+                 description = excluded.description, active = excluded.active, updated_at = excluded.updated_at",
+            rusqlite::params![
+                label.id,
+                label.name,
+                label.category,
+                label.description,
+                label.active as i32,
+                label.created_at,
+                label.updated_at,
+            ],
+        )
+        .map_err(|e| e.to_string())?;
+    }
+
+    for link in links {
+        let label_id = merged.get(link.label_id.as_str()).unwrap_or(&link.label_id);
+        conn.execute(
+            "INSERT OR IGNORE INTO trade_review_labels (trade_id, label_id, created_at) VALUES (?, ?, ?)",
+            rusqlite::params![link.trade_id, label_id, link.created_at],
+        )
+        .map_err(|e| e.to_string())?;
+    }
+
+    Ok(())
+}
+
 /// Import data from JSON backup
 #[tauri::command]
 pub async fn import_all_data(
+    app: AppHandle,
     db: State<'_, Database>,
     json_data: String,
 ) -> Result<(usize, usize), String> {
     let backup: BackupData = serde_json::from_str(&json_data).map_err(|e| e.to_string())?;
 
+    // Files first, a corrupted backup fails before anything in the database is replaced
+    let dir = attachments_dir(&app)?;
+    restore_attachment_files(&dir, &backup.attachment_files)?;
+
     let conn = db.conn.lock().map_err(|e| e.to_string())?;
 
+    // Trades and possibly the timezone are replaced, rebuild the daily aggregates on next read
+    invalidate_daily_stats(&conn)?;
+
     // Update settings
     conn.execute(
-        "UPDATE settings SET initial_capital = ?, current_r_percent = ?, default_min_rr = ?, default_leverage = ?, currency = ?, updated_at = ? WHERE id = 1",
+        "UPDATE settings SET initial_capital = ?, current_r_percent = ?, default_min_rr = ?, default_leverage = ?, currency = ?, timezone = ?, updated_at = ? WHERE id = 1",
         rusqlite::params![
             backup.settings.initial_capital,
             backup.settings.current_r_percent,
             backup.settings.default_min_rr,
             backup.settings.default_leverage,
             backup.settings.currency,
+            backup.settings.timezone,
             Utc::now().timestamp(),
         ],
     )
@@ -1370,10 +1571,24 @@ pub async fn import_all_data(
 
     let mut imported_trades = 0;
 
-    // Import trades (use REPLACE to overwrite existing trades)
+    // Existing trades are updated in place: REPLACE would delete them first and cascade
+    // to their tags, attachments and history
+    let upsert = format!(
+        "INSERT INTO trades ({}) VALUES ({}) ON CONFLICT(id) DO UPDATE SET {}",
+        BACKUP_TRADE_COLUMNS.join(", "),
+        vec!["?"; BACKUP_TRADE_COLUMNS.len()].join(", "),
+        BACKUP_TRADE_COLUMNS[1..]
+            .iter()
+            .map(|column| format!("{} = excluded.{}", column, column))
+            .collect::<Vec<_>>()
+            .join(", "),
+    );
+    let mut snapshots: Vec<(String, Option<TradeSnapshot>)> = Vec::new();
+
     for trade in backup.trades {
+        snapshots.push((trade.id.clone(), trade_snapshot(&conn, &trade.id)?));
         conn.execute(
-            "REPLACE INTO trades (id, pair, exchange, analysis_date, trade_date, close_date, status, portfolio_value, r_percent, min_rr, planned_pe, planned_sl, leverage, planned_tps, planned_entries, position_type, one_r, margin, position_size, quantity, planned_weighted_rr, effective_pe, effective_entries, exits, effective_weighted_rr, total_pnl, pnl_in_r, notes, import_fingerprint, import_source, execution_portfolio, execution_r_percent, execution_margin, execution_position_size, execution_quantity, execution_one_r, execution_potential_profit, created_at, updated_at, deleted_at) VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?)",
+            &upsert,
             rusqlite::params![
                 trade.id,
                 trade.pair,
@@ -1412,6 +1627,9 @@ pub async fn import_all_data(
                 trade.execution_quantity,
                 trade.execution_one_r,
                 trade.execution_potential_profit,
+                trade.fees,
+                trade.funding,
+                trade.execution_score,
                 trade.created_at,
                 trade.updated_at,
                 None::<i64>, // deleted_at is NULL for imported trades
@@ -1422,12 +1640,164 @@ pub async fn import_all_data(
         imported_trades += 1;
     }
 
+    // Import capital flows (REPLACE by id, same as trades)
+    for flow in backup.capital_flows {
+        conn.execute(
+            "REPLACE INTO capital_flows (id, flow_date, flow_type, amount, note, created_at, updated_at) VALUES (?, ?, ?, ?, ?, ?, ?)",
+            rusqlite::params![
+                flow.id,
+                flow.flow_date,
+                flow.flow_type,
+                flow.amount,
+                flow.note,
+                flow.created_at,
+                flow.updated_at,
+            ],
+        )
+        .map_err(|e| e.to_string())?;
+    }
+
+    for goal in backup.goals {
+        conn.execute(
+            "REPLACE INTO goals (id, name, metric, period, target, start_date, end_date, active, created_at, updated_at) VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?)",
+            rusqlite::params![
+                goal.id,
+                goal.name,
+                goal.metric,
+                goal.period,
+                goal.target,
+                goal.start_date,
+                goal.end_date,
+                goal.active as i32,
+                goal.created_at,
+                goal.updated_at,
+            ],
+        )
+        .map_err(|e| e.to_string())?;
+    }
+
+    // Tags before their links, links after the trades they point to
+    restore_tags(&conn, &backup.tags, &backup.trade_tags)?;
+
+    restore_review_labels(&conn, &backup.review_labels, &backup.trade_review_labels)?;
+
+    for attachment in backup.attachments {
+        if !dir.join(&attachment.stored_name).exists() {
+            log::warn!("Skipping attachment {} without its file", attachment.id);
+            continue;
+        }
+        conn.execute(
+            "REPLACE INTO attachments (id, trade_id, kind, file_name, stored_name, content_hash, mime_type, size_bytes, caption, sort_order, created_at) VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?)",
+            rusqlite::params![
+                attachment.id,
+                attachment.trade_id,
+                attachment.kind,
+                attachment.file_name,
+                attachment.stored_name,
+                attachment.content_hash,
+                attachment.mime_type,
+                attachment.size_bytes,
+                attachment.caption,
+                attachment.sort_order,
+                attachment.created_at,
+            ],
+        )
+        .map_err(|e| e.to_string())?;
+    }
+
+    // Recorded last so the revisions include the restored tags and review labels
+    for (trade_id, before) in snapshots {
+        let action = if before.is_some() { "UPDATE" } else { "CREATE" };
+        record_revision(&conn, &trade_id, action, "BACKUP_RESTORE", before)?;
+    }
+    // Restored tags may carry other names than the ones indexed
+    rebuild_trade_search(&conn)?;
+
     Ok((1, imported_trades)) // (settings_updated, trades_imported)
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::db::{insert_test_trade, test_connection};
+
+    fn tag(id: &str, name: &str) -> Tag {
+        Tag {
+            id: id.to_string(),
+            name: name.to_string(),
+            kind: "TAG".to_string(),
+            color: None,
+            description: String::new(),
+            created_at: 0,
+            updated_at: 0,
+        }
+    }
+
+    fn query_ids(conn: &rusqlite::Connection, sql: &str, param: &str) -> Vec<String> {
+        let mut stmt = conn.prepare(sql).unwrap();
+        stmt.query_map([param], |row| row.get(0))
+            .unwrap()
+            .collect::<Result<Vec<_>, _>>()
+            .unwrap()
+    }
+
+    #[test]
+    fn test_restore_keeps_tags_of_trades_missing_from_backup() {
+        let conn = test_connection();
+        insert_test_trade(&conn, "in_backup", &[]);
+        insert_test_trade(&conn, "local_only", &[]);
+        conn.execute_batch(
+            "INSERT INTO tags (id, name, created_at, updated_at) VALUES ('breakout', 'breakout', 0, 0), ('local-news', 'news', 0, 0);
+             INSERT INTO trade_tags (trade_id, tag_id, created_at) VALUES
+                 ('in_backup', 'breakout', 0), ('local_only', 'breakout', 0), ('local_only', 'local-news', 0);",
+        ).unwrap();
+
+        // Same id as a local tag, and another id with the name of a local tag
+        let tags = [tag("breakout", "Breakout"), tag("backup-news", "News")];
+        let links = [("in_backup", "breakout"), ("in_backup", "backup-news")]
+            .map(|(trade_id, tag_id)| TradeTag { trade_id: trade_id.to_string(), tag_id: tag_id.to_string(), created_at: 0 });
+        restore_tags(&conn, &tags, &links).unwrap();
+
+        let sql = "SELECT tag_id FROM trade_tags WHERE trade_id = ? ORDER BY tag_id";
+        assert_eq!(query_ids(&conn, sql, "local_only"), ["breakout", "local-news"]);
+        assert_eq!(query_ids(&conn, sql, "in_backup"), ["breakout", "local-news"]);
+        let names = query_ids(&conn, "SELECT name FROM tags WHERE kind = ? ORDER BY id", "TAG");
+        assert_eq!(names, ["Breakout", "news"]);
+    }
+
+    #[test]
+    fn test_restore_keeps_review_labels_of_trades_missing_from_backup() {
+        let conn = test_connection();
+        insert_test_trade(&conn, "in_backup", &[]);
+        insert_test_trade(&conn, "local_only", &[]);
+        conn.execute(
+            "INSERT INTO trade_review_labels (trade_id, label_id, created_at) VALUES ('local_only', 'MISTAKE-fomo-entry', 0)",
+            [],
+        ).unwrap();
+
+        // A seeded default label, as every backup carries them
+        let labels = [ReviewLabel {
+            id: "MISTAKE-fomo-entry".to_string(),
+            name: "FOMO entry".to_string(),
+            category: "MISTAKE".to_string(),
+            description: "Chased the move".to_string(),
+            active: true,
+            created_at: 0,
+            updated_at: 0,
+        }];
+        let links = [TradeReviewLabel {
+            trade_id: "in_backup".to_string(),
+            label_id: "MISTAKE-fomo-entry".to_string(),
+            created_at: 0,
+        }];
+        restore_review_labels(&conn, &labels, &links).unwrap();
+
+        let sql = "SELECT label_id FROM trade_review_labels WHERE trade_id = ?";
+        assert_eq!(query_ids(&conn, sql, "local_only"), ["MISTAKE-fomo-entry"]);
+        assert_eq!(query_ids(&conn, sql, "in_backup"), ["MISTAKE-fomo-entry"]);
+        let descriptions = query_ids(&conn, "SELECT description FROM review_labels WHERE id = ?", "MISTAKE-fomo-entry");
+        assert_eq!(descriptions, ["Chased the move"]);
+    }
 
     #[test]
     fn test_backward_compatibility_import_source() {
@@ -0,0 +1,214 @@
+use tauri::State;
+use crate::commands::settings::load_timezone;
+use crate::commands::stats::{trade_filter, DateWindow, TagFilter};
+use crate::db::Database;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+
+/// Risk fraction (fraction of equity risked per 1R) with its expected growth
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct KellyLevel {
+    pub label: String,   // "full" | "half" | "quarter" | "current"
+    pub r_percent: f64,  // fraction of equity (0.02 = 2%), same unit as settings.current_r_percent
+    pub growth_rate: Option<f64>, // expected log growth per trade, None if a max loss would wipe out equity
+    pub growth_percent_per_trade: Option<f64>, // (e^growth_rate - 1) * 100
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct KellyStats {
+    pub key: String, // "ALL" or the pair
+    pub sample_size: i32,
+    pub wins: i32,
+    pub losses: i32,
+    pub breakevens: i32,
+    pub win_rate: f64, // % of all sampled trades, breakevens included
+    pub avg_win_r: f64,
+    pub avg_loss_r: f64, // positive
+    pub payoff_ratio: Option<f64>, // avg_win_r / avg_loss_r
+    pub full_kelly: Option<f64>,   // None without both wins and losses, <= 0 means no edge
+    pub levels: Vec<KellyLevel>,
+    pub current_vs_full_kelly: Option<f64>, // current_r_percent / full_kelly
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct KellyAnalysis {
+    pub current_r_percent: f64,
+    pub overall: KellyStats,
+    pub by_pair: Vec<KellyStats>, // most traded first
+}
+
+/// Expected log growth per trade when risking `f` of equity per 1R (rates are probabilities, breakevens add nothing)
+fn growth_rate(win_rate: f64, avg_win_r: f64, loss_rate: f64, avg_loss_r: f64, f: f64) -> Option<f64> {
+    let loss_factor = 1.0 - f * avg_loss_r;
+    if loss_rate > 0.0 && loss_factor <= 0.0 {
+        return None;
+    }
+    let mut growth = win_rate * (1.0 + f * avg_win_r).ln();
+    if loss_rate > 0.0 {
+        growth += loss_rate * loss_factor.ln();
+    }
+    Some(growth)
+}
+
+/// Kelly fraction and growth at full/half/quarter Kelly and the current R% for a set of R multiples.
+/// Maximizing p_w ln(1 + fW) + p_l ln(1 - fL) gives f* = (p_w / L - p_l / W) / (p_w + p_l).
+fn compute_kelly(key: &str, r_multiples: &[f64], current_r_percent: f64) -> KellyStats {
+    let wins: Vec<f64> = r_multiples.iter().copied().filter(|r| *r > 0.0).collect();
+    let losses: Vec<f64> = r_multiples.iter().copied().filter(|r| *r < 0.0).map(f64::abs).collect();
+    let sample_size = r_multiples.len() as i32;
+    let breakevens = sample_size - wins.len() as i32 - losses.len() as i32;
+
+    let mean = |values: &[f64]| if values.is_empty() { 0.0 } else { values.iter().sum::<f64>() / values.len() as f64 };
+    let avg_win_r = mean(&wins);
+    let avg_loss_r = mean(&losses);
+
+    let (win_rate, loss_rate) = if sample_size > 0 {
+        (wins.len() as f64 / sample_size as f64, losses.len() as f64 / sample_size as f64)
+    } else {
+        (0.0, 0.0)
+    };
+
+    let full_kelly = if avg_win_r > 0.0 && avg_loss_r > 0.0 {
+        Some((win_rate / avg_loss_r - loss_rate / avg_win_r) / (win_rate + loss_rate))
+    } else {
+        None
+    };
+
+    let level = |label: &str, r_percent: f64| {
+        let growth = growth_rate(win_rate, avg_win_r, loss_rate, avg_loss_r, r_percent);
+        KellyLevel {
+            label: label.to_string(),
+            r_percent,
+            growth_rate: growth,
+            growth_percent_per_trade: growth.map(|g| (g.exp() - 1.0) * 100.0),
+        }
+    };
+
+    let mut levels = Vec::new();
+    if let Some(kelly) = full_kelly {
+        // Without an edge every Kelly level is "don't trade"
+        let kelly = kelly.max(0.0);
+        levels.push(level("full", kelly));
+        levels.push(level("half", kelly / 2.0));
+        levels.push(level("quarter", kelly / 4.0));
+    }
+    levels.push(level("current", current_r_percent));
+
+    KellyStats {
+        key: key.to_string(),
+        sample_size,
+        wins: wins.len() as i32,
+        losses: losses.len() as i32,
+        breakevens,
+        win_rate: win_rate * 100.0,
+        avg_win_r,
+        avg_loss_r,
+        payoff_ratio: if avg_loss_r > 0.0 { Some(avg_win_r / avg_loss_r) } else { None },
+        full_kelly,
+        levels,
+        current_vs_full_kelly: full_kelly.filter(|k| *k > 0.0).map(|k| current_r_percent / k),
+    }
+}
+
+/// Kelly-optimal risk fractions from closed trades' R multiples, overall and per pair
+#[tauri::command]
+pub async fn get_kelly_analysis(
+    db: State<'_, Database>,
+    date_range: Option<String>,
+    start_date: Option<i64>,
+    end_date: Option<i64>,
+    tag_ids: Option<Vec<String>>,
+) -> Result<KellyAnalysis, String> {
+    let conn = db.conn.lock().map_err(|e| e.to_string())?;
+
+    let current_r_percent: f64 = conn
+        .query_row("SELECT current_r_percent FROM settings WHERE id = 1", [], |row| row.get(0))
+        .map_err(|e| format!("Failed to load settings: {}", e))?;
+
+    let tz = load_timezone(&conn);
+    let window = DateWindow::resolve(date_range.as_deref(), start_date, end_date, &tz);
+    let (date_filter, date_params) = trade_filter(window, &TagFilter::new(tag_ids));
+
+    let mut stmt = conn.prepare(&format!(
+        "SELECT pair, pnl_in_r FROM trades
+         WHERE deleted_at IS NULL AND status IN ('WIN', 'LOSS', 'BE') AND pnl_in_r IS NOT NULL {}",
+        date_filter
+    )).map_err(|e| e.to_string())?;
+
+    let samples = stmt
+        .query_map(rusqlite::params_from_iter(date_params.iter()), |row| {
+            Ok((row.get::<_, String>(0)?, row.get::<_, f64>(1)?))
+        })
+        .map_err(|e| e.to_string())?
+        .collect::<Result<Vec<_>, _>>()
+        .map_err(|e| e.to_string())?;
+
+    let mut by_pair_samples: BTreeMap<String, Vec<f64>> = BTreeMap::new();
+    for (pair, r) in &samples {
+        by_pair_samples.entry(pair.clone()).or_default().push(*r);
+    }
+
+    let all: Vec<f64> = samples.iter().map(|(_, r)| *r).collect();
+    let mut by_pair: Vec<KellyStats> = by_pair_samples
+        .iter()
+        .map(|(pair, rs)| compute_kelly(pair, rs, current_r_percent))
+        .collect();
+    by_pair.sort_by_key(|stats| std::cmp::Reverse(stats.sample_size));
+
+    Ok(KellyAnalysis {
+        current_r_percent,
+        overall: compute_kelly("ALL", &all, current_r_percent),
+        by_pair,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn level<'a>(stats: &'a KellyStats, label: &str) -> &'a KellyLevel {
+        stats.levels.iter().find(|l| l.label == label).unwrap()
+    }
+
+    #[test]
+    fn test_kelly_matches_classic_formula() {
+        // 50% win rate, wins 2R, losses 1R: f* = p - q / b = 0.5 - 0.5 / 2 = 0.25
+        let stats = compute_kelly("ALL", &[2.0, -1.0, 2.0, -1.0], 0.02);
+
+        assert!((stats.full_kelly.unwrap() - 0.25).abs() < 1e-9);
+        assert!((stats.payoff_ratio.unwrap() - 2.0).abs() < 1e-9);
+        assert!((level(&stats, "half").r_percent - 0.125).abs() < 1e-9);
+        assert!((level(&stats, "quarter").r_percent - 0.0625).abs() < 1e-9);
+        assert!((stats.current_vs_full_kelly.unwrap() - 0.08).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_full_kelly_maximizes_growth() {
+        let stats = compute_kelly("ALL", &[2.0, -1.0, 1.5, -1.0, 0.0], 0.02);
+        let full = level(&stats, "full");
+        let full_growth = full.growth_rate.unwrap();
+
+        for f in [full.r_percent * 0.5, full.r_percent * 0.9, full.r_percent * 1.1, full.r_percent * 1.5] {
+            let growth = growth_rate(0.4, 1.75, 0.4, 1.0, f).unwrap();
+            assert!(growth < full_growth);
+        }
+        assert!(level(&stats, "half").growth_rate.unwrap() > 0.0);
+    }
+
+    #[test]
+    fn test_no_edge_and_missing_losses() {
+        let losing = compute_kelly("BTC/USDT", &[1.0, -1.0, -1.0], 0.02);
+        assert!(losing.full_kelly.unwrap() < 0.0);
+        assert_eq!(level(&losing, "full").r_percent, 0.0);
+        assert!(losing.current_vs_full_kelly.is_none());
+        assert!(level(&losing, "current").growth_rate.unwrap() < 0.0);
+
+        let only_wins = compute_kelly("ETH/USDT", &[1.0, 2.0], 0.02);
+        assert!(only_wins.full_kelly.is_none());
+        assert_eq!(only_wins.levels.len(), 1);
+
+        // Risking 100% with a 1R loss wipes out equity
+        let wiped = compute_kelly("ALL", &[2.0, -1.0], 1.0);
+        assert!(level(&wiped, "current").growth_rate.is_none());
+    }
+}
@@ -1,21 +1,55 @@
+pub mod adherence;
 pub mod api_sync;
+pub mod attachments;
+pub mod calendar;
+pub mod capital;
+pub mod comparison;
+pub mod daily_stats;
 pub mod debug;
+pub mod exposure;
+pub mod goals;
+pub mod holding_time;
 pub mod import;
+pub mod kelly;
 pub mod live_mirror;
+pub mod monte_carlo;
 pub mod open_orders;
 pub mod positions;
+pub mod review;
+pub mod revisions;
+pub mod risk;
+pub mod rolling;
+pub mod search;
 pub mod settings;
 pub mod stats;
 pub mod sync_scheduler;
+pub mod tags;
 pub mod trades;
 
+pub use adherence::*;
 pub use api_sync::*;
+pub use attachments::*;
+pub use calendar::*;
+pub use capital::*;
+pub use comparison::*;
+pub use daily_stats::*;
 pub use debug::*;
+pub use exposure::*;
+pub use goals::*;
+pub use holding_time::*;
 pub use import::*;
+pub use kelly::*;
 pub use live_mirror::*;
+pub use monte_carlo::*;
 pub use open_orders::*;
 pub use positions::*;
+pub use review::*;
+pub use revisions::*;
+pub use risk::*;
+pub use rolling::*;
+pub use search::*;
 pub use settings::*;
 pub use stats::*;
 pub use sync_scheduler::*;
+pub use tags::*;
 pub use trades::*;
@@ -0,0 +1,302 @@
+use tauri::State;
+use crate::commands::capital::load_account_balance;
+use crate::commands::settings::load_timezone;
+use crate::commands::stats::{trade_filter, DateWindow, TagFilter};
+use crate::db::Database;
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+use serde::{Deserialize, Serialize};
+
+const DEFAULT_NUM_TRADES: u32 = 100;
+const DEFAULT_NUM_PATHS: u32 = 1000;
+const DEFAULT_DRAWDOWN_THRESHOLD_PERCENT: f64 = 20.0;
+const DEFAULT_RUIN_THRESHOLD_PERCENT: f64 = 50.0;
+/// Upper bound on num_trades * num_paths so a simulation stays responsive and memory-bounded
+const MAX_SIMULATED_STEPS: u64 = 2_000_000;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MonteCarloBand {
+    pub trade_index: u32, // 0 = starting equity
+    pub p5: f64,
+    pub p25: f64,
+    pub p50: f64,
+    pub p75: f64,
+    pub p95: f64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MonteCarloResult {
+    pub seed: u32, // pass back in to reproduce the same paths (u32 so it survives a JS number)
+    pub sample_size: i32,
+    pub num_trades: u32,
+    pub num_paths: u32,
+    pub r_percent: f64, // fraction of equity risked per trade (0.02 = 2%)
+    pub starting_equity: f64,
+    pub bands: Vec<MonteCarloBand>,
+    pub drawdown_threshold_percent: f64,
+    pub drawdown_probability: f64, // % of paths whose max drawdown reached the threshold
+    pub ruin_threshold_percent: f64,
+    pub risk_of_ruin: f64, // % of paths that lost the ruin threshold of starting equity
+    pub median_max_drawdown_percent: f64,
+    pub median_final_return_percent: f64,
+}
+
+#[derive(Debug, Clone)]
+struct SimulationParams {
+    num_trades: u32,
+    num_paths: u32,
+    r_percent: f64,
+    starting_equity: f64,
+    drawdown_threshold_percent: f64,
+    ruin_threshold_percent: f64,
+    seed: u32,
+}
+
+/// Linear-interpolated percentile of an ascending sorted slice (p in 0..=100)
+pub(crate) fn percentile(sorted: &[f64], p: f64) -> f64 {
+    if sorted.is_empty() {
+        return 0.0;
+    }
+    let rank = (p / 100.0) * (sorted.len() - 1) as f64;
+    let lower = rank.floor() as usize;
+    let upper = rank.ceil() as usize;
+    let weight = rank - lower as f64;
+    sorted[lower] + (sorted[upper] - sorted[lower]) * weight
+}
+
+/// Bootstrap the R distribution into compounding equity paths risking r_percent of equity per trade
+fn simulate(samples: &[f64], params: &SimulationParams) -> MonteCarloResult {
+    let mut rng = StdRng::seed_from_u64(params.seed as u64);
+    let steps = params.num_trades as usize + 1;
+    let paths = params.num_paths as usize;
+    let ruin_level = params.starting_equity * (1.0 - params.ruin_threshold_percent / 100.0);
+
+    // equity_by_step[t][p] = equity of path p after t trades
+    let mut equity_by_step = vec![vec![0.0; paths]; steps];
+    let mut max_drawdowns = Vec::with_capacity(paths);
+    let mut ruined_paths = 0;
+
+    for path in 0..paths {
+        let mut equity = params.starting_equity;
+        let mut peak = equity;
+        let mut max_drawdown_percent: f64 = 0.0;
+        let mut ruined = false;
+        equity_by_step[0][path] = equity;
+
+        for step in equity_by_step.iter_mut().skip(1) {
+            let r = samples[rng.gen_range(0..samples.len())];
+            // A loss can't take more than the remaining equity
+            equity = (equity * (1.0 + params.r_percent * r)).max(0.0);
+            step[path] = equity;
+
+            peak = peak.max(equity);
+            if peak > 0.0 {
+                max_drawdown_percent = max_drawdown_percent.max((peak - equity) / peak * 100.0);
+            }
+            if equity <= ruin_level {
+                ruined = true;
+            }
+        }
+
+        max_drawdowns.push(max_drawdown_percent);
+        if ruined {
+            ruined_paths += 1;
+        }
+    }
+
+    let bands = equity_by_step
+        .iter_mut()
+        .enumerate()
+        .map(|(trade_index, values)| {
+            values.sort_by(|a, b| a.total_cmp(b));
+            MonteCarloBand {
+                trade_index: trade_index as u32,
+                p5: percentile(values, 5.0),
+                p25: percentile(values, 25.0),
+                p50: percentile(values, 50.0),
+                p75: percentile(values, 75.0),
+                p95: percentile(values, 95.0),
+            }
+        })
+        .collect::<Vec<_>>();
+
+    let drawdown_hits = max_drawdowns
+        .iter()
+        .filter(|dd| **dd >= params.drawdown_threshold_percent)
+        .count();
+    max_drawdowns.sort_by(|a, b| a.total_cmp(b));
+
+    let median_final = bands.last().map(|b| b.p50).unwrap_or(params.starting_equity);
+    let median_final_return_percent = if params.starting_equity > 0.0 {
+        (median_final - params.starting_equity) / params.starting_equity * 100.0
+    } else {
+        0.0
+    };
+
+    MonteCarloResult {
+        seed: params.seed,
+        sample_size: samples.len() as i32,
+        num_trades: params.num_trades,
+        num_paths: params.num_paths,
+        r_percent: params.r_percent,
+        starting_equity: params.starting_equity,
+        bands,
+        drawdown_threshold_percent: params.drawdown_threshold_percent,
+        drawdown_probability: drawdown_hits as f64 / paths as f64 * 100.0,
+        ruin_threshold_percent: params.ruin_threshold_percent,
+        risk_of_ruin: ruined_paths as f64 / paths as f64 * 100.0,
+        median_max_drawdown_percent: percentile(&max_drawdowns, 50.0),
+        median_final_return_percent,
+    }
+}
+
+/// Monte Carlo simulation of future equity by resampling historical pnl_in_r values.
+/// r_percent defaults to settings.current_r_percent, starting equity to the ledger-aware account balance.
+#[tauri::command]
+#[allow(clippy::too_many_arguments)]
+pub async fn run_monte_carlo_simulation(
+    db: State<'_, Database>,
+    date_range: Option<String>,
+    start_date: Option<i64>,
+    end_date: Option<i64>,
+    num_trades: Option<u32>,
+    num_paths: Option<u32>,
+    r_percent: Option<f64>,
+    drawdown_threshold_percent: Option<f64>,
+    ruin_threshold_percent: Option<f64>,
+    seed: Option<u32>,
+    tag_ids: Option<Vec<String>>,
+) -> Result<MonteCarloResult, String> {
+    let num_trades = num_trades.unwrap_or(DEFAULT_NUM_TRADES);
+    let num_paths = num_paths.unwrap_or(DEFAULT_NUM_PATHS);
+    if num_trades == 0 || num_paths == 0 {
+        return Err("num_trades and num_paths must be greater than zero".to_string());
+    }
+    if num_trades as u64 * num_paths as u64 > MAX_SIMULATED_STEPS {
+        return Err(format!(
+            "Simulation too large: num_trades * num_paths must not exceed {}",
+            MAX_SIMULATED_STEPS
+        ));
+    }
+
+    let drawdown_threshold_percent = drawdown_threshold_percent.unwrap_or(DEFAULT_DRAWDOWN_THRESHOLD_PERCENT);
+    let ruin_threshold_percent = ruin_threshold_percent.unwrap_or(DEFAULT_RUIN_THRESHOLD_PERCENT);
+    for (name, value) in [
+        ("drawdown_threshold_percent", drawdown_threshold_percent),
+        ("ruin_threshold_percent", ruin_threshold_percent),
+    ] {
+        if !(value > 0.0 && value <= 100.0) {
+            return Err(format!("{} must be between 0 and 100", name));
+        }
+    }
+
+    let conn = db.conn.lock().map_err(|e| e.to_string())?;
+
+    let current_r_percent: f64 = conn.query_row(
+        "SELECT current_r_percent FROM settings WHERE id = 1",
+        [],
+        |row| row.get(0),
+    ).map_err(|e| e.to_string())?;
+    let starting_equity = load_account_balance(&conn)?.balance;
+
+    let r_percent = r_percent.unwrap_or(current_r_percent);
+    if !(r_percent > 0.0 && r_percent < 1.0) {
+        return Err("r_percent must be a fraction between 0 and 1".to_string());
+    }
+
+    let tz = load_timezone(&conn);
+    let window = DateWindow::resolve(date_range.as_deref(), start_date, end_date, &tz);
+    let (date_filter, date_params) = trade_filter(window, &TagFilter::new(tag_ids));
+
+    let mut stmt = conn.prepare(&format!(
+        "SELECT pnl_in_r FROM trades
+         WHERE deleted_at IS NULL AND status IN ('WIN', 'LOSS', 'BE') AND pnl_in_r IS NOT NULL {}",
+        date_filter
+    )).map_err(|e| e.to_string())?;
+
+    let samples = stmt
+        .query_map(rusqlite::params_from_iter(date_params.iter()), |row| row.get::<_, f64>(0))
+        .map_err(|e| e.to_string())?
+        .collect::<Result<Vec<_>, _>>()
+        .map_err(|e| e.to_string())?;
+
+    if samples.is_empty() {
+        return Err("No closed trades with an R multiple to resample".to_string());
+    }
+
+    let params = SimulationParams {
+        num_trades,
+        num_paths,
+        r_percent,
+        starting_equity,
+        drawdown_threshold_percent,
+        ruin_threshold_percent,
+        seed: seed.unwrap_or_else(rand::random),
+    };
+
+    Ok(simulate(&samples, &params))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn params(seed: u32) -> SimulationParams {
+        SimulationParams {
+            num_trades: 50,
+            num_paths: 200,
+            r_percent: 0.02,
+            starting_equity: 10000.0,
+            drawdown_threshold_percent: 10.0,
+            ruin_threshold_percent: 50.0,
+            seed,
+        }
+    }
+
+    #[test]
+    fn test_percentile_interpolates() {
+        let values = [1.0, 2.0, 3.0, 4.0, 5.0];
+        assert_eq!(percentile(&values, 0.0), 1.0);
+        assert_eq!(percentile(&values, 50.0), 3.0);
+        assert_eq!(percentile(&values, 100.0), 5.0);
+        assert!((percentile(&values, 25.0) - 2.0).abs() < 1e-9);
+        assert!((percentile(&values, 10.0) - 1.4).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_simulation_is_reproducible_with_seed() {
+        let samples = [2.0, -1.0, -1.0, 3.0, 0.0];
+        let a = simulate(&samples, &params(42));
+        let b = simulate(&samples, &params(42));
+        let c = simulate(&samples, &params(7));
+
+        assert_eq!(a.bands.len(), 51);
+        assert_eq!(a.bands[0].p50, 10000.0);
+        assert_eq!(a.bands.last().unwrap().p50, b.bands.last().unwrap().p50);
+        assert_eq!(a.drawdown_probability, b.drawdown_probability);
+        assert_ne!(a.bands.last().unwrap().p95, c.bands.last().unwrap().p95);
+    }
+
+    #[test]
+    fn test_bands_are_ordered() {
+        let samples = [2.0, -1.0, -1.0, 3.0, 0.0];
+        let result = simulate(&samples, &params(1));
+        for band in &result.bands {
+            assert!(band.p5 <= band.p25 && band.p25 <= band.p50);
+            assert!(band.p50 <= band.p75 && band.p75 <= band.p95);
+        }
+    }
+
+    #[test]
+    fn test_all_losses_hit_drawdown_and_ruin() {
+        // Losing 1R at 2% risk compounds to 0.98^50 ≈ 36% of starting equity
+        let result = simulate(&[-1.0], &params(3));
+        assert_eq!(result.drawdown_probability, 100.0);
+        assert_eq!(result.risk_of_ruin, 100.0);
+        assert!((result.median_final_return_percent - (0.98f64.powi(50) - 1.0) * 100.0).abs() < 1e-6);
+
+        let winners = simulate(&[1.0], &params(3));
+        assert_eq!(winners.drawdown_probability, 0.0);
+        assert_eq!(winners.risk_of_ruin, 0.0);
+    }
+}
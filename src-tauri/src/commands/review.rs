@@ -0,0 +1,503 @@
+use tauri::State;
+use crate::commands::revisions::{record_revision, trade_snapshot};
+use crate::commands::settings::load_timezone;
+use crate::commands::stats::{trade_filter, DateWindow, TagFilter};
+use crate::db::Database;
+use crate::models::{CreateReviewLabelInput, ReviewLabel, TradeReview, TradeReviewLabel, UpdateReviewLabelInput};
+use chrono::Utc;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ReviewGroupStats {
+    pub trade_count: i32,
+    pub wins: i32,
+    pub losses: i32,
+    pub win_rate: f64,     // wins / (wins + losses), %
+    pub total_pnl: f64,    // net of fees and funding
+    pub avg_pnl: f64,
+    pub total_r: f64,
+    pub avg_r: Option<f64>, // None when no trade has pnl_in_r
+}
+
+/// What a mistake (or emotion) cost compared to clean trades
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LabelCost {
+    pub label: ReviewLabel,
+    pub stats: ReviewGroupStats,
+    // (clean average - labelled average) * labelled trades, positive = given up by the mistake.
+    // The clean average is 0 while no reviewed trade is free of mistakes.
+    pub cost_pnl: f64,
+    pub cost_r: Option<f64>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ExecutionScoreBucket {
+    pub score: i32,
+    pub stats: ReviewGroupStats,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ReviewCostReport {
+    pub reviewed_trades: i32,   // closed trades with a score, a mistake or an emotion
+    pub unreviewed_trades: i32,
+    pub clean: ReviewGroupStats, // reviewed trades without any mistake
+    pub mistakes: Vec<LabelCost>, // costliest first
+    pub emotions: Vec<LabelCost>,
+    pub by_execution_score: Vec<ExecutionScoreBucket>,
+}
+
+/// Closed trade with its review, for cost stats
+#[derive(Debug, Clone)]
+struct ReviewTrade {
+    status: String,
+    net_pnl: f64,
+    pnl_in_r: Option<f64>,
+    execution_score: Option<i32>,
+    label_ids: Vec<String>,
+}
+
+fn map_row_to_label(row: &rusqlite::Row) -> rusqlite::Result<ReviewLabel> {
+    Ok(ReviewLabel {
+        id: row.get("id")?,
+        name: row.get("name")?,
+        category: row.get("category")?,
+        description: row.get("description")?,
+        active: row.get::<_, i32>("active")? == 1,
+        created_at: row.get("created_at")?,
+        updated_at: row.get("updated_at")?,
+    })
+}
+
+/// Trimmed label name, or an error for an invalid name or category
+fn validate_label(name: &str, category: &str) -> Result<String, String> {
+    if category != "MISTAKE" && category != "EMOTION" {
+        return Err(format!("Invalid review category: {} (expected MISTAKE or EMOTION)", category));
+    }
+    let name = name.trim();
+    if name.is_empty() {
+        return Err("Name cannot be empty".to_string());
+    }
+    Ok(name.to_string())
+}
+
+pub(crate) fn validate_execution_score(score: Option<i64>) -> Result<(), String> {
+    match score {
+        Some(score) if !(1..=5).contains(&score) => {
+            Err(format!("Execution score must be between 1 and 5, got {}", score))
+        }
+        _ => Ok(()),
+    }
+}
+
+fn label_write_error(e: rusqlite::Error, name: &str, category: &str) -> String {
+    match e {
+        rusqlite::Error::SqliteFailure(err, _) if err.code == rusqlite::ErrorCode::ConstraintViolation => {
+            format!("A {} named '{}' already exists", category.to_lowercase(), name)
+        }
+        e => e.to_string(),
+    }
+}
+
+fn get_label_by_id(conn: &rusqlite::Connection, id: &str) -> Result<ReviewLabel, String> {
+    conn.query_row("SELECT * FROM review_labels WHERE id = ?", [id], map_row_to_label)
+        .map_err(|e| match e {
+            rusqlite::Error::QueryReturnedNoRows => format!("Review label not found: {}", id),
+            e => e.to_string(),
+        })
+}
+
+pub(crate) fn load_trade_review(conn: &rusqlite::Connection, trade_id: &str) -> Result<TradeReview, String> {
+    let execution_score: Option<i32> = conn
+        .query_row("SELECT execution_score FROM trades WHERE id = ?", [trade_id], |row| row.get(0))
+        .map_err(|e| match e {
+            rusqlite::Error::QueryReturnedNoRows => format!("Trade not found: {}", trade_id),
+            e => e.to_string(),
+        })?;
+
+    let mut stmt = conn.prepare(
+        "SELECT review_labels.* FROM review_labels
+         JOIN trade_review_labels ON trade_review_labels.label_id = review_labels.id
+         WHERE trade_review_labels.trade_id = ?
+         ORDER BY review_labels.name ASC"
+    ).map_err(|e| e.to_string())?;
+
+    let labels = stmt
+        .query_map([trade_id], map_row_to_label)
+        .map_err(|e| e.to_string())?
+        .collect::<Result<Vec<_>, _>>()
+        .map_err(|e| e.to_string())?;
+    let (mistakes, emotions) = labels.into_iter().partition(|label| label.category == "MISTAKE");
+
+    Ok(TradeReview {
+        trade_id: trade_id.to_string(),
+        execution_score,
+        mistakes,
+        emotions,
+    })
+}
+
+/// Replace a trade's execution score and review labels
+pub(crate) fn save_trade_review(
+    conn: &rusqlite::Connection,
+    trade_id: &str,
+    label_ids: &[String],
+    execution_score: Option<i32>,
+) -> Result<(), String> {
+    validate_execution_score(execution_score.map(i64::from))?;
+
+    let now = Utc::now().timestamp();
+    let updated = conn.execute(
+        "UPDATE trades SET execution_score = ?, updated_at = ? WHERE id = ?",
+        rusqlite::params![execution_score, now, trade_id],
+    ).map_err(|e| e.to_string())?;
+    if updated == 0 {
+        return Err(format!("Trade not found: {}", trade_id));
+    }
+
+    conn.execute("DELETE FROM trade_review_labels WHERE trade_id = ?", [trade_id])
+        .map_err(|e| e.to_string())?;
+    for label_id in label_ids {
+        get_label_by_id(conn, label_id)?;
+        conn.execute(
+            "INSERT OR IGNORE INTO trade_review_labels (trade_id, label_id, created_at) VALUES (?, ?, ?)",
+            rusqlite::params![trade_id, label_id, now],
+        ).map_err(|e| e.to_string())?;
+    }
+    Ok(())
+}
+
+/// The whole catalogue and every trade link, for backups
+pub(crate) fn load_all_review_labels(
+    conn: &rusqlite::Connection,
+) -> Result<(Vec<ReviewLabel>, Vec<TradeReviewLabel>), String> {
+    let mut stmt = conn
+        .prepare("SELECT * FROM review_labels ORDER BY created_at ASC")
+        .map_err(|e| e.to_string())?;
+    let labels = stmt
+        .query_map([], map_row_to_label)
+        .map_err(|e| e.to_string())?
+        .collect::<Result<Vec<_>, _>>()
+        .map_err(|e| e.to_string())?;
+
+    let mut stmt = conn
+        .prepare("SELECT trade_id, label_id, created_at FROM trade_review_labels ORDER BY trade_id, label_id")
+        .map_err(|e| e.to_string())?;
+    let links = stmt
+        .query_map([], |row| {
+            Ok(TradeReviewLabel {
+                trade_id: row.get(0)?,
+                label_id: row.get(1)?,
+                created_at: row.get(2)?,
+            })
+        })
+        .map_err(|e| e.to_string())?
+        .collect::<Result<Vec<_>, _>>()
+        .map_err(|e| e.to_string())?;
+
+    Ok((labels, links))
+}
+
+/// Mistakes and emotions, optionally one category, active ones unless `include_inactive`
+#[tauri::command]
+pub async fn get_review_labels(
+    db: State<'_, Database>,
+    category: Option<String>,
+    include_inactive: Option<bool>,
+) -> Result<Vec<ReviewLabel>, String> {
+    let conn = db.conn.lock().map_err(|e| e.to_string())?;
+
+    let mut stmt = conn.prepare(
+        "SELECT * FROM review_labels
+         WHERE (?1 IS NULL OR category = ?1) AND (?2 = 1 OR active = 1)
+         ORDER BY category DESC, name ASC"
+    ).map_err(|e| e.to_string())?;
+
+    let labels = stmt
+        .query_map(rusqlite::params![category, include_inactive.unwrap_or(false) as i32], map_row_to_label)
+        .map_err(|e| e.to_string())?
+        .collect::<Result<Vec<_>, _>>()
+        .map_err(|e| e.to_string())?;
+
+    Ok(labels)
+}
+
+#[tauri::command]
+pub async fn create_review_label(
+    db: State<'_, Database>,
+    label: CreateReviewLabelInput,
+) -> Result<ReviewLabel, String> {
+    let name = validate_label(&label.name, &label.category)?;
+
+    let conn = db.conn.lock().map_err(|e| e.to_string())?;
+    let id = format!("{}-{}-{}", label.category, Utc::now().timestamp_millis(), uuid::Uuid::new_v4());
+    let now = Utc::now().timestamp();
+
+    conn.execute(
+        "INSERT INTO review_labels (id, name, category, description, active, created_at, updated_at)
+         VALUES (?, ?, ?, ?, 1, ?, ?)",
+        rusqlite::params![id, name, label.category, label.description.trim(), now, now],
+    ).map_err(|e| label_write_error(e, &name, &label.category))?;
+
+    get_label_by_id(&conn, &id)
+}
+
+/// Rename, describe or (de)activate a label. The category is fixed once trades use it
+#[tauri::command]
+pub async fn update_review_label(
+    db: State<'_, Database>,
+    id: String,
+    label: UpdateReviewLabelInput,
+) -> Result<ReviewLabel, String> {
+    let conn = db.conn.lock().map_err(|e| e.to_string())?;
+    let existing = get_label_by_id(&conn, &id)?;
+
+    let name = validate_label(&label.name.unwrap_or(existing.name), &existing.category)?;
+    let description = label.description.unwrap_or(existing.description);
+    let active = label.active.unwrap_or(existing.active);
+
+    conn.execute(
+        "UPDATE review_labels SET name = ?, description = ?, active = ?, updated_at = ? WHERE id = ?",
+        rusqlite::params![name, description.trim(), active as i32, Utc::now().timestamp(), id],
+    ).map_err(|e| label_write_error(e, &name, &existing.category))?;
+
+    get_label_by_id(&conn, &id)
+}
+
+/// Delete a label and remove it from every trade, deactivate it to keep its history instead
+#[tauri::command]
+pub async fn delete_review_label(db: State<'_, Database>, id: String) -> Result<(), String> {
+    let conn = db.conn.lock().map_err(|e| e.to_string())?;
+    conn.execute("DELETE FROM trade_review_labels WHERE label_id = ?", [&id])
+        .map_err(|e| e.to_string())?;
+    let deleted = conn
+        .execute("DELETE FROM review_labels WHERE id = ?", [&id])
+        .map_err(|e| e.to_string())?;
+    if deleted == 0 {
+        return Err(format!("Review label not found: {}", id));
+    }
+    Ok(())
+}
+
+#[tauri::command]
+pub async fn get_trade_review(db: State<'_, Database>, trade_id: String) -> Result<TradeReview, String> {
+    let conn = db.conn.lock().map_err(|e| e.to_string())?;
+    load_trade_review(&conn, &trade_id)
+}
+
+/// Replace the execution score (1-5, null to clear) and the mistakes/emotions of a trade
+#[tauri::command]
+pub async fn set_trade_review(
+    db: State<'_, Database>,
+    trade_id: String,
+    label_ids: Vec<String>,
+    execution_score: Option<i32>,
+) -> Result<TradeReview, String> {
+    let mut conn = db.conn.lock().map_err(|e| e.to_string())?;
+
+    let tx = conn.transaction().map_err(|e| e.to_string())?;
+    let before = trade_snapshot(&tx, &trade_id)?;
+    save_trade_review(&tx, &trade_id, &label_ids, execution_score)?;
+    record_revision(&tx, &trade_id, "UPDATE", "USER", before)?;
+    tx.commit().map_err(|e| e.to_string())?;
+
+    load_trade_review(&conn, &trade_id)
+}
+
+fn group_stats<'a>(trades: impl Iterator<Item = &'a ReviewTrade>) -> ReviewGroupStats {
+    let mut stats = ReviewGroupStats::default();
+    let mut r_count = 0;
+    for trade in trades {
+        stats.trade_count += 1;
+        match trade.status.as_str() {
+            "WIN" => stats.wins += 1,
+            "LOSS" => stats.losses += 1,
+            _ => {}
+        }
+        stats.total_pnl += trade.net_pnl;
+        if let Some(r) = trade.pnl_in_r {
+            stats.total_r += r;
+            r_count += 1;
+        }
+    }
+
+    let decided = stats.wins + stats.losses;
+    if decided > 0 {
+        stats.win_rate = stats.wins as f64 / decided as f64 * 100.0;
+    }
+    if stats.trade_count > 0 {
+        stats.avg_pnl = stats.total_pnl / stats.trade_count as f64;
+    }
+    if r_count > 0 {
+        stats.avg_r = Some(stats.total_r / r_count as f64);
+    }
+    stats
+}
+
+fn compute_review_costs(trades: &[ReviewTrade], labels: &[ReviewLabel]) -> ReviewCostReport {
+    let categories: HashMap<&str, &str> = labels
+        .iter()
+        .map(|label| (label.id.as_str(), label.category.as_str()))
+        .collect();
+    let has_mistake = |trade: &ReviewTrade| {
+        trade.label_ids.iter().any(|id| categories.get(id.as_str()) == Some(&"MISTAKE"))
+    };
+
+    let reviewed: Vec<&ReviewTrade> = trades
+        .iter()
+        .filter(|t| t.execution_score.is_some() || !t.label_ids.is_empty())
+        .collect();
+    let clean = group_stats(reviewed.iter().copied().filter(|t| !has_mistake(t)));
+
+    let mut mistakes = Vec::new();
+    let mut emotions = Vec::new();
+    for label in labels {
+        let stats = group_stats(reviewed.iter().copied().filter(|t| t.label_ids.contains(&label.id)));
+        if stats.trade_count == 0 {
+            continue;
+        }
+        let count = stats.trade_count as f64;
+        let cost = LabelCost {
+            label: label.clone(),
+            cost_pnl: (clean.avg_pnl - stats.avg_pnl) * count,
+            cost_r: stats.avg_r.map(|avg_r| (clean.avg_r.unwrap_or(0.0) - avg_r) * count),
+            stats,
+        };
+        if label.category == "MISTAKE" {
+            mistakes.push(cost);
+        } else {
+            emotions.push(cost);
+        }
+    }
+    mistakes.sort_by(|a, b| b.cost_pnl.total_cmp(&a.cost_pnl));
+    emotions.sort_by(|a, b| b.cost_pnl.total_cmp(&a.cost_pnl));
+
+    let by_execution_score = (1..=5)
+        .map(|score| ExecutionScoreBucket {
+            score,
+            stats: group_stats(reviewed.iter().copied().filter(|t| t.execution_score == Some(score))),
+        })
+        .filter(|bucket| bucket.stats.trade_count > 0)
+        .collect();
+
+    ReviewCostReport {
+        reviewed_trades: reviewed.len() as i32,
+        unreviewed_trades: (trades.len() - reviewed.len()) as i32,
+        clean,
+        mistakes,
+        emotions,
+        by_execution_score,
+    }
+}
+
+/// PnL and R cost of each mistake and emotion over closed trades, plus results by execution score
+#[tauri::command]
+pub async fn get_review_costs(
+    db: State<'_, Database>,
+    date_range: Option<String>,
+    start_date: Option<i64>,
+    end_date: Option<i64>,
+    tag_ids: Option<Vec<String>>,
+) -> Result<ReviewCostReport, String> {
+    let conn = db.conn.lock().map_err(|e| e.to_string())?;
+
+    let tz = load_timezone(&conn);
+    let window = DateWindow::resolve(date_range.as_deref(), start_date, end_date, &tz);
+    let (filter, params) = trade_filter(window, &TagFilter::new(tag_ids));
+
+    let mut label_ids: HashMap<String, Vec<String>> = HashMap::new();
+    let (labels, links) = load_all_review_labels(&conn)?;
+    for link in links {
+        label_ids.entry(link.trade_id).or_default().push(link.label_id);
+    }
+
+    let mut stmt = conn.prepare(&format!(
+        "SELECT id, status, total_pnl - COALESCE(fees, 0.0) + COALESCE(funding, 0.0), pnl_in_r, execution_score
+         FROM trades
+         WHERE deleted_at IS NULL AND status IN ('WIN', 'LOSS', 'BE') {}",
+        filter
+    )).map_err(|e| e.to_string())?;
+
+    let trades = stmt.query_map(rusqlite::params_from_iter(params.iter()), |row| {
+        let id: String = row.get(0)?;
+        Ok(ReviewTrade {
+            status: row.get(1)?,
+            net_pnl: row.get::<_, Option<f64>>(2)?.unwrap_or(0.0),
+            pnl_in_r: row.get(3)?,
+            execution_score: row.get(4)?,
+            label_ids: label_ids.get(&id).cloned().unwrap_or_default(),
+        })
+    })
+    .map_err(|e| e.to_string())?
+    .collect::<Result<Vec<_>, _>>()
+    .map_err(|e| e.to_string())?;
+
+    Ok(compute_review_costs(&trades, &labels))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn label(id: &str, category: &str) -> ReviewLabel {
+        ReviewLabel {
+            id: id.to_string(),
+            name: id.to_string(),
+            category: category.to_string(),
+            description: String::new(),
+            active: true,
+            created_at: 0,
+            updated_at: 0,
+        }
+    }
+
+    fn trade(status: &str, net_pnl: f64, r: f64, score: Option<i32>, labels: &[&str]) -> ReviewTrade {
+        ReviewTrade {
+            status: status.to_string(),
+            net_pnl,
+            pnl_in_r: Some(r),
+            execution_score: score,
+            label_ids: labels.iter().map(|l| l.to_string()).collect(),
+        }
+    }
+
+    #[test]
+    fn test_mistake_cost_against_clean_trades() {
+        let labels = vec![label("moved-stop", "MISTAKE"), label("fomo", "MISTAKE"), label("calm", "EMOTION")];
+        let trades = vec![
+            trade("WIN", 200.0, 2.0, Some(5), &["calm"]),
+            trade("LOSS", -100.0, -1.0, Some(4), &[]),
+            trade("LOSS", -300.0, -3.0, Some(2), &["moved-stop"]),
+            trade("LOSS", -200.0, -2.0, Some(1), &["moved-stop", "fomo"]),
+            trade("WIN", 150.0, 1.5, None, &[]), // not reviewed
+        ];
+
+        let report = compute_review_costs(&trades, &labels);
+        assert_eq!((report.reviewed_trades, report.unreviewed_trades), (4, 1));
+        assert_eq!(report.clean.trade_count, 2);
+        assert!((report.clean.avg_pnl - 50.0).abs() < 1e-9);
+
+        // Moved stop: avg -250 vs clean +50 over 2 trades
+        let moved = &report.mistakes[0];
+        assert_eq!(moved.label.id, "moved-stop");
+        assert!((moved.cost_pnl - 600.0).abs() < 1e-9);
+        assert!((moved.cost_r.unwrap() - 6.0).abs() < 1e-9);
+        assert!((report.mistakes[1].cost_pnl - 250.0).abs() < 1e-9);
+
+        assert_eq!(report.emotions.len(), 1);
+        assert!((report.emotions[0].cost_pnl + 150.0).abs() < 1e-9);
+        let scores: Vec<i32> = report.by_execution_score.iter().map(|b| b.score).collect();
+        assert_eq!(scores, [1, 2, 4, 5]);
+    }
+
+    #[test]
+    fn test_execution_score_range() {
+        assert!(validate_execution_score(None).is_ok());
+        assert!(validate_execution_score(Some(1)).is_ok());
+        assert!(validate_execution_score(Some(5)).is_ok());
+        assert!(validate_execution_score(Some(0)).is_err());
+        assert!(validate_execution_score(Some(6)).is_err());
+        assert!(validate_label("  FOMO entry ", "MISTAKE").unwrap() == "FOMO entry");
+        assert!(validate_label("Tilt", "MOOD").is_err());
+    }
+}
@@ -0,0 +1,387 @@
+use tauri::State;
+use crate::commands::daily_stats::{refresh_trade_days, trade_close_date};
+use crate::commands::search::reindex_trade;
+use crate::commands::trades::get_trade;
+use crate::db::Database;
+use crate::models::{FieldChange, Trade, TradeRevision};
+use chrono::Utc;
+use rusqlite::types::{Value as SqlValue, ValueRef};
+use rusqlite::OptionalExtension;
+use serde_json::{Map, Value};
+use std::collections::{BTreeSet, HashSet};
+
+/// Column values of a trade by name, plus its linked tag and review label ids
+pub(crate) type TradeSnapshot = Map<String, Value>;
+
+// Bookkeeping columns that change with every write and would bury the real changes
+const UNTRACKED_COLUMNS: [&str; 2] = ["updated_at", "created_at"];
+
+fn sql_to_json(value: ValueRef) -> Value {
+    match value {
+        ValueRef::Null | ValueRef::Blob(_) => Value::Null,
+        ValueRef::Integer(i) => Value::from(i),
+        ValueRef::Real(f) => serde_json::Number::from_f64(f).map(Value::Number).unwrap_or(Value::Null),
+        ValueRef::Text(t) => Value::String(String::from_utf8_lossy(t).into_owned()),
+    }
+}
+
+fn json_to_sql(value: &Value) -> SqlValue {
+    match value {
+        Value::Null => SqlValue::Null,
+        Value::Bool(b) => SqlValue::Integer(*b as i64),
+        Value::Number(n) => match n.as_i64() {
+            Some(i) => SqlValue::Integer(i),
+            None => SqlValue::Real(n.as_f64().unwrap_or_default()),
+        },
+        Value::String(s) => SqlValue::Text(s.clone()),
+        other => SqlValue::Text(other.to_string()),
+    }
+}
+
+fn linked_ids(conn: &rusqlite::Connection, sql: &str, trade_id: &str) -> Result<Value, String> {
+    let mut stmt = conn.prepare(sql).map_err(|e| e.to_string())?;
+    let ids = stmt
+        .query_map([trade_id], |row| row.get::<_, String>(0))
+        .map_err(|e| e.to_string())?
+        .collect::<Result<Vec<_>, _>>()
+        .map_err(|e| e.to_string())?;
+    Ok(Value::from(ids))
+}
+
+/// Current state of a trade, None if it does not exist
+pub(crate) fn trade_snapshot(conn: &rusqlite::Connection, trade_id: &str) -> Result<Option<TradeSnapshot>, String> {
+    let mut stmt = conn.prepare("SELECT * FROM trades WHERE id = ?").map_err(|e| e.to_string())?;
+    let columns: Vec<String> = stmt.column_names().into_iter().map(String::from).collect();
+
+    let snapshot = stmt
+        .query_row([trade_id], |row| {
+            let mut snapshot = Map::new();
+            for (i, column) in columns.iter().enumerate() {
+                if !UNTRACKED_COLUMNS.contains(&column.as_str()) {
+                    snapshot.insert(column.clone(), sql_to_json(row.get_ref(i)?));
+                }
+            }
+            Ok(snapshot)
+        })
+        .optional()
+        .map_err(|e| e.to_string())?;
+
+    let Some(mut snapshot) = snapshot else {
+        return Ok(None);
+    };
+    snapshot.insert(
+        "tag_ids".to_string(),
+        linked_ids(conn, "SELECT tag_id FROM trade_tags WHERE trade_id = ? ORDER BY tag_id", trade_id)?,
+    );
+    snapshot.insert(
+        "review_label_ids".to_string(),
+        linked_ids(conn, "SELECT label_id FROM trade_review_labels WHERE trade_id = ? ORDER BY label_id", trade_id)?,
+    );
+    Ok(Some(snapshot))
+}
+
+fn diff_snapshots(before: Option<&TradeSnapshot>, after: Option<&TradeSnapshot>) -> Vec<FieldChange> {
+    let fields: BTreeSet<&String> = before.into_iter().chain(after).flat_map(|s| s.keys()).collect();
+    fields
+        .into_iter()
+        .filter_map(|field| {
+            let old_value = before.and_then(|s| s.get(field)).cloned().unwrap_or(Value::Null);
+            let new_value = after.and_then(|s| s.get(field)).cloned().unwrap_or(Value::Null);
+            (old_value != new_value).then(|| FieldChange { field: field.clone(), old_value, new_value })
+        })
+        .collect()
+}
+
+/// Fold a newer set of changes into an older one: first old value, last new value
+fn merge_changes(older: Vec<FieldChange>, newer: Vec<FieldChange>) -> Vec<FieldChange> {
+    let mut merged = older;
+    for change in newer {
+        match merged.iter_mut().find(|c| c.field == change.field) {
+            Some(existing) => existing.new_value = change.new_value,
+            None => merged.push(change),
+        }
+    }
+    merged.retain(|c| c.old_value != c.new_value);
+    merged.sort_by(|a, b| a.field.cmp(&b.field));
+    merged
+}
+
+/// Record what a write changed, comparing `before` with the trade's current state.
+/// Writes that change nothing are not recorded, and consecutive live mirror updates
+/// are folded into one revision so polling does not flood the history.
+pub(crate) fn record_revision(
+    conn: &rusqlite::Connection,
+    trade_id: &str,
+    action: &str,
+    source: &str,
+    before: Option<TradeSnapshot>,
+) -> Result<(), String> {
+    let after = trade_snapshot(conn, trade_id)?;
+    let changes = diff_snapshots(before.as_ref(), after.as_ref());
+    if changes.is_empty() {
+        return Ok(());
+    }
+    let now = Utc::now().timestamp();
+
+    if action == "UPDATE" && source == "LIVE_MIRROR" {
+        let latest: Option<(i64, String, String, String)> = conn
+            .query_row(
+                "SELECT id, action, source, changes FROM trade_revisions WHERE trade_id = ? ORDER BY id DESC LIMIT 1",
+                [trade_id],
+                |row| Ok((row.get(0)?, row.get(1)?, row.get(2)?, row.get(3)?)),
+            )
+            .optional()
+            .map_err(|e| e.to_string())?;
+        if let Some((id, latest_action, latest_source, latest_changes)) = latest
+            && latest_action == "UPDATE"
+            && latest_source == "LIVE_MIRROR"
+        {
+            let older: Vec<FieldChange> = serde_json::from_str(&latest_changes).map_err(|e| e.to_string())?;
+            let merged = merge_changes(older, changes);
+            let json = serde_json::to_string(&merged).map_err(|e| e.to_string())?;
+            conn.execute(
+                "UPDATE trade_revisions SET changes = ?, created_at = ? WHERE id = ?",
+                rusqlite::params![json, now, id],
+            ).map_err(|e| e.to_string())?;
+            return Ok(());
+        }
+    }
+
+    let json = serde_json::to_string(&changes).map_err(|e| e.to_string())?;
+    conn.execute(
+        "INSERT INTO trade_revisions (trade_id, action, source, changes, created_at) VALUES (?, ?, ?, ?, ?)",
+        rusqlite::params![trade_id, action, source, json, now],
+    ).map_err(|e| e.to_string())?;
+    Ok(())
+}
+
+fn map_row_to_revision(row: &rusqlite::Row) -> rusqlite::Result<TradeRevision> {
+    let changes: String = row.get("changes")?;
+    Ok(TradeRevision {
+        id: row.get("id")?,
+        trade_id: row.get("trade_id")?,
+        action: row.get("action")?,
+        source: row.get("source")?,
+        changes: serde_json::from_str(&changes).unwrap_or_default(),
+        created_at: row.get("created_at")?,
+    })
+}
+
+pub(crate) fn load_trade_history(conn: &rusqlite::Connection, trade_id: &str) -> Result<Vec<TradeRevision>, String> {
+    let mut stmt = conn
+        .prepare("SELECT * FROM trade_revisions WHERE trade_id = ? ORDER BY id DESC")
+        .map_err(|e| e.to_string())?;
+    let revisions = stmt
+        .query_map([trade_id], map_row_to_revision)
+        .map_err(|e| e.to_string())?
+        .collect::<Result<Vec<_>, _>>()
+        .map_err(|e| e.to_string())?;
+    Ok(revisions)
+}
+
+/// State of the trade right after `revision_id`, rebuilt by undoing every later revision
+fn state_at_revision(
+    current: &TradeSnapshot,
+    history: &[TradeRevision],
+    revision_id: i64,
+) -> TradeSnapshot {
+    let mut state = current.clone();
+    // history is newest first
+    for revision in history.iter().take_while(|r| r.id > revision_id) {
+        for change in &revision.changes {
+            state.insert(change.field.clone(), change.old_value.clone());
+        }
+    }
+    state
+}
+
+/// Replace a trade's links with `ids`, skipping tags or labels deleted since
+fn restore_links(
+    conn: &rusqlite::Connection,
+    link_table: &str,
+    link_column: &str,
+    target_table: &str,
+    trade_id: &str,
+    ids: &Value,
+) -> Result<(), String> {
+    conn.execute(&format!("DELETE FROM {} WHERE trade_id = ?", link_table), [trade_id])
+        .map_err(|e| e.to_string())?;
+    let now = Utc::now().timestamp();
+    for id in ids.as_array().into_iter().flatten().filter_map(Value::as_str) {
+        conn.execute(
+            &format!(
+                "INSERT OR IGNORE INTO {} (trade_id, {}, created_at) SELECT ?, id, ? FROM {} WHERE id = ?",
+                link_table, link_column, target_table
+            ),
+            rusqlite::params![trade_id, now, id],
+        ).map_err(|e| e.to_string())?;
+    }
+    Ok(())
+}
+
+/// Write `target` over the current trade, only touching fields that differ
+fn apply_snapshot(
+    conn: &rusqlite::Connection,
+    trade_id: &str,
+    current: &TradeSnapshot,
+    target: &TradeSnapshot,
+) -> Result<(), String> {
+    let stmt = conn.prepare("SELECT * FROM trades LIMIT 0").map_err(|e| e.to_string())?;
+    let columns: HashSet<String> = stmt.column_names().into_iter().map(String::from).collect();
+
+    let mut updates = vec!["updated_at = ?".to_string()];
+    let mut values = vec![SqlValue::Integer(Utc::now().timestamp())];
+    for (field, value) in target {
+        if current.get(field) == Some(value) {
+            continue;
+        }
+        match field.as_str() {
+            "tag_ids" => restore_links(conn, "trade_tags", "tag_id", "tags", trade_id, value)?,
+            "review_label_ids" => {
+                restore_links(conn, "trade_review_labels", "label_id", "review_labels", trade_id, value)?
+            }
+            // Column names come from the trades table itself, never from the stored revision
+            "id" => {}
+            _ => {
+                if let Some(column) = columns.get(field) {
+                    updates.push(format!("{} = ?", column));
+                    values.push(json_to_sql(value));
+                }
+            }
+        }
+    }
+
+    values.push(SqlValue::Text(trade_id.to_string()));
+    conn.execute(
+        &format!("UPDATE trades SET {} WHERE id = ?", updates.join(", ")),
+        rusqlite::params_from_iter(values.iter()),
+    ).map_err(|e| e.to_string())?;
+    Ok(())
+}
+
+/// Change history of a trade, newest first
+#[tauri::command]
+pub async fn get_trade_history(db: State<'_, Database>, trade_id: String) -> Result<Vec<TradeRevision>, String> {
+    let conn = db.conn.lock().map_err(|e| e.to_string())?;
+    load_trade_history(&conn, &trade_id)
+}
+
+/// Put a trade back in the state it had right after the given revision.
+/// The revert is itself recorded, so it can be undone the same way.
+#[tauri::command]
+pub async fn revert_trade_to_revision(db: State<'_, Database>, revision_id: i64) -> Result<Trade, String> {
+    let trade_id = {
+        let mut conn = db.conn.lock().map_err(|e| e.to_string())?;
+
+        let trade_id: String = conn
+            .query_row("SELECT trade_id FROM trade_revisions WHERE id = ?", [revision_id], |row| row.get(0))
+            .map_err(|e| match e {
+                rusqlite::Error::QueryReturnedNoRows => format!("Revision not found: {}", revision_id),
+                e => e.to_string(),
+            })?;
+
+        let tx = conn.transaction().map_err(|e| e.to_string())?;
+        let current = trade_snapshot(&tx, &trade_id)?.ok_or_else(|| format!("Trade not found: {}", trade_id))?;
+        let history = load_trade_history(&tx, &trade_id)?;
+        let target = state_at_revision(&current, &history, revision_id);
+
+        let previous_close_date = trade_close_date(&tx, &trade_id)?;
+        apply_snapshot(&tx, &trade_id, &current, &target)?;
+        refresh_trade_days(&tx, &trade_id, previous_close_date)?;
+        record_revision(&tx, &trade_id, "REVERT", "USER", Some(current))?;
+        reindex_trade(&tx, &trade_id, false)?;
+        tx.commit().map_err(|e| e.to_string())?;
+
+        trade_id
+    };
+
+    get_trade(db, trade_id).await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::db::{insert_test_trade, test_connection};
+    use rusqlite::Connection;
+
+    fn setup() -> Connection {
+        let conn = test_connection();
+        for id in ["breakout", "news"] {
+            conn.execute(
+                "INSERT INTO tags (id, name, created_at, updated_at) VALUES (?1, ?1, 0, 0)",
+                [id],
+            ).unwrap();
+        }
+        conn
+    }
+
+    fn update(conn: &Connection, source: &str, sql: &str) {
+        let before = trade_snapshot(conn, "t1").unwrap();
+        conn.execute_batch(sql).unwrap();
+        record_revision(conn, "t1", "UPDATE", source, before).unwrap();
+    }
+
+    #[test]
+    fn test_revisions_record_field_changes() {
+        let conn = setup();
+        insert_test_trade(&conn, "t1", &[("notes", &"plan")]);
+        record_revision(&conn, "t1", "CREATE", "USER", None).unwrap();
+        update(&conn, "USER", "UPDATE trades SET notes = 'plan + review', updated_at = 5 WHERE id = 't1'");
+        update(&conn, "USER", "UPDATE trades SET updated_at = 9 WHERE id = 't1'"); // nothing tracked changed
+
+        let history = load_trade_history(&conn, "t1").unwrap();
+        assert_eq!(history.len(), 2);
+        assert_eq!(history[0].action, "UPDATE");
+        assert_eq!(
+            history[0].changes,
+            vec![FieldChange {
+                field: "notes".to_string(),
+                old_value: Value::from("plan"),
+                new_value: Value::from("plan + review"),
+            }]
+        );
+        assert!(history[1].changes.iter().any(|c| c.field == "status" && c.old_value.is_null()));
+    }
+
+    #[test]
+    fn test_live_mirror_updates_are_folded() {
+        let conn = setup();
+        insert_test_trade(&conn, "t1", &[("notes", &""), ("total_pnl", &10.0)]);
+        update(&conn, "LIVE_MIRROR", "UPDATE trades SET total_pnl = 25.0 WHERE id = 't1'");
+        update(&conn, "LIVE_MIRROR", "UPDATE trades SET total_pnl = 40.5 WHERE id = 't1'");
+        update(&conn, "USER", "UPDATE trades SET notes = 'scaled out' WHERE id = 't1'");
+        update(&conn, "LIVE_MIRROR", "UPDATE trades SET total_pnl = 12.0 WHERE id = 't1'");
+
+        let history = load_trade_history(&conn, "t1").unwrap();
+        assert_eq!(history.len(), 3);
+        let folded = &history[2].changes[0];
+        assert_eq!((folded.old_value.as_f64(), folded.new_value.as_f64()), (Some(10.0), Some(40.5)));
+    }
+
+    #[test]
+    fn test_revert_restores_fields_and_tags() {
+        let conn = setup();
+        insert_test_trade(&conn, "t1", &[("notes", &"careful notes")]);
+        record_revision(&conn, "t1", "CREATE", "USER", None).unwrap();
+        update(&conn, "USER", "INSERT INTO trade_tags (trade_id, tag_id, created_at) VALUES ('t1', 'breakout', 0)");
+        let keep = load_trade_history(&conn, "t1").unwrap()[0].id;
+        update(&conn, "LIVE_MIRROR", "UPDATE trades SET notes = '', total_pnl = 50.0, status = 'WIN' WHERE id = 't1'");
+        update(&conn, "USER", "DELETE FROM trade_tags; INSERT INTO trade_tags (trade_id, tag_id, created_at) VALUES ('t1', 'news', 0)");
+
+        let current = trade_snapshot(&conn, "t1").unwrap().unwrap();
+        let history = load_trade_history(&conn, "t1").unwrap();
+        let target = state_at_revision(&current, &history, keep);
+        apply_snapshot(&conn, "t1", &current, &target).unwrap();
+        record_revision(&conn, "t1", "REVERT", "USER", Some(current)).unwrap();
+
+        let restored = trade_snapshot(&conn, "t1").unwrap().unwrap();
+        assert_eq!(restored["notes"], Value::from("careful notes"));
+        assert_eq!(restored["total_pnl"], Value::Null);
+        assert_eq!(restored["status"], Value::from("OPEN"));
+        assert_eq!(restored["tag_ids"], Value::from(vec!["breakout"]));
+
+        let history = load_trade_history(&conn, "t1").unwrap();
+        assert_eq!(history[0].action, "REVERT");
+        assert_eq!(history[0].changes.len(), 4);
+    }
+}
@@ -0,0 +1,582 @@
+use tauri::State;
+use crate::commands::settings::load_timezone;
+use crate::commands::stats::{leverage_bucket, local_date, trade_filter, DateWindow, TagFilter};
+use crate::db::Database;
+use chrono_tz::Tz;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+
+/// r_percent buckets as fractions: [low, high)
+const R_PERCENT_BUCKETS: [(f64, f64, &str); 6] = [
+    (0.0, 0.005, "<0.5%"),
+    (0.005, 0.01, "0.5-1%"),
+    (0.01, 0.02, "1-2%"),
+    (0.02, 0.03, "2-3%"),
+    (0.03, 0.05, "3-5%"),
+    (0.05, f64::INFINITY, "5%+"),
+];
+
+/// Loss size buckets in multiples of one_r: [low, high)
+const LOSS_SIZE_BUCKETS: [(f64, f64, &str); 5] = [
+    (0.0, 0.5, "<0.5R"),
+    (0.5, 1.0, "0.5-1R"),
+    (1.0, 1.5, "1-1.5R"),
+    (1.5, 2.0, "1.5-2R"),
+    (2.0, f64::INFINITY, "2R+"),
+];
+
+/// Risk creep needs enough trades, a positive correlation with time and a meaningful increase
+const MIN_CREEP_SAMPLE: usize = 10;
+const MIN_CREEP_CORRELATION: f64 = 0.3;
+const MIN_CREEP_INCREASE_PERCENT: f64 = 10.0;
+/// Consecutive wins before a trade that count as "after a winning streak"
+const WIN_STREAK_LENGTH: i32 = 2;
+/// A loss counts as an overshoot beyond 1R + tolerance (in R)
+const DEFAULT_OVERSHOOT_TOLERANCE_R: f64 = 0.1;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RiskBucket {
+    pub label: String,
+    pub sort_order: i64,
+    pub trade_count: i32,
+    pub wins: i32,
+    pub losses: i32,
+    pub breakevens: i32,
+    pub win_rate: f64,
+    pub expectancy_r: f64, // mean pnl_in_r over trades with a known R multiple
+    pub total_pnl: f64,
+    pub avg_pnl: f64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RiskCreep {
+    pub field: String, // "r_percent" | "execution_r_percent"
+    pub sample_size: i32,
+    pub correlation: f64,        // Pearson correlation of the risk fraction with trade order
+    pub slope_per_trade: f64,    // least-squares change of the risk fraction per trade
+    pub first_half_avg: f64,
+    pub second_half_avg: f64,
+    pub change_percent: f64,     // second half vs first half
+    pub avg_after_win_streak: Option<f64>, // average risk on trades opened after WIN_STREAK_LENGTH+ wins
+    pub avg_otherwise: Option<f64>,
+    pub detected: bool,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RiskAnalysis {
+    pub by_r_percent: Vec<RiskBucket>,
+    pub by_leverage: Vec<RiskBucket>,
+    pub by_loss_size: Vec<RiskBucket>, // losing trades only, loss relative to one_r
+    pub r_percent_creep: RiskCreep,
+    pub execution_r_percent_creep: RiskCreep,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RiskOvershootTrade {
+    pub trade_id: String,
+    pub pair: String,
+    pub exchange: String,
+    pub import_source: String,
+    pub close_date: i64,
+    pub total_pnl: f64,
+    pub risk_amount: f64, // execution_one_r when set, otherwise one_r
+    pub uses_execution_one_r: bool,
+    pub loss_r: f64,      // loss in multiples of risk_amount (positive)
+    pub overshoot_r: f64, // loss_r - 1
+    pub overshoot_amount: f64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RiskOvershootGroup {
+    pub key: String, // pair or local close month (YYYY-MM)
+    pub losing_trades: i32,
+    pub overshoot_count: i32,
+    pub overshoot_rate: f64, // % of losing trades
+    pub total_overshoot_r: f64,
+    pub total_overshoot_amount: f64,
+    pub max_overshoot_r: f64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RiskOvershootReport {
+    pub tolerance_r: f64,
+    pub overall: RiskOvershootGroup,
+    pub by_pair: Vec<RiskOvershootGroup>,  // most overshoot R first
+    pub by_month: Vec<RiskOvershootGroup>, // chronological
+    pub trades: Vec<RiskOvershootTrade>,   // largest overshoot first
+}
+
+/// Closed trade columns needed for risk analytics
+#[derive(Debug, Clone)]
+struct RiskTrade {
+    status: String,
+    leverage: i32,
+    r_percent: f64,
+    execution_r_percent: Option<f64>,
+    one_r: f64,
+    total_pnl: Option<f64>,
+    pnl_in_r: Option<f64>,
+}
+
+fn range_bucket(value: f64, buckets: &[(f64, f64, &'static str)], scale: f64) -> (String, i64) {
+    buckets
+        .iter()
+        .find(|(low, high, _)| value >= *low && value < *high)
+        .map(|(low, _, label)| (label.to_string(), (low * scale).round() as i64))
+        .unwrap_or_else(|| ("Unknown".to_string(), -1))
+}
+
+/// Group trades by key and compute win rate, expectancy and PnL per group, in natural order
+fn bucket_trades<'a>(trades: impl IntoIterator<Item = (&'a RiskTrade, (String, i64))>) -> Vec<RiskBucket> {
+    let mut buckets: Vec<(RiskBucket, i32)> = Vec::new();
+
+    for (trade, (label, sort_order)) in trades {
+        let index = match buckets.iter().position(|(b, _)| b.label == label) {
+            Some(index) => index,
+            None => {
+                buckets.push((
+                    RiskBucket {
+                        label,
+                        sort_order,
+                        trade_count: 0,
+                        wins: 0,
+                        losses: 0,
+                        breakevens: 0,
+                        win_rate: 0.0,
+                        expectancy_r: 0.0,
+                        total_pnl: 0.0,
+                        avg_pnl: 0.0,
+                    },
+                    0,
+                ));
+                buckets.len() - 1
+            }
+        };
+
+        let (bucket, r_count) = &mut buckets[index];
+        bucket.trade_count += 1;
+        match trade.status.as_str() {
+            "WIN" => bucket.wins += 1,
+            "LOSS" => bucket.losses += 1,
+            _ => bucket.breakevens += 1,
+        }
+        bucket.total_pnl += trade.total_pnl.unwrap_or(0.0);
+        if let Some(r) = trade.pnl_in_r {
+            bucket.expectancy_r += r; // summed here, averaged below
+            *r_count += 1;
+        }
+    }
+
+    let mut result: Vec<RiskBucket> = buckets
+        .into_iter()
+        .map(|(mut bucket, r_count)| {
+            let decided = bucket.wins + bucket.losses;
+            if decided > 0 {
+                bucket.win_rate = bucket.wins as f64 / decided as f64 * 100.0;
+            }
+            bucket.expectancy_r = if r_count > 0 { bucket.expectancy_r / r_count as f64 } else { 0.0 };
+            bucket.avg_pnl = bucket.total_pnl / bucket.trade_count as f64;
+            bucket
+        })
+        .collect();
+    result.sort_by(|a, b| a.sort_order.cmp(&b.sort_order).then_with(|| a.label.cmp(&b.label)));
+    result
+}
+
+/// Trend of a risk fraction over trades in trade_date order. `series` pairs each trade's
+/// risk with whether it was opened after a winning streak.
+fn detect_risk_creep(field: &str, series: &[(f64, bool)]) -> RiskCreep {
+    let n = series.len();
+    let values: Vec<f64> = series.iter().map(|(value, _)| *value).collect();
+    let mean = |values: &[f64]| -> Option<f64> {
+        (!values.is_empty()).then(|| values.iter().sum::<f64>() / values.len() as f64)
+    };
+
+    let (mut correlation, mut slope_per_trade) = (0.0, 0.0);
+    if n >= 2 {
+        let mean_x = (n - 1) as f64 / 2.0;
+        let mean_y = mean(&values).unwrap_or(0.0);
+        let (mut sxy, mut sxx, mut syy) = (0.0, 0.0, 0.0);
+        for (i, y) in values.iter().enumerate() {
+            let dx = i as f64 - mean_x;
+            let dy = y - mean_y;
+            sxy += dx * dy;
+            sxx += dx * dx;
+            syy += dy * dy;
+        }
+        slope_per_trade = sxy / sxx;
+        if syy > 0.0 {
+            correlation = sxy / (sxx * syy).sqrt();
+        }
+    }
+
+    let first_half_avg = mean(&values[..n / 2]).unwrap_or(0.0);
+    let second_half_avg = mean(&values[n / 2..]).unwrap_or(0.0);
+    let change_percent = if first_half_avg > 0.0 {
+        (second_half_avg - first_half_avg) / first_half_avg * 100.0
+    } else {
+        0.0
+    };
+
+    let after_streak: Vec<f64> = series.iter().filter(|(_, after)| *after).map(|(v, _)| *v).collect();
+    let otherwise: Vec<f64> = series.iter().filter(|(_, after)| !*after).map(|(v, _)| *v).collect();
+
+    RiskCreep {
+        field: field.to_string(),
+        sample_size: n as i32,
+        correlation,
+        slope_per_trade,
+        first_half_avg,
+        second_half_avg,
+        change_percent,
+        avg_after_win_streak: mean(&after_streak),
+        avg_otherwise: mean(&otherwise),
+        detected: n >= MIN_CREEP_SAMPLE
+            && correlation >= MIN_CREEP_CORRELATION
+            && change_percent >= MIN_CREEP_INCREASE_PERCENT,
+    }
+}
+
+/// Flag each trade (in trade_date order) opened right after WIN_STREAK_LENGTH or more consecutive wins
+fn after_win_streak_flags(trades: &[RiskTrade]) -> Vec<bool> {
+    let mut streak = 0;
+    trades
+        .iter()
+        .map(|trade| {
+            let after = streak >= WIN_STREAK_LENGTH;
+            streak = if trade.status == "WIN" { streak + 1 } else { 0 };
+            after
+        })
+        .collect()
+}
+
+fn compute_risk_analysis(trades: &[RiskTrade]) -> RiskAnalysis {
+    let by_r_percent = bucket_trades(
+        trades.iter().map(|t| (t, range_bucket(t.r_percent, &R_PERCENT_BUCKETS, 10_000.0))),
+    );
+    let by_leverage = bucket_trades(trades.iter().map(|t| (t, leverage_bucket(t.leverage))));
+    let by_loss_size = bucket_trades(
+        trades
+            .iter()
+            .filter(|t| t.one_r > 0.0 && t.total_pnl.is_some_and(|pnl| pnl < 0.0))
+            .map(|t| {
+                let loss_in_r = -t.total_pnl.unwrap_or(0.0) / t.one_r;
+                (t, range_bucket(loss_in_r, &LOSS_SIZE_BUCKETS, 100.0))
+            }),
+    );
+
+    let flags = after_win_streak_flags(trades);
+    let planned: Vec<(f64, bool)> = trades.iter().zip(&flags).map(|(t, f)| (t.r_percent, *f)).collect();
+    let executed: Vec<(f64, bool)> = trades
+        .iter()
+        .zip(&flags)
+        .filter_map(|(t, f)| t.execution_r_percent.map(|r| (r, *f)))
+        .collect();
+
+    RiskAnalysis {
+        by_r_percent,
+        by_leverage,
+        by_loss_size,
+        r_percent_creep: detect_risk_creep("r_percent", &planned),
+        execution_r_percent_creep: detect_risk_creep("execution_r_percent", &executed),
+    }
+}
+
+/// Losing trade columns needed for overshoot detection
+#[derive(Debug, Clone)]
+struct LosingTrade {
+    id: String,
+    pair: String,
+    exchange: String,
+    import_source: String,
+    close_date: i64,
+    total_pnl: f64,
+    one_r: f64,
+    execution_one_r: Option<f64>,
+}
+
+impl LosingTrade {
+    /// Planned risk at execution when recorded, otherwise the planned 1R
+    fn risk_amount(&self) -> (f64, bool) {
+        match self.execution_one_r {
+            Some(risk) if risk > 0.0 => (risk, true),
+            _ => (self.one_r, false),
+        }
+    }
+}
+
+fn overshoot_group(key: String, losing_trades: i32, overshoots: &[&RiskOvershootTrade]) -> RiskOvershootGroup {
+    RiskOvershootGroup {
+        key,
+        losing_trades,
+        overshoot_count: overshoots.len() as i32,
+        overshoot_rate: if losing_trades > 0 { overshoots.len() as f64 / losing_trades as f64 * 100.0 } else { 0.0 },
+        total_overshoot_r: overshoots.iter().map(|o| o.overshoot_r).sum(),
+        total_overshoot_amount: overshoots.iter().map(|o| o.overshoot_amount).sum(),
+        max_overshoot_r: overshoots.iter().map(|o| o.overshoot_r).fold(0.0, f64::max),
+    }
+}
+
+/// Flag losses beyond 1R + tolerance_r and aggregate them by pair and local close month
+fn compute_risk_overshoot(trades: &[LosingTrade], tolerance_r: f64, tz: &Tz) -> Result<RiskOvershootReport, String> {
+    let mut overshoots = Vec::new();
+    // key -> (losing trades, indexes into overshoots)
+    let mut by_pair: BTreeMap<String, (i32, Vec<usize>)> = BTreeMap::new();
+    let mut by_month: BTreeMap<String, (i32, Vec<usize>)> = BTreeMap::new();
+    let mut losing_trades = 0;
+
+    for trade in trades {
+        let (risk_amount, uses_execution_one_r) = trade.risk_amount();
+        if risk_amount <= 0.0 || trade.total_pnl >= 0.0 {
+            continue;
+        }
+        let month = local_date(trade.close_date, tz)?.format("%Y-%m").to_string();
+        losing_trades += 1;
+        let pair_entry = by_pair.entry(trade.pair.clone()).or_default();
+        pair_entry.0 += 1;
+        let month_entry = by_month.entry(month).or_default();
+        month_entry.0 += 1;
+
+        let loss_r = -trade.total_pnl / risk_amount;
+        if loss_r > 1.0 + tolerance_r {
+            pair_entry.1.push(overshoots.len());
+            month_entry.1.push(overshoots.len());
+            overshoots.push(RiskOvershootTrade {
+                trade_id: trade.id.clone(),
+                pair: trade.pair.clone(),
+                exchange: trade.exchange.clone(),
+                import_source: trade.import_source.clone(),
+                close_date: trade.close_date,
+                total_pnl: trade.total_pnl,
+                risk_amount,
+                uses_execution_one_r,
+                loss_r,
+                overshoot_r: loss_r - 1.0,
+                overshoot_amount: -trade.total_pnl - risk_amount,
+            });
+        }
+    }
+
+    let groups = |grouped: BTreeMap<String, (i32, Vec<usize>)>| -> Vec<RiskOvershootGroup> {
+        grouped
+            .into_iter()
+            .map(|(key, (count, indexes))| {
+                let members: Vec<&RiskOvershootTrade> = indexes.iter().map(|i| &overshoots[*i]).collect();
+                overshoot_group(key, count, &members)
+            })
+            .collect()
+    };
+
+    let mut by_pair = groups(by_pair);
+    by_pair.sort_by(|a, b| b.total_overshoot_r.total_cmp(&a.total_overshoot_r).then_with(|| a.key.cmp(&b.key)));
+    let by_month = groups(by_month);
+    let overall = overshoot_group("ALL".to_string(), losing_trades, &overshoots.iter().collect::<Vec<_>>());
+
+    overshoots.sort_by(|a, b| b.overshoot_r.total_cmp(&a.overshoot_r));
+
+    Ok(RiskOvershootReport {
+        tolerance_r,
+        overall,
+        by_pair,
+        by_month,
+        trades: overshoots,
+    })
+}
+
+/// Performance by r_percent, leverage and loss size buckets, plus risk creep detection
+#[tauri::command]
+pub async fn get_risk_analysis(
+    db: State<'_, Database>,
+    date_range: Option<String>,
+    start_date: Option<i64>,
+    end_date: Option<i64>,
+    tag_ids: Option<Vec<String>>,
+) -> Result<RiskAnalysis, String> {
+    let conn = db.conn.lock().map_err(|e| e.to_string())?;
+
+    let tz = load_timezone(&conn);
+    let window = DateWindow::resolve(date_range.as_deref(), start_date, end_date, &tz);
+    let (date_filter, date_params) = trade_filter(window, &TagFilter::new(tag_ids));
+
+    let mut stmt = conn.prepare(&format!(
+        "SELECT status, leverage, r_percent, execution_r_percent, one_r, total_pnl, pnl_in_r
+         FROM trades
+         WHERE deleted_at IS NULL AND status IN ('WIN', 'LOSS', 'BE') {}
+         ORDER BY trade_date ASC",
+        date_filter
+    )).map_err(|e| e.to_string())?;
+
+    let trades = stmt.query_map(rusqlite::params_from_iter(date_params.iter()), |row| {
+        Ok(RiskTrade {
+            status: row.get(0)?,
+            leverage: row.get(1)?,
+            r_percent: row.get(2)?,
+            execution_r_percent: row.get(3)?,
+            one_r: row.get(4)?,
+            total_pnl: row.get(5)?,
+            pnl_in_r: row.get(6)?,
+        })
+    })
+    .map_err(|e| e.to_string())?
+    .collect::<Result<Vec<_>, _>>()
+    .map_err(|e| e.to_string())?;
+
+    Ok(compute_risk_analysis(&trades))
+}
+
+/// Losing trades whose loss exceeded 1R (execution_one_r when set) by more than tolerance_r,
+/// aggregated by pair and by month of close
+#[tauri::command]
+pub async fn get_risk_overshoot(
+    db: State<'_, Database>,
+    date_range: Option<String>,
+    start_date: Option<i64>,
+    end_date: Option<i64>,
+    tolerance_r: Option<f64>,
+    tag_ids: Option<Vec<String>>,
+) -> Result<RiskOvershootReport, String> {
+    let tolerance_r = tolerance_r.unwrap_or(DEFAULT_OVERSHOOT_TOLERANCE_R);
+    if !tolerance_r.is_finite() || tolerance_r < 0.0 {
+        return Err("tolerance_r must be a non-negative number".to_string());
+    }
+
+    let conn = db.conn.lock().map_err(|e| e.to_string())?;
+
+    let tz = load_timezone(&conn);
+    let window = DateWindow::resolve(date_range.as_deref(), start_date, end_date, &tz);
+    let (date_filter, date_params) = trade_filter(window, &TagFilter::new(tag_ids));
+
+    let mut stmt = conn.prepare(&format!(
+        "SELECT id, pair, exchange, import_source, close_date, total_pnl, one_r, execution_one_r
+         FROM trades
+         WHERE deleted_at IS NULL AND status = 'LOSS'
+           AND close_date IS NOT NULL AND total_pnl IS NOT NULL {}
+         ORDER BY close_date ASC",
+        date_filter
+    )).map_err(|e| e.to_string())?;
+
+    let trades = stmt.query_map(rusqlite::params_from_iter(date_params.iter()), |row| {
+        Ok(LosingTrade {
+            id: row.get(0)?,
+            pair: row.get(1)?,
+            exchange: row.get(2)?,
+            import_source: row.get(3)?,
+            close_date: row.get(4)?,
+            total_pnl: row.get(5)?,
+            one_r: row.get(6)?,
+            execution_one_r: row.get(7)?,
+        })
+    })
+    .map_err(|e| e.to_string())?
+    .collect::<Result<Vec<_>, _>>()
+    .map_err(|e| e.to_string())?;
+
+    compute_risk_overshoot(&trades, tolerance_r, &tz)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn trade(status: &str, r_percent: f64, leverage: i32, pnl: f64) -> RiskTrade {
+        RiskTrade {
+            status: status.to_string(),
+            leverage,
+            r_percent,
+            execution_r_percent: None,
+            one_r: 100.0,
+            total_pnl: Some(pnl),
+            pnl_in_r: Some(pnl / 100.0),
+        }
+    }
+
+    #[test]
+    fn test_buckets_by_risk_leverage_and_loss_size() {
+        let trades = vec![
+            trade("WIN", 0.01, 10, 200.0),
+            trade("LOSS", 0.01, 10, -100.0),
+            trade("LOSS", 0.025, 50, -250.0),
+        ];
+        let analysis = compute_risk_analysis(&trades);
+
+        assert_eq!(analysis.by_r_percent.len(), 2);
+        assert_eq!(analysis.by_r_percent[0].label, "1-2%");
+        assert!((analysis.by_r_percent[0].win_rate - 50.0).abs() < 1e-9);
+        assert!((analysis.by_r_percent[0].expectancy_r - 0.5).abs() < 1e-9);
+        assert_eq!(analysis.by_r_percent[1].label, "2-3%");
+
+        assert_eq!(analysis.by_leverage[0].label, "6-10x");
+        assert_eq!(analysis.by_leverage[1].label, "21-50x");
+
+        // Only losers, -100 is exactly 1R and -250 is 2.5R
+        assert_eq!(analysis.by_loss_size.len(), 2);
+        assert_eq!(analysis.by_loss_size[0].label, "1-1.5R");
+        assert_eq!(analysis.by_loss_size[1].label, "2R+");
+    }
+
+    #[test]
+    fn test_detects_risk_creep_after_win_streaks() {
+        let mut trades = Vec::new();
+        for i in 0..12 {
+            trades.push(trade("WIN", 0.01 + i as f64 * 0.001, 10, 100.0));
+        }
+        let analysis = compute_risk_analysis(&trades);
+        let creep = &analysis.r_percent_creep;
+
+        assert!(creep.detected);
+        assert!(creep.correlation > 0.99);
+        assert!((creep.slope_per_trade - 0.001).abs() < 1e-12);
+        assert!(creep.avg_after_win_streak.unwrap() > creep.avg_otherwise.unwrap());
+        assert_eq!(analysis.execution_r_percent_creep.sample_size, 0);
+        assert!(!analysis.execution_r_percent_creep.detected);
+    }
+
+    #[test]
+    fn test_flat_risk_is_not_creep() {
+        let trades: Vec<RiskTrade> = (0..12).map(|_| trade("LOSS", 0.02, 10, -100.0)).collect();
+        let creep = compute_risk_analysis(&trades).r_percent_creep;
+
+        assert!(!creep.detected);
+        assert_eq!(creep.correlation, 0.0);
+        assert_eq!(creep.avg_after_win_streak, None);
+    }
+
+    fn loss(id: &str, pair: &str, close_date: i64, pnl: f64, execution_one_r: Option<f64>) -> LosingTrade {
+        LosingTrade {
+            id: id.to_string(),
+            pair: pair.to_string(),
+            exchange: "BitGet".to_string(),
+            import_source: "API_IMPORT".to_string(),
+            close_date,
+            total_pnl: pnl,
+            one_r: 100.0,
+            execution_one_r,
+        }
+    }
+
+    #[test]
+    fn test_overshoot_uses_execution_one_r_and_tolerance() {
+        const JAN_1: i64 = 1704067200; // 2024-01-01 00:00 UTC
+        const FEB_1: i64 = 1706745600;
+        let trades = vec![
+            loss("within", "BTC/USDT", JAN_1, -105.0, None),
+            loss("over", "BTC/USDT", JAN_1, -150.0, None),
+            // 1.2R of the planned 100, but 0.6R of the 200 actually risked
+            loss("sized_up", "ETH/USDT", FEB_1, -120.0, Some(200.0)),
+            loss("exec", "ETH/USDT", FEB_1, -130.0, Some(50.0)),
+        ];
+        let report = compute_risk_overshoot(&trades, 0.1, &Tz::UTC).unwrap();
+
+        assert_eq!(report.overall.losing_trades, 4);
+        assert_eq!(report.overall.overshoot_count, 2);
+        assert_eq!(report.trades[0].trade_id, "exec");
+        assert!(report.trades[0].uses_execution_one_r);
+        assert!((report.trades[0].overshoot_r - 1.6).abs() < 1e-9);
+        assert!((report.trades[0].overshoot_amount - 80.0).abs() < 1e-9);
+        assert!((report.trades[1].overshoot_r - 0.5).abs() < 1e-9);
+
+        assert_eq!(report.by_pair[0].key, "ETH/USDT");
+        assert!((report.by_pair[1].overshoot_rate - 50.0).abs() < 1e-9);
+        assert_eq!(report.by_month.iter().map(|g| g.key.as_str()).collect::<Vec<_>>(), ["2024-01", "2024-02"]);
+    }
+}
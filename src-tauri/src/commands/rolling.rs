@@ -0,0 +1,273 @@
+use tauri::State;
+use crate::commands::settings::load_timezone;
+use crate::commands::stats::{local_date, DateWindow, TagFilter};
+use crate::db::Database;
+use chrono_tz::Tz;
+use serde::{Deserialize, Serialize};
+use std::collections::VecDeque;
+
+const DEFAULT_TRADE_WINDOW: u32 = 20;
+const DEFAULT_DAY_WINDOW: u32 = 30;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RollingMetricPoint {
+    pub date: String, // local close date (YYYY-MM-DD)
+    pub trade_id: Option<String>, // set in "trades" mode
+    pub close_date: Option<i64>,  // set in "trades" mode
+    pub sample_size: i32,
+    pub window_full: bool, // false while fewer than N trades/days of history exist
+    pub win_rate: Option<f64>,     // wins / (wins + losses), %
+    pub expectancy_r: Option<f64>, // mean pnl_in_r
+    pub profit_factor: Option<f64>, // None without losses
+    pub avg_rr: Option<f64>,       // mean effective_weighted_rr
+}
+
+/// Closed trade columns needed for rolling metrics
+#[derive(Debug, Clone)]
+struct RollingTrade {
+    id: String,
+    close_date: i64,
+    status: String,
+    total_pnl: Option<f64>,
+    pnl_in_r: Option<f64>,
+    effective_weighted_rr: Option<f64>,
+}
+
+/// Running sums over the trades currently inside the window
+#[derive(Debug, Clone, Default)]
+struct RollingSums {
+    count: i32,
+    wins: i32,
+    losses: i32,
+    r_sum: f64,
+    r_count: i32,
+    gross_profit: f64,
+    gross_loss: f64,
+    rr_sum: f64,
+    rr_count: i32,
+}
+
+impl RollingSums {
+    /// Add (sign = 1.0) or remove (sign = -1.0) a trade
+    fn apply(&mut self, trade: &RollingTrade, sign: f64) {
+        let step = sign as i32;
+        self.count += step;
+        match trade.status.as_str() {
+            "WIN" => self.wins += step,
+            "LOSS" => self.losses += step,
+            _ => {}
+        }
+        if let Some(r) = trade.pnl_in_r {
+            self.r_sum += sign * r;
+            self.r_count += step;
+        }
+        match trade.total_pnl {
+            Some(pnl) if pnl > 0.0 => self.gross_profit += sign * pnl,
+            Some(pnl) if pnl < 0.0 => self.gross_loss += sign * -pnl,
+            _ => {}
+        }
+        if let Some(rr) = trade.effective_weighted_rr {
+            self.rr_sum += sign * rr;
+            self.rr_count += step;
+        }
+    }
+
+    fn point(&self, date: String, trade: Option<&RollingTrade>, window_full: bool) -> RollingMetricPoint {
+        let decided = self.wins + self.losses;
+        RollingMetricPoint {
+            date,
+            trade_id: trade.map(|t| t.id.clone()),
+            close_date: trade.map(|t| t.close_date),
+            sample_size: self.count,
+            window_full,
+            win_rate: (decided > 0).then(|| self.wins as f64 / decided as f64 * 100.0),
+            expectancy_r: (self.r_count > 0).then(|| self.r_sum / self.r_count as f64),
+            // Sums are maintained incrementally, treat float residue as zero
+            profit_factor: (self.gross_loss > 1e-9).then(|| self.gross_profit / self.gross_loss),
+            avg_rr: (self.rr_count > 0).then(|| self.rr_sum / self.rr_count as f64),
+        }
+    }
+}
+
+/// One point per closed trade, over the last `window` trades (trades must be in close_date order)
+fn rolling_by_trades(trades: &[RollingTrade], window: usize, tz: &Tz) -> Result<Vec<RollingMetricPoint>, String> {
+    let mut sums = RollingSums::default();
+    let mut points = Vec::with_capacity(trades.len());
+
+    for (i, trade) in trades.iter().enumerate() {
+        sums.apply(trade, 1.0);
+        if i >= window {
+            sums.apply(&trades[i - window], -1.0);
+        }
+        let date = local_date(trade.close_date, tz)?.format("%Y-%m-%d").to_string();
+        points.push(sums.point(date, Some(trade), i + 1 >= window));
+    }
+
+    Ok(points)
+}
+
+/// One point per calendar day from the first to the last close, over trades closed in the last `window` days
+fn rolling_by_days(trades: &[RollingTrade], window: i64, tz: &Tz) -> Result<Vec<RollingMetricPoint>, String> {
+    let (Some(first), Some(last)) = (trades.first(), trades.last()) else {
+        return Ok(Vec::new());
+    };
+    let first_day = local_date(first.close_date, tz)?;
+    let last_day = local_date(last.close_date, tz)?;
+
+    let mut sums = RollingSums::default();
+    let mut in_window: VecDeque<(chrono::NaiveDate, &RollingTrade)> = VecDeque::new();
+    let mut next = 0;
+    let mut points = Vec::new();
+
+    let mut day = first_day;
+    while day <= last_day {
+        while next < trades.len() && local_date(trades[next].close_date, tz)? <= day {
+            let trade = &trades[next];
+            sums.apply(trade, 1.0);
+            in_window.push_back((local_date(trade.close_date, tz)?, trade));
+            next += 1;
+        }
+        let window_start = day - chrono::Duration::days(window - 1);
+        while in_window.front().is_some_and(|(date, _)| *date < window_start) {
+            if let Some((_, trade)) = in_window.pop_front() {
+                sums.apply(trade, -1.0);
+            }
+        }
+
+        let window_full = (day - first_day).num_days() + 1 >= window;
+        points.push(sums.point(day.format("%Y-%m-%d").to_string(), None, window_full));
+        day = day.succ_opt().ok_or("Date out of range")?;
+    }
+
+    Ok(points)
+}
+
+/// Rolling win rate, expectancy (R), profit factor and average RR over the last N closed trades
+/// (mode "trades") or N days (mode "days"). History before the date window is used to warm up
+/// the rolling window, only points inside it are returned.
+#[tauri::command]
+pub async fn get_rolling_metrics(
+    db: State<'_, Database>,
+    mode: Option<String>,
+    window: Option<u32>,
+    date_range: Option<String>,
+    start_date: Option<i64>,
+    end_date: Option<i64>,
+    tag_ids: Option<Vec<String>>,
+) -> Result<Vec<RollingMetricPoint>, String> {
+    let mode = mode.as_deref().unwrap_or("trades");
+    let window = window.unwrap_or(if mode == "days" { DEFAULT_DAY_WINDOW } else { DEFAULT_TRADE_WINDOW });
+    if window == 0 {
+        return Err("window must be greater than zero".to_string());
+    }
+
+    let conn = db.conn.lock().map_err(|e| e.to_string())?;
+
+    let tz = load_timezone(&conn);
+    let date_window = DateWindow::resolve(date_range.as_deref(), start_date, end_date, &tz);
+    // Only the tag filter applies to the query, the date window is applied to the points
+    let (tag_filter, tag_params) = TagFilter::new(tag_ids).filter();
+
+    let mut stmt = conn.prepare(&format!(
+        "SELECT id, close_date, status, total_pnl, pnl_in_r, effective_weighted_rr
+         FROM trades
+         WHERE deleted_at IS NULL AND status IN ('WIN', 'LOSS', 'BE') AND close_date IS NOT NULL {}
+         ORDER BY close_date ASC, id ASC",
+        tag_filter
+    )).map_err(|e| e.to_string())?;
+
+    let trades = stmt.query_map(rusqlite::params_from_iter(tag_params.iter()), |row| {
+        Ok(RollingTrade {
+            id: row.get(0)?,
+            close_date: row.get(1)?,
+            status: row.get(2)?,
+            total_pnl: row.get(3)?,
+            pnl_in_r: row.get(4)?,
+            effective_weighted_rr: row.get(5)?,
+        })
+    })
+    .map_err(|e| e.to_string())?
+    .collect::<Result<Vec<_>, _>>()
+    .map_err(|e| e.to_string())?;
+
+    let points = match mode {
+        "trades" => rolling_by_trades(&trades, window as usize, &tz)?,
+        "days" => rolling_by_days(&trades, window as i64, &tz)?,
+        _ => return Err(format!("Unsupported mode: {} (expected trades or days)", mode)),
+    };
+
+    // Keep points inside the requested window, compared by local date in "days" mode
+    let start_day = date_window.start.map(|ts| local_date(ts, &tz)).transpose()?.map(|d| d.format("%Y-%m-%d").to_string());
+    let end_day = date_window.end.map(|ts| local_date(ts, &tz)).transpose()?.map(|d| d.format("%Y-%m-%d").to_string());
+    let in_window = |point: &RollingMetricPoint| match point.close_date {
+        Some(ts) => date_window.start.is_none_or(|s| ts >= s) && date_window.end.is_none_or(|e| ts <= e),
+        None => {
+            start_day.as_ref().is_none_or(|s| point.date >= *s) && end_day.as_ref().is_none_or(|e| point.date <= *e)
+        }
+    };
+
+    Ok(points.into_iter().filter(in_window).collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const DAY: i64 = 86400;
+    const JAN_1: i64 = 1704067200; // 2024-01-01 00:00 UTC
+
+    fn trade(id: &str, close_date: i64, status: &str, pnl: f64, r: f64) -> RollingTrade {
+        RollingTrade {
+            id: id.to_string(),
+            close_date,
+            status: status.to_string(),
+            total_pnl: Some(pnl),
+            pnl_in_r: Some(r),
+            effective_weighted_rr: Some(r.max(0.0)),
+        }
+    }
+
+    #[test]
+    fn test_rolling_by_trades_drops_old_trades() {
+        let trades = vec![
+            trade("a", JAN_1, "LOSS", -100.0, -1.0),
+            trade("b", JAN_1 + 60, "WIN", 200.0, 2.0),
+            trade("c", JAN_1 + 120, "WIN", 100.0, 1.0),
+        ];
+        let points = rolling_by_trades(&trades, 2, &Tz::UTC).unwrap();
+
+        assert_eq!(points.len(), 3);
+        assert!(!points[0].window_full);
+        assert_eq!(points[0].win_rate, Some(0.0));
+        assert_eq!(points[0].profit_factor, Some(0.0));
+
+        assert!(points[1].window_full);
+        assert_eq!(points[1].expectancy_r, Some(0.5));
+        assert_eq!(points[1].profit_factor, Some(2.0));
+
+        // "a" has left the window: no losses, profit factor undefined
+        assert_eq!(points[2].trade_id.as_deref(), Some("c"));
+        assert_eq!(points[2].sample_size, 2);
+        assert_eq!(points[2].win_rate, Some(100.0));
+        assert_eq!(points[2].expectancy_r, Some(1.5));
+        assert_eq!(points[2].profit_factor, None);
+    }
+
+    #[test]
+    fn test_rolling_by_days_fills_gaps() {
+        let trades = vec![
+            trade("a", JAN_1 + 3600, "WIN", 100.0, 1.0),
+            trade("b", JAN_1 + 3 * DAY, "LOSS", -50.0, -0.5),
+        ];
+        let points = rolling_by_days(&trades, 2, &Tz::UTC).unwrap();
+
+        assert_eq!(points.len(), 4);
+        assert_eq!(points[0].date, "2024-01-01");
+        assert_eq!(points[1].sample_size, 1);
+        assert!(points[1].window_full);
+        assert_eq!(points[2].sample_size, 0);
+        assert_eq!(points[2].win_rate, None);
+        assert_eq!(points[3].sample_size, 1);
+        assert_eq!(points[3].expectancy_r, Some(-0.5));
+    }
+}
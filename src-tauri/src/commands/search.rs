@@ -0,0 +1,269 @@
+use tauri::State;
+use crate::commands::trades::map_row_to_trade;
+use crate::db::Database;
+use crate::models::Trade;
+use serde::{Deserialize, Serialize};
+
+const DEFAULT_SEARCH_LIMIT: i64 = 50;
+const MAX_SEARCH_LIMIT: i64 = 500;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TradeSearchResult {
+    pub trade: Trade,
+    pub rank: f64,       // higher is a better match
+    pub snippet: String, // best matching fragment, matched terms wrapped in ** **
+}
+
+/// Indexed match before the trade itself is loaded
+#[derive(Debug, Clone, PartialEq)]
+struct SearchHit {
+    trade_id: String,
+    rank: f64,
+    snippet: String,
+}
+
+// Index rows use the trade's key from trade_search_keys as rowid, so they are found without
+// scanning the unindexed trade_id column
+const INDEX_TRADE_SQL: &str =
+    "INSERT INTO trade_search (rowid, trade_id, pair, exchange, tags, notes)
+     SELECT
+         trade_search_keys.id,
+         trades.id,
+         trades.pair,
+         trades.exchange,
+         COALESCE((
+             SELECT group_concat(tags.name, ' ')
+             FROM trade_tags JOIN tags ON tags.id = trade_tags.tag_id
+             WHERE trade_tags.trade_id = trades.id
+         ), ''),
+         trades.notes
+     FROM trades
+     JOIN trade_search_keys ON trade_search_keys.trade_id = trades.id";
+
+/// Refresh the index entry of one trade. `is_new` skips removing an entry that cannot exist yet
+pub(crate) fn reindex_trade(conn: &rusqlite::Connection, trade_id: &str, is_new: bool) -> Result<(), String> {
+    if !is_new {
+        conn.execute(
+            "DELETE FROM trade_search WHERE rowid = (SELECT id FROM trade_search_keys WHERE trade_id = ?)",
+            [trade_id],
+        )
+        .map_err(|e| e.to_string())?;
+    }
+    conn.execute("INSERT OR IGNORE INTO trade_search_keys (trade_id) VALUES (?)", [trade_id])
+        .map_err(|e| e.to_string())?;
+    conn.execute(&format!("{} WHERE trades.id = ?", INDEX_TRADE_SQL), [trade_id])
+        .map_err(|e| e.to_string())?;
+    Ok(())
+}
+
+/// Trades linked to a tag, whose index entries change when the tag is renamed or deleted
+pub(crate) fn tagged_trade_ids(conn: &rusqlite::Connection, tag_id: &str) -> Result<Vec<String>, String> {
+    let mut stmt = conn
+        .prepare("SELECT trade_id FROM trade_tags WHERE tag_id = ?")
+        .map_err(|e| e.to_string())?;
+    let trade_ids = stmt
+        .query_map([tag_id], |row| row.get::<_, String>(0))
+        .map_err(|e| e.to_string())?
+        .collect::<Result<Vec<_>, _>>()
+        .map_err(|e| e.to_string())?;
+    Ok(trade_ids)
+}
+
+pub(crate) fn reindex_trades(conn: &rusqlite::Connection, trade_ids: &[String]) -> Result<(), String> {
+    for trade_id in trade_ids {
+        reindex_trade(conn, trade_id, false)?;
+    }
+    Ok(())
+}
+
+/// Drop index entries and keys of permanently deleted trades
+pub(crate) fn prune_search_index(conn: &rusqlite::Connection) -> Result<(), String> {
+    conn.execute_batch(
+        "DELETE FROM trade_search WHERE rowid IN (
+             SELECT id FROM trade_search_keys WHERE trade_id NOT IN (SELECT id FROM trades)
+         );
+         DELETE FROM trade_search_keys WHERE trade_id NOT IN (SELECT id FROM trades);",
+    )
+    .map_err(|e| e.to_string())
+}
+
+/// Rebuild the whole index from the trades table
+pub(crate) fn rebuild_trade_search(conn: &rusqlite::Connection) -> Result<usize, String> {
+    conn.execute_batch(
+        "DELETE FROM trade_search;
+         DELETE FROM trade_search_keys WHERE trade_id NOT IN (SELECT id FROM trades);
+         INSERT OR IGNORE INTO trade_search_keys (trade_id) SELECT id FROM trades;",
+    )
+    .map_err(|e| e.to_string())?;
+    conn.execute(INDEX_TRADE_SQL, []).map_err(|e| e.to_string())
+}
+
+/// FTS5 query from free text: every word must match, as a prefix ("sol" finds SOLUSDT).
+/// Words are quoted, so FTS5 operators and column filters typed by the user are plain text.
+fn build_match_query(query: &str) -> Option<String> {
+    let terms: Vec<String> = query
+        .split_whitespace()
+        .filter(|word| word.chars().any(char::is_alphanumeric))
+        .map(|word| format!("\"{}\"*", word.replace('"', "\"\"")))
+        .collect();
+    (!terms.is_empty()).then(|| terms.join(" "))
+}
+
+fn find_matches(
+    conn: &rusqlite::Connection,
+    match_query: &str,
+    include_deleted: bool,
+    limit: i64,
+) -> Result<Vec<SearchHit>, String> {
+    // Column weights follow the table order: trade_id (unindexed), pair, exchange, tags, notes
+    let mut stmt = conn.prepare(
+        "SELECT trade_search.trade_id,
+                bm25(trade_search, 0.0, 4.0, 2.0, 3.0, 1.0) AS score,
+                snippet(trade_search, -1, '**', '**', '…', 16)
+         FROM trade_search
+         JOIN trades ON trades.id = trade_search.trade_id
+         WHERE trade_search MATCH ?1 AND (?2 = 1 OR trades.deleted_at IS NULL)
+         ORDER BY score ASC, trades.trade_date DESC
+         LIMIT ?3"
+    ).map_err(|e| e.to_string())?;
+
+    let hits = stmt
+        .query_map(rusqlite::params![match_query, include_deleted as i32, limit], |row| {
+            Ok(SearchHit {
+                trade_id: row.get(0)?,
+                // bm25 is lower for better matches
+                rank: -row.get::<_, f64>(1)?,
+                snippet: row.get(2)?,
+            })
+        })
+        .map_err(|e| e.to_string())?
+        .collect::<Result<Vec<_>, _>>()
+        .map_err(|e| e.to_string())?;
+
+    Ok(hits)
+}
+
+/// Ranked full-text search over notes, pair, exchange and tag/strategy names
+#[tauri::command]
+pub async fn search_trades(
+    db: State<'_, Database>,
+    query: String,
+    limit: Option<i64>,
+    include_deleted: Option<bool>,
+) -> Result<Vec<TradeSearchResult>, String> {
+    let Some(match_query) = build_match_query(&query) else {
+        return Ok(vec![]);
+    };
+    let limit = limit.unwrap_or(DEFAULT_SEARCH_LIMIT).clamp(1, MAX_SEARCH_LIMIT);
+
+    let conn = db.conn.lock().map_err(|e| e.to_string())?;
+    let hits = find_matches(&conn, &match_query, include_deleted.unwrap_or(false), limit)?;
+
+    hits.into_iter()
+        .map(|hit| {
+            let trade = conn
+                .query_row("SELECT * FROM trades WHERE id = ?", [&hit.trade_id], map_row_to_trade)
+                .map_err(|e| e.to_string())?;
+            Ok(TradeSearchResult {
+                trade,
+                rank: hit.rank,
+                snippet: hit.snippet,
+            })
+        })
+        .collect()
+}
+
+/// Rebuild the search index from scratch, returns the number of trades indexed
+#[tauri::command]
+pub async fn rebuild_search_index(db: State<'_, Database>) -> Result<usize, String> {
+    let conn = db.conn.lock().map_err(|e| e.to_string())?;
+    rebuild_trade_search(&conn)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::db::{insert_test_trade, test_connection};
+    use rusqlite::Connection;
+
+    fn setup() -> Connection {
+        let conn = test_connection();
+        for (id, pair, exchange, notes, trade_date, deleted_at) in [
+            ("sol", "SOLUSDT", "BitGet", "Paid a lot of funding overnight, should have closed earlier", 1, None),
+            ("btc", "BTC/USDT", "BloFin", "Clean breakout, no funding issue", 2, None),
+            ("eth", "ETHUSDT", "BingX", "Revenge trade after the SOL loss", 3, None),
+            ("old", "SOLUSDT", "BitGet", "Funding drama", 4, Some(100)),
+        ] {
+            insert_test_trade(&conn, id, &[
+                ("pair", &pair),
+                ("exchange", &exchange),
+                ("notes", &notes),
+                ("trade_date", &trade_date),
+                ("deleted_at", &deleted_at),
+            ]);
+        }
+        conn.execute_batch(
+            "INSERT INTO tags (id, name, created_at, updated_at) VALUES ('t1', 'Breakout', 0, 0), ('t2', 'Range', 0, 0);
+             INSERT INTO trade_tags (trade_id, tag_id, created_at) VALUES ('btc', 't1', 0), ('eth', 't2', 0);",
+        ).unwrap();
+        rebuild_trade_search(&conn).unwrap();
+        conn
+    }
+
+    fn search(conn: &Connection, query: &str) -> Vec<String> {
+        let match_query = build_match_query(query).unwrap();
+        find_matches(conn, &match_query, false, 10).unwrap().into_iter().map(|h| h.trade_id).collect()
+    }
+
+    fn count(conn: &Connection, table: &str) -> i64 {
+        conn.query_row(&format!("SELECT COUNT(*) FROM {}", table), [], |row| row.get(0)).unwrap()
+    }
+
+    #[test]
+    fn test_search_ranks_and_filters() {
+        let conn = setup();
+
+        // Every word must match, as a prefix, and soft-deleted trades are hidden
+        assert_eq!(search(&conn, "sol funding"), ["sol"]);
+        // A pair match outranks a mention in the notes
+        assert_eq!(search(&conn, "sol"), ["sol", "eth"]);
+        assert_eq!(search(&conn, "breakout"), ["btc"]);
+        assert_eq!(search(&conn, "range"), ["eth"]);
+        assert_eq!(search(&conn, "btc/usdt"), ["btc"]);
+
+        let hit = &find_matches(&conn, &build_match_query("overnight").unwrap(), false, 10).unwrap()[0];
+        assert!(hit.snippet.contains("**overnight**"));
+        assert!(hit.rank > 0.0);
+    }
+
+    #[test]
+    fn test_index_follows_writes() {
+        let conn = setup();
+        conn.execute("UPDATE trades SET notes = 'funding again' WHERE id = 'eth'", []).unwrap();
+        reindex_trade(&conn, "eth", false).unwrap();
+        conn.execute("UPDATE tags SET name = 'Trend' WHERE id = 't1'", []).unwrap();
+        let trade_ids = tagged_trade_ids(&conn, "t1").unwrap();
+        assert_eq!(trade_ids, ["btc"]);
+        reindex_trades(&conn, &trade_ids).unwrap();
+
+        assert_eq!(search(&conn, "funding again"), ["eth"]);
+        assert_eq!(search(&conn, "trend"), ["btc"]);
+        assert_eq!(search(&conn, "range"), ["eth"]);
+        // Old entries were replaced, not kept next to the new ones
+        assert_eq!(count(&conn, "trade_search"), 4);
+
+        conn.execute("DELETE FROM trades WHERE id = 'btc'", []).unwrap();
+        prune_search_index(&conn).unwrap();
+        assert!(search(&conn, "trend").is_empty());
+        assert_eq!((count(&conn, "trade_search"), count(&conn, "trade_search_keys")), (3, 3));
+        assert_eq!(rebuild_trade_search(&conn).unwrap(), 3);
+    }
+
+    #[test]
+    fn test_match_query_escapes_user_input() {
+        assert_eq!(build_match_query("  "), None);
+        assert_eq!(build_match_query("- *"), None);
+        assert_eq!(build_match_query("SOL funding").unwrap(), "\"SOL\"* \"funding\"*");
+        assert_eq!(build_match_query("notes:\"x OR").unwrap(), "\"notes:\"\"x\"* \"OR\"*");
+    }
+}
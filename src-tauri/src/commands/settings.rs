@@ -1,5 +1,6 @@
 use tauri::State;
 use crate::db::Database;
+use chrono_tz::Tz;
 use crate::models::{Settings, UpdateSettingsInput};
 
 #[tauri::command]
@@ -7,7 +8,7 @@ pub async fn get_settings(db: State<'_, Database>) -> Result<Settings, String> {
     let conn = db.conn.lock().map_err(|e| e.to_string())?;
 
     let settings = conn.query_row(
-        "SELECT id, initial_capital, current_r_percent, default_min_rr, default_leverage, currency, enable_position_monitor, enable_api_connections, created_at, updated_at FROM settings WHERE id = 1",
+        "SELECT id, initial_capital, current_r_percent, default_min_rr, default_leverage, currency, enable_position_monitor, enable_api_connections, timezone, created_at, updated_at FROM settings WHERE id = 1",
         [],
         |row| {
             Ok(Settings {
@@ -19,8 +20,9 @@ pub async fn get_settings(db: State<'_, Database>) -> Result<Settings, String> {
                 currency: row.get(5)?,
                 enable_position_monitor: row.get::<_, i32>(6)? == 1,
                 enable_api_connections: row.get::<_, i32>(7)? == 1,
-                created_at: row.get(8)?,
-                updated_at: row.get(9)?,
+                timezone: row.get(8)?,
+                created_at: row.get(9)?,
+                updated_at: row.get(10)?,
             })
         },
     ).map_err(|e| e.to_string())?;
@@ -68,6 +70,11 @@ pub async fn update_settings(
             updates.push("enable_api_connections = ?");
             values.push(Box::new(val as i32));
         }
+        if let Some(val) = settings.timezone {
+            val.parse::<Tz>().map_err(|_| format!("Unknown timezone: {}", val))?;
+            updates.push("timezone = ?");
+            values.push(Box::new(val));
+        }
 
         updates.push("updated_at = strftime('%s', 'now')");
 
@@ -79,3 +86,11 @@ pub async fn update_settings(
 
     get_settings(db).await
 }
+
+/// Load the user's timezone from settings, falling back to UTC if unset or unrecognized
+pub(crate) fn load_timezone(conn: &rusqlite::Connection) -> Tz {
+    conn.query_row("SELECT timezone FROM settings WHERE id = 1", [], |row| row.get::<_, String>(0))
+        .ok()
+        .and_then(|name| name.parse().ok())
+        .unwrap_or(Tz::UTC)
+}
@@ -1,5 +1,14 @@
 use tauri::State;
+use crate::commands::capital::load_account_balance;
+use crate::commands::daily_stats::{load_daily_closed_pnl, load_window_aggregate, TradeAggregate};
+use crate::commands::monte_carlo::percentile;
+use crate::commands::settings::load_timezone;
 use crate::db::Database;
+use chrono::{Datelike, TimeZone};
+use chrono_tz::Tz;
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+use rusqlite::types::Value;
 use serde::{Deserialize, Serialize};
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -10,7 +19,10 @@ pub struct DashboardStats {
     pub breakevens: i32,
     pub open_trades: i32,
     pub win_rate: f64,
-    pub total_pnl: f64,
+    pub total_pnl: f64, // gross realized PnL, before fees and funding
+    pub total_fees: f64,
+    pub total_funding: f64, // positive = received
+    pub net_pnl: f64,       // total_pnl - total_fees + total_funding
     pub gross_profit: f64,
     pub gross_loss: f64,
     pub profit_factor: f64,
@@ -27,244 +39,1408 @@ pub struct EquityCurvePoint {
     pub trade_count: i32,
 }
 
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RMultipleBucket {
+    pub lower: f64,
+    pub upper: f64,
+    pub count: i32,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RMultipleStats {
+    pub sample_size: i32,
+    pub bucket_width: f64,
+    pub buckets: Vec<RMultipleBucket>,
+    pub expectancy_r: f64,
+    pub expectancy_currency: f64,
+    pub std_dev_r: f64,
+    pub sqn: f64,
+    pub avg_win_r: f64,
+    pub avg_loss_r: f64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct UnderwaterPoint {
+    pub date: String,
+    pub cumulative_pnl: f64,
+    pub drawdown: f64,         // <= 0, distance below the running peak
+    pub drawdown_percent: f64, // drawdown as % of contributed capital
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DrawdownAnalysis {
+    pub max_drawdown: f64,
+    pub max_drawdown_percent: f64, // % of contributed capital (initial capital + net deposits)
+    pub max_drawdown_r: f64,       // in units of account balance * current_r_percent
+    pub max_drawdown_peak_date: Option<String>,
+    pub max_drawdown_trough_date: Option<String>,
+    pub current_drawdown: f64,
+    pub current_drawdown_percent: f64,
+    pub current_drawdown_r: f64,
+    pub longest_drawdown_days: i64,
+    pub current_drawdown_days: i64,
+    pub net_profit: f64,
+    pub recovery_factor: f64,
+    pub underwater: Vec<UnderwaterPoint>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BreakdownGroup {
+    pub key: String,
+    pub sort_order: i64, // natural order of the key (weekday index, hour, leverage floor)
+    pub stats: DashboardStats,
+}
+
+/// Trade columns needed to compute per-group dashboard stats
+#[derive(Debug, Clone)]
+struct StatTrade {
+    pair: String,
+    exchange: String,
+    position_type: String,
+    import_source: String,
+    trade_date: i64,
+    close_date: Option<i64>,
+    leverage: i32,
+    status: String,
+    total_pnl: Option<f64>,
+    fees: Option<f64>,
+    funding: Option<f64>,
+    effective_weighted_rr: Option<f64>,
+    pnl_in_r: Option<f64>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct StreakLengthCount {
+    pub length: i32,
+    pub count: i32,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ConditionalStreakStat {
+    pub after_status: String, // "WIN" | "LOSS"
+    pub streak_length: i32,   // trades preceded by at least this many consecutive after_status
+    pub sample_size: i32,
+    pub wins: i32,
+    pub losses: i32,
+    pub breakevens: i32,
+    pub win_rate: f64,
+    pub avg_pnl: f64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct StreakAnalysis {
+    pub longest_win_streak: i32,
+    pub longest_loss_streak: i32,
+    pub current_streak_status: Option<String>,
+    pub current_streak_length: i32,
+    pub win_streak_distribution: Vec<StreakLengthCount>,
+    pub loss_streak_distribution: Vec<StreakLengthCount>,
+    pub after_losses: Vec<ConditionalStreakStat>,
+    pub after_wins: Vec<ConditionalStreakStat>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ConfidenceInterval {
+    pub level: f64,         // e.g. 95.0
+    pub lower: Option<f64>, // None when unbounded or undefined (e.g. profit factor with no losses)
+    pub upper: Option<f64>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MetricConfidence {
+    pub metric: String, // "win_rate" | "expectancy_r" | "profit_factor"
+    pub sample_size: i32, // trades that feed this metric
+    pub estimate: Option<f64>,
+    pub intervals: Vec<ConfidenceInterval>,
+    pub insufficient_sample: bool, // sample_size below min_sample_size, treat the estimate as noise
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BootstrapStats {
+    pub seed: u32, // pass back in to reproduce the same intervals
+    pub iterations: u32,
+    pub dimension: Option<String>,
+    pub key: Option<String>,
+    pub sample_size: i32,
+    pub min_sample_size: i32,
+    pub insufficient_sample: bool,
+    pub metrics: Vec<MetricConfidence>,
+}
+
+const LEVERAGE_BUCKETS: [(i32, i32, &str); 6] = [
+    (i32::MIN, 5, "1-5x"),
+    (6, 10, "6-10x"),
+    (11, 20, "11-20x"),
+    (21, 50, "21-50x"),
+    (51, 100, "51-100x"),
+    (101, i32::MAX, "100x+"),
+];
+
+const DEFAULT_R_BUCKET_WIDTH: f64 = 0.5;
+/// Histogram size limit, a tiny width or an outlier R would otherwise allocate without bound
+const MAX_R_BUCKETS: i64 = 1000;
+
+const DEFAULT_BOOTSTRAP_ITERATIONS: u32 = 2000;
+const MAX_BOOTSTRAP_ITERATIONS: u32 = 100_000;
+const DEFAULT_CONFIDENCE_LEVELS: [f64; 2] = [90.0, 95.0];
+/// Below this many trades a statistic is flagged as likely noise
+const MIN_RELIABLE_SAMPLE: i32 = 30;
+
+/// Resolved close_date window for stats queries
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub(crate) struct DateWindow {
+    pub start: Option<i64>,
+    pub end: Option<i64>,
+}
+
+impl DateWindow {
+    /// Explicit start/end timestamps take precedence over a named dashboard range: "today" (since
+    /// local midnight), the rolling "week", "month", "3months", "6months" and "year" (last 7, 30,
+    /// 90, 180 and 365 days) and the calendar "this_week" (since Monday), "this_month" and "this_year".
+    pub(crate) fn resolve(
+        date_range: Option<&str>,
+        start_date: Option<i64>,
+        end_date: Option<i64>,
+        tz: &Tz,
+    ) -> Self {
+        if start_date.is_some() || end_date.is_some() {
+            return DateWindow { start: start_date, end: end_date };
+        }
+
+        let start = date_range.and_then(|range| range_start(range, chrono::Utc::now().with_timezone(tz)));
+        DateWindow { start, end: None }
+    }
+
+    /// close_date filter clause and params for this window
+    pub(crate) fn filter(&self) -> (&'static str, Vec<i64>) {
+        // SAFETY: the returned filter is always one of these compile-time constant strings,
+        // never user-provided input. This pattern is safe from SQL injection as long as the filter
+        // remains a hardcoded string. All dynamic values are passed through parameterized queries.
+        match (self.start, self.end) {
+            (Some(start), Some(end)) => ("AND close_date >= ? AND close_date <= ?", vec![start, end]),
+            (Some(start), None) => ("AND close_date >= ?", vec![start]),
+            (None, Some(end)) => ("AND close_date <= ?", vec![end]),
+            (None, None) => ("", vec![]),
+        }
+    }
+}
+
+/// Restricts stats to trades linked to any of the given tags or strategies
+#[derive(Debug, Clone, Default, PartialEq)]
+pub(crate) struct TagFilter {
+    pub tag_ids: Vec<String>,
+}
+
+impl TagFilter {
+    pub(crate) fn new(tag_ids: Option<Vec<String>>) -> Self {
+        TagFilter { tag_ids: tag_ids.unwrap_or_default() }
+    }
+
+    pub(crate) fn is_empty(&self) -> bool {
+        self.tag_ids.is_empty()
+    }
+
+    /// Trade id clause and params, one placeholder per tag (ids are never formatted into SQL)
+    pub(crate) fn filter(&self) -> (String, Vec<String>) {
+        if self.tag_ids.is_empty() {
+            return (String::new(), vec![]);
+        }
+        let placeholders = vec!["?"; self.tag_ids.len()].join(", ");
+        (
+            format!("AND id IN (SELECT trade_id FROM trade_tags WHERE tag_id IN ({}))", placeholders),
+            self.tag_ids.clone(),
+        )
+    }
+}
+
+/// close_date window and tag filter as one clause, with params in placeholder order
+pub(crate) fn trade_filter(window: DateWindow, tags: &TagFilter) -> (String, Vec<Value>) {
+    let (date_filter, date_params) = window.filter();
+    let (tag_filter, tag_params) = tags.filter();
+    let mut params: Vec<Value> = date_params.into_iter().map(Value::from).collect();
+    params.extend(tag_params.into_iter().map(Value::from));
+    (format!("{} {}", date_filter, tag_filter), params)
+}
+
+/// Start of a named dashboard range ending `now`, in the user's timezone
+fn range_start(date_range: &str, now: chrono::DateTime<Tz>) -> Option<i64> {
+    let tz = now.timezone();
+    let today = now.date_naive();
+    // Same local time n days ago, a DST change in between does not shift the window by an hour
+    let days_back = |days: u64| {
+        now.checked_sub_days(chrono::Days::new(days))
+            .map_or(now.timestamp() - days as i64 * 24 * 60 * 60, |start| start.timestamp())
+    };
+    match date_range {
+        "today" => Some(local_midnight(today, &tz)),
+        "week" => Some(days_back(7)),
+        "month" => Some(days_back(30)),
+        "3months" => Some(days_back(90)),
+        "6months" => Some(days_back(180)),
+        "year" => Some(days_back(365)),
+        "this_week" => period_start_of_date(today, "week").ok().map(|date| local_midnight(date, &tz)),
+        "this_month" => period_start_of_date(today, "month").ok().map(|date| local_midnight(date, &tz)),
+        "this_year" => today.with_ordinal(1).map(|date| local_midnight(date, &tz)),
+        _ => None,
+    }
+}
+
+/// Unix timestamp of the start of `date` in `tz`: local midnight, or the first valid local
+/// time after it when a DST transition skips midnight (e.g. America/Santiago)
+pub(crate) fn local_midnight(date: chrono::NaiveDate, tz: &Tz) -> i64 {
+    let midnight = date.and_hms_opt(0, 0, 0).unwrap();
+    (0..=24)
+        .find_map(|hours| tz.from_local_datetime(&(midnight + chrono::Duration::hours(hours))).earliest())
+        // Unreachable, no transition skips more than a day
+        .unwrap_or_else(|| tz.from_utc_datetime(&midnight))
+        .timestamp()
+}
+
+/// Local calendar date of a timestamp in `tz`
+pub(crate) fn local_date(timestamp: i64, tz: &Tz) -> Result<chrono::NaiveDate, String> {
+    chrono::DateTime::from_timestamp(timestamp, 0)
+        .map(|dt| dt.with_timezone(tz).date_naive())
+        .ok_or(format!("Invalid timestamp: {}", timestamp))
+}
+
+/// First day of the day/week/month bucket containing `timestamp` in `tz` (weeks start on Monday)
+pub(crate) fn period_start(timestamp: i64, tz: &Tz, interval: &str) -> Result<chrono::NaiveDate, String> {
+    period_start_of_date(local_date(timestamp, tz)?, interval)
+}
+
+/// First day of the day/week/month bucket containing a local date
+fn period_start_of_date(date: chrono::NaiveDate, interval: &str) -> Result<chrono::NaiveDate, String> {
+    match interval {
+        "day" => Ok(date),
+        "week" => Ok(date - chrono::Duration::days(date.weekday().num_days_from_monday() as i64)),
+        "month" => Ok(date.with_day(1).unwrap()),
+        _ => Err(format!("Unsupported interval: {}", interval)),
+    }
+}
+
+/// Mean and sample standard deviation of a series (0.0 when undefined)
+fn mean_and_std_dev(values: &[f64]) -> (f64, f64) {
+    if values.is_empty() {
+        return (0.0, 0.0);
+    }
+    let n = values.len() as f64;
+    let mean = values.iter().sum::<f64>() / n;
+    if values.len() < 2 {
+        return (mean, 0.0);
+    }
+    let variance = values.iter().map(|v| (v - mean).powi(2)).sum::<f64>() / (n - 1.0);
+    (mean, variance.sqrt())
+}
+
+/// Build the R-multiple histogram, expectancy and SQN from (pnl_in_r, total_pnl) samples
+fn compute_r_multiple_stats(samples: &[(f64, f64)], bucket_width: f64) -> Result<RMultipleStats, String> {
+    let r_values: Vec<f64> = samples.iter().map(|(r, _)| *r).collect();
+    let (expectancy_r, std_dev_r) = mean_and_std_dev(&r_values);
+
+    let expectancy_currency = if samples.is_empty() {
+        0.0
+    } else {
+        samples.iter().map(|(_, pnl)| pnl).sum::<f64>() / samples.len() as f64
+    };
+
+    // Van Tharp's System Quality Number: sqrt(N) * expectancy / std dev
+    let sqn = if std_dev_r > 0.0 {
+        (r_values.len() as f64).sqrt() * expectancy_r / std_dev_r
+    } else {
+        0.0
+    };
+
+    let wins: Vec<f64> = r_values.iter().copied().filter(|r| *r > 0.0).collect();
+    let losses: Vec<f64> = r_values.iter().copied().filter(|r| *r < 0.0).collect();
+    let avg_win_r = mean_and_std_dev(&wins).0;
+    let avg_loss_r = mean_and_std_dev(&losses).0;
+
+    // Contiguous buckets from the lowest to the highest populated one so gaps chart as zero.
+    // The epsilon keeps values sitting exactly on a boundary (e.g. 0.3 / 0.1) in the upper bucket.
+    let bucket_index = |r: f64| ((r / bucket_width) + 1e-9).floor() as i64;
+    let mut buckets = Vec::new();
+    if let (Some(min), Some(max)) = (
+        r_values.iter().map(|r| bucket_index(*r)).min(),
+        r_values.iter().map(|r| bucket_index(*r)).max(),
+    ) {
+        let span = max.checked_sub(min).filter(|span| *span < MAX_R_BUCKETS).ok_or(format!(
+            "R-multiple histogram would need more than {} buckets, use a wider bucket width",
+            MAX_R_BUCKETS
+        ))?;
+        let mut counts = vec![0; (span + 1) as usize];
+        for r in &r_values {
+            counts[(bucket_index(*r) - min) as usize] += 1;
+        }
+        for (offset, count) in counts.into_iter().enumerate() {
+            let lower = (min + offset as i64) as f64 * bucket_width;
+            buckets.push(RMultipleBucket {
+                lower,
+                upper: lower + bucket_width,
+                count,
+            });
+        }
+    }
+
+    Ok(RMultipleStats {
+        sample_size: r_values.len() as i32,
+        bucket_width,
+        buckets,
+        expectancy_r,
+        expectancy_currency,
+        std_dev_r,
+        sqn,
+        avg_win_r,
+        avg_loss_r,
+    })
+}
+
+/// Walk the equity curve tracking the running peak to derive drawdown depth, duration and recovery
+fn compute_drawdown(points: &[EquityCurvePoint], capital: f64, one_r: f64) -> DrawdownAnalysis {
+    let parse_date = |date: &str| chrono::NaiveDate::parse_from_str(date, "%Y-%m-%d").ok();
+    let percent_of_capital = |amount: f64| {
+        if capital > 0.0 { amount / capital * 100.0 } else { 0.0 }
+    };
+    let in_r = |amount: f64| if one_r > 0.0 { amount / one_r } else { 0.0 };
+
+    // Equity starts at the baseline (0 cumulative PnL), which counts as the first peak
+    let mut peak = 0.0;
+    let mut peak_date = points.first().and_then(|p| parse_date(&p.date));
+    let mut peak_label = points.first().map(|p| p.date.clone());
+
+    let mut max_drawdown = 0.0;
+    let mut max_drawdown_peak_date = None;
+    let mut max_drawdown_trough_date = None;
+    let mut longest_drawdown_days = 0;
+    let mut below_peak = false; // equity went under the peak since peak_date
+    let mut underwater = Vec::with_capacity(points.len());
+
+    for point in points {
+        let date = parse_date(&point.date);
+
+        if point.cumulative_pnl >= peak {
+            // New high (or full recovery) closes any running drawdown period
+            if below_peak && let (Some(start), Some(end)) = (peak_date, date) {
+                longest_drawdown_days = longest_drawdown_days.max((end - start).num_days());
+            }
+            below_peak = false;
+            peak = point.cumulative_pnl;
+            peak_date = date;
+            peak_label = Some(point.date.clone());
+        }
+
+        let drawdown = point.cumulative_pnl - peak;
+        below_peak |= drawdown < 0.0;
+        if -drawdown > max_drawdown {
+            max_drawdown = -drawdown;
+            max_drawdown_peak_date = peak_label.clone();
+            max_drawdown_trough_date = Some(point.date.clone());
+        }
+
+        underwater.push(UnderwaterPoint {
+            date: point.date.clone(),
+            cumulative_pnl: point.cumulative_pnl,
+            drawdown,
+            drawdown_percent: percent_of_capital(drawdown),
+        });
+    }
+
+    let current_drawdown = underwater.last().map(|p| -p.drawdown).unwrap_or(0.0);
+    let current_drawdown_days = if current_drawdown > 0.0 {
+        match (peak_date, points.last().and_then(|p| parse_date(&p.date))) {
+            (Some(start), Some(end)) => (end - start).num_days(),
+            _ => 0,
+        }
+    } else {
+        0
+    };
+    longest_drawdown_days = longest_drawdown_days.max(current_drawdown_days);
+
+    let net_profit = points.last().map(|p| p.cumulative_pnl).unwrap_or(0.0);
+    let recovery_factor = if max_drawdown > 0.0 { net_profit / max_drawdown } else { 0.0 };
+
+    DrawdownAnalysis {
+        max_drawdown,
+        max_drawdown_percent: percent_of_capital(max_drawdown),
+        max_drawdown_r: in_r(max_drawdown),
+        max_drawdown_peak_date,
+        max_drawdown_trough_date,
+        current_drawdown,
+        current_drawdown_percent: percent_of_capital(current_drawdown),
+        current_drawdown_r: in_r(current_drawdown),
+        longest_drawdown_days,
+        current_drawdown_days,
+        net_profit,
+        recovery_factor,
+        underwater,
+    }
+}
+
+/// Realized PnL and closed trade count per local close day, like the daily_stats closed columns
+fn daily_closed_pnl(trades: &[StatTrade], tz: &Tz) -> Result<std::collections::BTreeMap<chrono::NaiveDate, (f64, i32)>, String> {
+    let mut days = std::collections::BTreeMap::new();
+    for trade in trades.iter().filter(|t| matches!(t.status.as_str(), "WIN" | "LOSS" | "BE")) {
+        if let (Some(close_date), Some(pnl)) = (trade.close_date, trade.total_pnl) {
+            let entry = days.entry(local_date(close_date, tz)?).or_insert((0.0, 0));
+            entry.0 += pnl;
+            entry.1 += 1;
+        }
+    }
+    Ok(days)
+}
+
+/// Compute the core DashboardStats fields in memory, with the same semantics as get_dashboard_stats
+fn summarize_trades<'a>(trades: impl IntoIterator<Item = &'a StatTrade>) -> DashboardStats {
+    let mut aggregate = TradeAggregate::default();
+    for trade in trades {
+        aggregate.add(&trade.status, trade.total_pnl, trade.fees, trade.funding, trade.effective_weighted_rr);
+    }
+    aggregate.to_dashboard_stats()
+}
+
+pub(crate) fn leverage_bucket(leverage: i32) -> (String, i64) {
+    LEVERAGE_BUCKETS
+        .iter()
+        .find(|(low, high, _)| leverage >= *low && leverage <= *high)
+        .map(|(low, _, label)| (label.to_string(), (*low).max(1) as i64))
+        .unwrap_or_else(|| (format!("{}x", leverage), leverage as i64))
+}
+
+/// Resolve the group key and its natural sort order for a trade along a breakdown dimension
+fn breakdown_key(trade: &StatTrade, dimension: &str, tz: &Tz) -> Result<(String, i64), String> {
+    use chrono::Timelike;
+
+    // Weekday and hour are evaluated in the user's timezone
+    let trade_time = || {
+        chrono::DateTime::from_timestamp(trade.trade_date, 0)
+            .map(|dt| dt.with_timezone(tz))
+            .ok_or(format!("Invalid timestamp: {} for trade_date", trade.trade_date))
+    };
+
+    match dimension {
+        "pair" => Ok((trade.pair.clone(), 0)),
+        "exchange" => Ok((trade.exchange.clone(), 0)),
+        "position_type" => Ok((trade.position_type.clone(), 0)),
+        "import_source" => Ok((trade.import_source.clone(), 0)),
+        "weekday" => {
+            let weekday = trade_time()?.weekday();
+            Ok((weekday.to_string(), weekday.num_days_from_monday() as i64))
+        }
+        "hour" => {
+            let hour = trade_time()?.hour();
+            Ok((format!("{:02}:00", hour), hour as i64))
+        }
+        "leverage" => Ok(leverage_bucket(trade.leverage)),
+        _ => Err(format!("Unsupported breakdown dimension: {}", dimension)),
+    }
+}
+
+/// Group trades along a dimension and sort the groups ("key", "total_trades", "win_rate",
+/// "total_pnl", "net_pnl", "total_fees", "profit_factor" or "avg_effective_rr")
+fn compute_breakdown(
+    trades: &[StatTrade],
+    dimension: &str,
+    tz: &Tz,
+    sort_by: &str,
+    descending: bool,
+) -> Result<Vec<BreakdownGroup>, String> {
+    let mut grouped: std::collections::HashMap<String, (i64, Vec<&StatTrade>)> =
+        std::collections::HashMap::new();
+
+    for trade in trades {
+        let (key, sort_order) = breakdown_key(trade, dimension, tz)?;
+        grouped.entry(key).or_insert_with(|| (sort_order, Vec::new())).1.push(trade);
+    }
+
+    let mut groups: Vec<BreakdownGroup> = grouped
+        .into_iter()
+        .map(|(key, (sort_order, members))| BreakdownGroup {
+            key,
+            sort_order,
+            stats: summarize_trades(members),
+        })
+        .collect();
+
+    let metric = |g: &BreakdownGroup| -> f64 {
+        match sort_by {
+            "total_trades" => g.stats.total_trades as f64,
+            "win_rate" => g.stats.win_rate,
+            "net_pnl" => g.stats.net_pnl,
+            "total_fees" => g.stats.total_fees,
+            "profit_factor" => g.stats.profit_factor,
+            "avg_effective_rr" => g.stats.avg_effective_rr,
+            _ => g.stats.total_pnl,
+        }
+    };
+
+    match sort_by {
+        "key" => groups.sort_by(|a, b| a.sort_order.cmp(&b.sort_order).then_with(|| a.key.cmp(&b.key))),
+        "total_trades" | "win_rate" | "total_pnl" | "net_pnl" | "total_fees" | "profit_factor"
+        | "avg_effective_rr" => {
+            groups.sort_by(|a, b| metric(a).total_cmp(&metric(b)).then_with(|| a.key.cmp(&b.key)))
+        }
+        _ => return Err(format!("Unsupported sort field: {}", sort_by)),
+    }
+    if descending {
+        groups.reverse();
+    }
+
+    Ok(groups)
+}
+
+/// Streaks and conditional outcomes over closed trades in close_date order.
+/// A breakeven trade ends the running streak without starting a new one.
+fn compute_streaks(outcomes: &[(String, f64)], max_conditional_streak: i32) -> StreakAnalysis {
+    let mut win_runs: std::collections::BTreeMap<i32, i32> = std::collections::BTreeMap::new();
+    let mut loss_runs: std::collections::BTreeMap<i32, i32> = std::collections::BTreeMap::new();
+    let mut current: Option<(&str, i32)> = None;
+
+    let mut close_run = |run: Option<(&str, i32)>| match run {
+        Some(("WIN", len)) => *win_runs.entry(len).or_insert(0) += 1,
+        Some(("LOSS", len)) => *loss_runs.entry(len).or_insert(0) += 1,
+        _ => {}
+    };
+
+    // Conditional accumulators indexed by streak length - 1: (wins, losses, breakevens, pnl sum)
+    let max_n = max_conditional_streak.max(0) as usize;
+    let mut after_losses = vec![(0, 0, 0, 0.0); max_n];
+    let mut after_wins = vec![(0, 0, 0, 0.0); max_n];
+
+    for (status, pnl) in outcomes {
+        // Record this trade against the streak that preceded it
+        if let Some((run_status, len)) = current {
+            let buckets = if run_status == "LOSS" { &mut after_losses } else { &mut after_wins };
+            for bucket in buckets.iter_mut().take((len as usize).min(max_n)) {
+                match status.as_str() {
+                    "WIN" => bucket.0 += 1,
+                    "LOSS" => bucket.1 += 1,
+                    _ => bucket.2 += 1,
+                }
+                bucket.3 += pnl;
+            }
+        }
+
+        current = match (current, status.as_str()) {
+            (Some((run_status, len)), s) if run_status == s => Some((run_status, len + 1)),
+            (previous, "WIN") => {
+                close_run(previous);
+                Some(("WIN", 1))
+            }
+            (previous, "LOSS") => {
+                close_run(previous);
+                Some(("LOSS", 1))
+            }
+            (previous, _) => {
+                close_run(previous);
+                None
+            }
+        };
+    }
+    close_run(current);
+
+    let distribution = |runs: &std::collections::BTreeMap<i32, i32>| -> Vec<StreakLengthCount> {
+        runs.iter().map(|(length, count)| StreakLengthCount { length: *length, count: *count }).collect()
+    };
+    let conditional = |after_status: &str, buckets: &[(i32, i32, i32, f64)]| -> Vec<ConditionalStreakStat> {
+        buckets
+            .iter()
+            .enumerate()
+            .map(|(i, (wins, losses, breakevens, pnl_sum))| {
+                let sample_size = wins + losses + breakevens;
+                ConditionalStreakStat {
+                    after_status: after_status.to_string(),
+                    streak_length: i as i32 + 1,
+                    sample_size,
+                    wins: *wins,
+                    losses: *losses,
+                    breakevens: *breakevens,
+                    win_rate: if wins + losses > 0 { *wins as f64 / (wins + losses) as f64 * 100.0 } else { 0.0 },
+                    avg_pnl: if sample_size > 0 { pnl_sum / sample_size as f64 } else { 0.0 },
+                }
+            })
+            .collect()
+    };
+
+    StreakAnalysis {
+        longest_win_streak: win_runs.keys().next_back().copied().unwrap_or(0),
+        longest_loss_streak: loss_runs.keys().next_back().copied().unwrap_or(0),
+        current_streak_status: current.map(|(status, _)| status.to_string()),
+        current_streak_length: current.map(|(_, len)| len).unwrap_or(0),
+        win_streak_distribution: distribution(&win_runs),
+        loss_streak_distribution: distribution(&loss_runs),
+        after_losses: conditional("LOSS", &after_losses),
+        after_wins: conditional("WIN", &after_wins),
+    }
+}
+
+/// Dashboard stats of closed trades in a window. Whole days come from the daily_stats aggregates,
+/// only partial edge days touch trades. Aggregates are not kept per tag, so tagged stats are computed from trades.
+pub(crate) fn load_window_stats(
+    conn: &rusqlite::Connection,
+    window: DateWindow,
+    tags: &TagFilter,
+    tz: &Tz,
+) -> Result<DashboardStats, String> {
+    if tags.is_empty() {
+        Ok(load_window_aggregate(conn, window, tz)?.to_dashboard_stats())
+    } else {
+        Ok(summarize_trades(&load_stat_trades(conn, window, tags)?))
+    }
+}
+
 #[tauri::command]
 pub async fn get_dashboard_stats(
     db: State<'_, Database>,
     date_range: Option<String>,
+    start_date: Option<i64>,
+    end_date: Option<i64>,
+    tag_ids: Option<Vec<String>>,
 ) -> Result<DashboardStats, String> {
     let conn = db.conn.lock().map_err(|e| e.to_string())?;
 
-    // Calculate date threshold based on range
-    let date_threshold = match date_range.as_deref() {
-        Some("today") => {
-            Some(chrono::Utc::now().date_naive().and_hms_opt(0, 0, 0).unwrap().and_utc().timestamp())
-        },
-        Some("week") => {
-            Some(chrono::Utc::now().timestamp() - (7 * 24 * 60 * 60))
-        },
-        Some("month") => {
-            Some(chrono::Utc::now().timestamp() - (30 * 24 * 60 * 60))
-        },
-        Some("3months") => {
-            Some(chrono::Utc::now().timestamp() - (90 * 24 * 60 * 60))
-        },
-        Some("6months") => {
-            Some(chrono::Utc::now().timestamp() - (180 * 24 * 60 * 60))
-        },
-        Some("year") => {
-            Some(chrono::Utc::now().timestamp() - (365 * 24 * 60 * 60))
-        },
-        _ => None,
-    };
-
-    // Build filter clause and params
-    // SAFETY: date_filter is always a compile-time constant string ("AND close_date >= ?" or ""),
-    // never user-provided input. This pattern is safe from SQL injection as long as date_filter
-    // remains a hardcoded string. All dynamic values are passed through parameterized queries.
-    let (date_filter, date_params): (&str, Vec<i64>) = match date_threshold {
-        Some(threshold) => ("AND close_date >= ?", vec![threshold]),
-        None => ("", vec![]),
-    };
-
-    // Total trades
-    let total_trades: i32 = conn.query_row(
-        &format!("SELECT COUNT(*) FROM trades WHERE deleted_at IS NULL {}", date_filter),
-        rusqlite::params_from_iter(date_params.iter()),
-        |row| row.get(0),
-    ).unwrap_or(0);
+    let tz = load_timezone(&conn);
+    let window = DateWindow::resolve(date_range.as_deref(), start_date, end_date, &tz);
+    let tags = TagFilter::new(tag_ids);
+    let mut stats = load_window_stats(&conn, window, &tags, &tz)?;
 
-    // Status counts
-    let wins: i32 = conn.query_row(
-        &format!("SELECT COUNT(*) FROM trades WHERE deleted_at IS NULL AND status = 'WIN' {}", date_filter),
-        rusqlite::params_from_iter(date_params.iter()),
+    // Open trades are counted regardless of the date window
+    let (tag_filter, tag_params) = tags.filter();
+    stats.open_trades = conn.query_row(
+        &format!("SELECT COUNT(*) FROM trades WHERE deleted_at IS NULL AND status = 'OPEN' {}", tag_filter),
+        rusqlite::params_from_iter(tag_params.iter()),
         |row| row.get(0),
     ).unwrap_or(0);
 
-    let losses: i32 = conn.query_row(
-        &format!("SELECT COUNT(*) FROM trades WHERE deleted_at IS NULL AND status = 'LOSS' {}", date_filter),
-        rusqlite::params_from_iter(date_params.iter()),
-        |row| row.get(0),
-    ).unwrap_or(0);
+    Ok(stats)
+}
 
-    let breakevens: i32 = conn.query_row(
-        &format!("SELECT COUNT(*) FROM trades WHERE deleted_at IS NULL AND status = 'BE' {}", date_filter),
-        rusqlite::params_from_iter(date_params.iter()),
-        |row| row.get(0),
-    ).unwrap_or(0);
+#[tauri::command]
+pub async fn get_equity_curve(
+    db: State<'_, Database>,
+    date_range: Option<String>,
+    start_date: Option<i64>,
+    end_date: Option<i64>,
+    interval: Option<String>,
+    tag_ids: Option<Vec<String>>,
+) -> Result<Vec<EquityCurvePoint>, String> {
+    let conn = db.conn.lock().map_err(|e| e.to_string())?;
 
-    let open_trades: i32 = conn.query_row(
-        "SELECT COUNT(*) FROM trades WHERE deleted_at IS NULL AND status = 'OPEN'",
-        [],
-        |row| row.get(0),
-    ).unwrap_or(0);
+    let tz = load_timezone(&conn);
+    let window = DateWindow::resolve(date_range.as_deref(), start_date, end_date, &tz);
+    let tags = TagFilter::new(tag_ids);
+    load_equity_curve(&conn, window, &tags, &tz, interval.as_deref().unwrap_or("day"))
+}
 
-    // Win rate
-    let closed_trades = wins + losses;
-    let win_rate = if closed_trades > 0 {
-        (wins as f64 / closed_trades as f64) * 100.0
+/// Build the equity curve from closed trades, bucketed by day/week/month in the user's timezone.
+/// Shared by the equity chart and drawdown analysis so both always agree on how equity is computed.
+fn load_equity_curve(
+    conn: &rusqlite::Connection,
+    window: DateWindow,
+    tags: &TagFilter,
+    tz: &Tz,
+    interval: &str,
+) -> Result<Vec<EquityCurvePoint>, String> {
+    let daily = if tags.is_empty() {
+        load_daily_closed_pnl(conn, window, tz)?
     } else {
-        0.0
+        daily_closed_pnl(&load_stat_trades(conn, window, tags)?, tz)?
     };
 
-    // Total P&L
-    let total_pnl: f64 = conn.query_row(
-        &format!("SELECT COALESCE(SUM(total_pnl), 0.0) FROM trades WHERE deleted_at IS NULL AND total_pnl IS NOT NULL {}", date_filter),
-        rusqlite::params_from_iter(date_params.iter()),
-        |row| row.get(0),
-    ).unwrap_or(0.0);
+    // Group days by period start (YYYY-MM-DD), BTreeMap keeps them sorted
+    let mut periods: std::collections::BTreeMap<String, (f64, i32)> = std::collections::BTreeMap::new();
+    for (date, (pnl, count)) in daily {
+        let period = period_start_of_date(date, interval)?.format("%Y-%m-%d").to_string();
+        let entry = periods.entry(period).or_insert((0.0, 0));
+        entry.0 += pnl;
+        entry.1 += count;
+    }
 
-    // Gross profit
-    let gross_profit: f64 = conn.query_row(
-        &format!("SELECT COALESCE(SUM(total_pnl), 0.0) FROM trades WHERE deleted_at IS NULL AND total_pnl > 0 {}", date_filter),
-        rusqlite::params_from_iter(date_params.iter()),
-        |row| row.get(0),
-    ).unwrap_or(0.0);
+    let mut cumulative_pnl = 0.0;
+    let result = periods
+        .into_iter()
+        .map(|(date, (daily_pnl, trade_count))| {
+            cumulative_pnl += daily_pnl;
+            EquityCurvePoint {
+                date,
+                cumulative_pnl,
+                daily_pnl,
+                trade_count,
+            }
+        })
+        .collect();
 
-    // Gross loss
-    let gross_loss: f64 = conn.query_row(
-        &format!("SELECT COALESCE(ABS(SUM(total_pnl)), 0.0) FROM trades WHERE deleted_at IS NULL AND total_pnl < 0 {}", date_filter),
-        rusqlite::params_from_iter(date_params.iter()),
-        |row| row.get(0),
-    ).unwrap_or(0.0);
+    Ok(result)
+}
+
+#[tauri::command]
+pub async fn get_r_multiple_stats(
+    db: State<'_, Database>,
+    date_range: Option<String>,
+    start_date: Option<i64>,
+    end_date: Option<i64>,
+    bucket_width: Option<f64>,
+    tag_ids: Option<Vec<String>>,
+) -> Result<RMultipleStats, String> {
+    let bucket_width = bucket_width.unwrap_or(DEFAULT_R_BUCKET_WIDTH);
+    if !bucket_width.is_finite() || bucket_width <= 0.0 {
+        return Err(format!("Invalid bucket width: {}", bucket_width));
+    }
 
-    // Profit factor
+    let conn = db.conn.lock().map_err(|e| e.to_string())?;
+
+    let tz = load_timezone(&conn);
+    let window = DateWindow::resolve(date_range.as_deref(), start_date, end_date, &tz);
+    let (date_filter, date_params) = trade_filter(window, &TagFilter::new(tag_ids));
+
+    let mut stmt = conn.prepare(&format!(
+        "SELECT pnl_in_r, COALESCE(total_pnl, 0.0)
+         FROM trades
+         WHERE deleted_at IS NULL
+         AND pnl_in_r IS NOT NULL
+         AND status IN ('WIN', 'LOSS', 'BE')
+         {}",
+        date_filter
+    )).map_err(|e| e.to_string())?;
+
+    let samples = stmt.query_map(rusqlite::params_from_iter(date_params.iter()), |row| {
+        Ok((row.get::<_, f64>(0)?, row.get::<_, f64>(1)?))
+    })
+    .map_err(|e| e.to_string())?
+    .collect::<Result<Vec<_>, _>>()
+    .map_err(|e| e.to_string())?;
+
+    compute_r_multiple_stats(&samples, bucket_width)
+}
+
+#[tauri::command]
+pub async fn get_drawdown_analysis(
+    db: State<'_, Database>,
+    date_range: Option<String>,
+    start_date: Option<i64>,
+    end_date: Option<i64>,
+    tag_ids: Option<Vec<String>>,
+) -> Result<DrawdownAnalysis, String> {
+    let conn = db.conn.lock().map_err(|e| e.to_string())?;
+
+    let r_percent: f64 = conn
+        .query_row("SELECT current_r_percent FROM settings WHERE id = 1", [], |row| row.get(0))
+        .map_err(|e| format!("Failed to load settings: {}", e))?;
+    let account = load_account_balance(&conn)?;
+
+    let tz = load_timezone(&conn);
+    let window = DateWindow::resolve(date_range.as_deref(), start_date, end_date, &tz);
+    let points = load_equity_curve(&conn, window, &TagFilter::new(tag_ids), &tz, "day")?;
+
+    Ok(compute_drawdown(&points, account.contributed_capital, account.balance * r_percent))
+}
+
+/// Win rate, expectancy (R) and profit factor of a set of closed trades, NaN when undefined.
+/// Profit factor is infinite with profits and no losses, like DashboardStats.
+fn bootstrap_metrics<'a>(trades: impl IntoIterator<Item = &'a StatTrade>) -> [f64; 3] {
+    let (mut wins, mut losses, mut r_sum, mut r_count) = (0, 0, 0.0, 0);
+    let (mut gross_profit, mut gross_loss) = (0.0, 0.0);
+    for trade in trades {
+        match trade.status.as_str() {
+            "WIN" => wins += 1,
+            "LOSS" => losses += 1,
+            _ => {}
+        }
+        if let Some(r) = trade.pnl_in_r {
+            r_sum += r;
+            r_count += 1;
+        }
+        match trade.total_pnl {
+            Some(pnl) if pnl > 0.0 => gross_profit += pnl,
+            Some(pnl) if pnl < 0.0 => gross_loss -= pnl,
+            _ => {}
+        }
+    }
+
+    let win_rate = if wins + losses > 0 { wins as f64 / (wins + losses) as f64 * 100.0 } else { f64::NAN };
+    let expectancy_r = if r_count > 0 { r_sum / r_count as f64 } else { f64::NAN };
     let profit_factor = if gross_loss > 0.0 {
         gross_profit / gross_loss
     } else if gross_profit > 0.0 {
         f64::INFINITY
     } else {
-        0.0
+        f64::NAN
     };
+    [win_rate, expectancy_r, profit_factor]
+}
 
-    // Average effective RR
-    let avg_effective_rr: f64 = conn.query_row(
-        &format!("SELECT COALESCE(AVG(effective_weighted_rr), 0.0) FROM trades WHERE deleted_at IS NULL AND effective_weighted_rr IS NOT NULL {}", date_filter),
-        rusqlite::params_from_iter(date_params.iter()),
-        |row| row.get(0),
-    ).unwrap_or(0.0);
+/// Percentile bootstrap: resample the closed trades with replacement `iterations` times and take
+/// the central `level`% of each metric's resampled values. Resamples where a metric is undefined are dropped.
+fn compute_bootstrap(
+    trades: &[&StatTrade],
+    levels: &[f64],
+    iterations: u32,
+    seed: u32,
+    min_sample_size: i32,
+) -> Vec<MetricConfidence> {
+    const METRICS: [&str; 3] = ["win_rate", "expectancy_r", "profit_factor"];
 
-    // Best trade
-    let best_trade: f64 = conn.query_row(
-        &format!("SELECT COALESCE(MAX(total_pnl), 0.0) FROM trades WHERE deleted_at IS NULL AND total_pnl IS NOT NULL {}", date_filter),
-        rusqlite::params_from_iter(date_params.iter()),
-        |row| row.get(0),
-    ).unwrap_or(0.0);
+    let mut rng = StdRng::seed_from_u64(seed as u64);
+    let mut resampled: [Vec<f64>; 3] = Default::default();
+    if !trades.is_empty() {
+        for _ in 0..iterations {
+            let sample = (0..trades.len()).map(|_| trades[rng.gen_range(0..trades.len())]);
+            for (values, value) in resampled.iter_mut().zip(bootstrap_metrics(sample)) {
+                if !value.is_nan() {
+                    values.push(value);
+                }
+            }
+        }
+    }
 
-    // Worst trade
-    let worst_trade: f64 = conn.query_row(
-        &format!("SELECT COALESCE(MIN(total_pnl), 0.0) FROM trades WHERE deleted_at IS NULL AND total_pnl IS NOT NULL {}", date_filter),
-        rusqlite::params_from_iter(date_params.iter()),
-        |row| row.get(0),
-    ).unwrap_or(0.0);
-
-    Ok(DashboardStats {
-        total_trades,
-        wins,
-        losses,
-        breakevens,
-        open_trades,
-        win_rate,
-        total_pnl,
-        gross_profit,
-        gross_loss,
-        profit_factor,
-        avg_effective_rr,
-        best_trade,
-        worst_trade,
+    let estimates = bootstrap_metrics(trades.iter().copied());
+    let sample_sizes = [
+        trades.iter().filter(|t| t.status == "WIN" || t.status == "LOSS").count(),
+        trades.iter().filter(|t| t.pnl_in_r.is_some()).count(),
+        trades.iter().filter(|t| t.total_pnl.is_some_and(|pnl| pnl != 0.0)).count(),
+    ];
+
+    METRICS
+        .iter()
+        .zip(resampled.iter_mut())
+        .zip(estimates.iter().zip(sample_sizes))
+        .map(|((metric, values), (estimate, sample_size))| {
+            values.sort_by(|a, b| a.total_cmp(b));
+            let bound = |p: f64| {
+                let value = percentile(values, p);
+                (!values.is_empty() && value.is_finite()).then_some(value)
+            };
+            MetricConfidence {
+                metric: metric.to_string(),
+                sample_size: sample_size as i32,
+                estimate: estimate.is_finite().then_some(*estimate),
+                intervals: levels
+                    .iter()
+                    .map(|level| ConfidenceInterval {
+                        level: *level,
+                        lower: bound((100.0 - level) / 2.0),
+                        upper: bound(100.0 - (100.0 - level) / 2.0),
+                    })
+                    .collect(),
+                insufficient_sample: (sample_size as i32) < min_sample_size,
+            }
+        })
+        .collect()
+}
+
+/// Load the trades that feed dashboard-style stats, using the dashboard date filter
+fn load_stat_trades(
+    conn: &rusqlite::Connection,
+    window: DateWindow,
+    tags: &TagFilter,
+) -> Result<Vec<StatTrade>, String> {
+    let (filter, params) = trade_filter(window, tags);
+
+    let mut stmt = conn.prepare(&format!(
+        "SELECT pair, exchange, position_type, import_source, trade_date, leverage,
+                status, total_pnl, fees, funding, effective_weighted_rr, pnl_in_r, close_date
+         FROM trades
+         WHERE deleted_at IS NULL
+         {}",
+        filter
+    )).map_err(|e| e.to_string())?;
+
+    let trades = stmt.query_map(rusqlite::params_from_iter(params.iter()), |row| {
+        Ok(StatTrade {
+            pair: row.get(0)?,
+            exchange: row.get(1)?,
+            position_type: row.get(2)?,
+            import_source: row.get(3)?,
+            trade_date: row.get(4)?,
+            leverage: row.get(5)?,
+            status: row.get(6)?,
+            total_pnl: row.get(7)?,
+            fees: row.get(8)?,
+            funding: row.get(9)?,
+            effective_weighted_rr: row.get(10)?,
+            pnl_in_r: row.get(11)?,
+            close_date: row.get(12)?,
+        })
     })
+    .map_err(|e| e.to_string())?
+    .collect::<Result<Vec<_>, _>>()
+    .map_err(|e| e.to_string())?;
+
+    Ok(trades)
 }
 
+/// Dashboard stats grouped by pair, exchange, position_type, import_source, weekday, hour or leverage
 #[tauri::command]
-pub async fn get_equity_curve(
+#[allow(clippy::too_many_arguments)]
+pub async fn get_performance_breakdown(
     db: State<'_, Database>,
+    dimension: String,
     date_range: Option<String>,
-) -> Result<Vec<EquityCurvePoint>, String> {
+    start_date: Option<i64>,
+    end_date: Option<i64>,
+    sort_by: Option<String>,
+    descending: Option<bool>,
+    tag_ids: Option<Vec<String>>,
+) -> Result<Vec<BreakdownGroup>, String> {
     let conn = db.conn.lock().map_err(|e| e.to_string())?;
 
-    // Calculate date threshold based on range
-    let date_threshold = match date_range.as_deref() {
-        Some("today") => {
-            Some(chrono::Utc::now().date_naive().and_hms_opt(0, 0, 0).unwrap().and_utc().timestamp())
-        },
-        Some("week") => {
-            Some(chrono::Utc::now().timestamp() - (7 * 24 * 60 * 60))
-        },
-        Some("month") => {
-            Some(chrono::Utc::now().timestamp() - (30 * 24 * 60 * 60))
-        },
-        Some("3months") => {
-            Some(chrono::Utc::now().timestamp() - (90 * 24 * 60 * 60))
-        },
-        Some("6months") => {
-            Some(chrono::Utc::now().timestamp() - (180 * 24 * 60 * 60))
-        },
-        Some("year") => {
-            Some(chrono::Utc::now().timestamp() - (365 * 24 * 60 * 60))
-        },
-        _ => None,
-    };
+    let tz = load_timezone(&conn);
+    let window = DateWindow::resolve(date_range.as_deref(), start_date, end_date, &tz);
+    let trades = load_stat_trades(&conn, window, &TagFilter::new(tag_ids))?;
 
-    // Build filter clause and params
-    // SAFETY: date_filter is always a compile-time constant string ("AND close_date >= ?" or ""),
-    // never user-provided input. This pattern is safe from SQL injection as long as date_filter
-    // remains a hardcoded string. All dynamic values are passed through parameterized queries.
-    let (date_filter, date_params): (&str, Vec<i64>) = match date_threshold {
-        Some(threshold) => ("AND close_date >= ?", vec![threshold]),
-        None => ("", vec![]),
-    };
+    compute_breakdown(
+        &trades,
+        &dimension,
+        &tz,
+        sort_by.as_deref().unwrap_or("total_pnl"),
+        descending.unwrap_or(true),
+    )
+}
+
+#[tauri::command]
+pub async fn get_streak_analysis(
+    db: State<'_, Database>,
+    date_range: Option<String>,
+    start_date: Option<i64>,
+    end_date: Option<i64>,
+    max_conditional_streak: Option<i32>,
+    tag_ids: Option<Vec<String>>,
+) -> Result<StreakAnalysis, String> {
+    let conn = db.conn.lock().map_err(|e| e.to_string())?;
+
+    let tz = load_timezone(&conn);
+    let window = DateWindow::resolve(date_range.as_deref(), start_date, end_date, &tz);
+    let (date_filter, date_params) = trade_filter(window, &TagFilter::new(tag_ids));
 
-    // Query all closed trades with close_date
     let mut stmt = conn.prepare(&format!(
-        "SELECT close_date, total_pnl
+        "SELECT status, COALESCE(total_pnl, 0.0)
          FROM trades
-         WHERE close_date IS NOT NULL
-         AND total_pnl IS NOT NULL
+         WHERE deleted_at IS NULL
+         AND close_date IS NOT NULL
          AND status IN ('WIN', 'LOSS', 'BE')
          {}
-         ORDER BY close_date ASC",
+         ORDER BY close_date ASC, created_at ASC",
         date_filter
     )).map_err(|e| e.to_string())?;
 
-    let trades = stmt.query_map(rusqlite::params_from_iter(date_params.iter()), |row| {
-        Ok((
-            row.get::<_, i64>(0)?,
-            row.get::<_, f64>(1)?,
-        ))
-    }).map_err(|e| e.to_string())?;
+    let outcomes = stmt.query_map(rusqlite::params_from_iter(date_params.iter()), |row| {
+        Ok((row.get::<_, String>(0)?, row.get::<_, f64>(1)?))
+    })
+    .map_err(|e| e.to_string())?
+    .collect::<Result<Vec<_>, _>>()
+    .map_err(|e| e.to_string())?;
 
-    // Group by date and calculate cumulative P&L
-    let mut daily_map: std::collections::HashMap<String, (f64, i32)> = std::collections::HashMap::new();
+    Ok(compute_streaks(&outcomes, max_conditional_streak.unwrap_or(5)))
+}
 
-    for trade in trades {
-        let (close_timestamp, pnl) = trade.map_err(|e| e.to_string())?;
+/// Seeded bootstrap confidence intervals for win rate, expectancy and profit factor of closed trades,
+/// overall or for one breakdown group (dimension + key, e.g. "pair" + "BTC/USDT").
+/// confidence_levels are percentages and default to 90 and 95.
+#[tauri::command]
+#[allow(clippy::too_many_arguments)]
+pub async fn get_bootstrap_stats(
+    db: State<'_, Database>,
+    date_range: Option<String>,
+    start_date: Option<i64>,
+    end_date: Option<i64>,
+    dimension: Option<String>,
+    key: Option<String>,
+    confidence_levels: Option<Vec<f64>>,
+    iterations: Option<u32>,
+    seed: Option<u32>,
+    tag_ids: Option<Vec<String>>,
+) -> Result<BootstrapStats, String> {
+    let iterations = iterations.unwrap_or(DEFAULT_BOOTSTRAP_ITERATIONS);
+    if iterations == 0 || iterations > MAX_BOOTSTRAP_ITERATIONS {
+        return Err(format!("iterations must be between 1 and {}", MAX_BOOTSTRAP_ITERATIONS));
+    }
+    let levels = confidence_levels.unwrap_or_else(|| DEFAULT_CONFIDENCE_LEVELS.to_vec());
+    if levels.iter().any(|level| !(*level > 0.0 && *level < 100.0)) {
+        return Err("confidence_levels must be percentages between 0 and 100".to_string());
+    }
+    if dimension.is_some() != key.is_some() {
+        return Err("dimension and key must be provided together".to_string());
+    }
 
-        // Convert timestamp to date string (YYYY-MM-DD)
-        let date = chrono::DateTime::from_timestamp(close_timestamp, 0)
-            .ok_or(format!("Invalid timestamp: {} for trade with close_date {}", close_timestamp, close_timestamp))?
-            .format("%Y-%m-%d")
-            .to_string();
+    let conn = db.conn.lock().map_err(|e| e.to_string())?;
 
-        let entry = daily_map.entry(date).or_insert((0.0, 0));
-        entry.0 += pnl;
-        entry.1 += 1;
+    let tz = load_timezone(&conn);
+    let window = DateWindow::resolve(date_range.as_deref(), start_date, end_date, &tz);
+    let trades = load_stat_trades(&conn, window, &TagFilter::new(tag_ids))?;
+
+    let mut closed = Vec::new();
+    for trade in trades.iter().filter(|t| matches!(t.status.as_str(), "WIN" | "LOSS" | "BE")) {
+        if let (Some(dimension), Some(key)) = (&dimension, &key)
+            && breakdown_key(trade, dimension, &tz)?.0 != *key
+        {
+            continue;
+        }
+        closed.push(trade);
     }
 
-    // Sort by date and calculate cumulative
-    let mut sorted_dates: Vec<_> = daily_map.into_iter().collect();
-    sorted_dates.sort_by(|a, b| a.0.cmp(&b.0));
+    let seed = seed.unwrap_or_else(rand::random);
+    let sample_size = closed.len() as i32;
 
-    let mut cumulative_pnl = 0.0;
-    let mut result: Vec<EquityCurvePoint> = Vec::new();
+    Ok(BootstrapStats {
+        seed,
+        iterations,
+        dimension,
+        key,
+        sample_size,
+        min_sample_size: MIN_RELIABLE_SAMPLE,
+        insufficient_sample: sample_size < MIN_RELIABLE_SAMPLE,
+        metrics: compute_bootstrap(&closed, &levels, iterations, seed, MIN_RELIABLE_SAMPLE),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_r_multiple_stats_expectancy_and_sqn() {
+        let samples = vec![(2.0, 200.0), (-1.0, -100.0), (3.0, 300.0), (-1.0, -100.0)];
+        let stats = compute_r_multiple_stats(&samples, 1.0).unwrap();
+
+        assert_eq!(stats.sample_size, 4);
+        assert!((stats.expectancy_r - 0.75).abs() < 1e-9);
+        assert!((stats.expectancy_currency - 75.0).abs() < 1e-9);
+        // Sample variance: (1.5625 + 3.0625 + 5.0625 + 3.0625) / 3 = 4.25
+        assert!((stats.std_dev_r - 4.25_f64.sqrt()).abs() < 1e-9);
+        assert!((stats.sqn - 2.0 * 0.75 / 4.25_f64.sqrt()).abs() < 1e-9);
+        assert!((stats.avg_win_r - 2.5).abs() < 1e-9);
+        assert!((stats.avg_loss_r + 1.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_r_multiple_histogram_is_contiguous() {
+        let samples = vec![(-1.0, -100.0), (0.25, 25.0), (1.6, 160.0)];
+        let stats = compute_r_multiple_stats(&samples, 0.5).unwrap();
+
+        let lowers: Vec<f64> = stats.buckets.iter().map(|b| b.lower).collect();
+        assert_eq!(lowers, vec![-1.0, -0.5, 0.0, 0.5, 1.0, 1.5]);
+        let counts: Vec<i32> = stats.buckets.iter().map(|b| b.count).collect();
+        assert_eq!(counts, vec![1, 0, 1, 0, 0, 1]);
+    }
+
+    #[test]
+    fn test_r_multiple_stats_empty_sample() {
+        let stats = compute_r_multiple_stats(&[], 0.5).unwrap();
+        assert_eq!(stats.sample_size, 0);
+        assert!(stats.buckets.is_empty());
+        assert_eq!(stats.sqn, 0.0);
+    }
+
+    #[test]
+    fn test_r_multiple_histogram_bucket_limit() {
+        // One outlier from a trade with a tiny 1R, or a tiny width, must not allocate billions of buckets
+        assert!(compute_r_multiple_stats(&[(-1.0, -100.0), (1e12, 5.0)], 0.5).is_err());
+        assert!(compute_r_multiple_stats(&[(-1.0, -100.0), (2.0, 200.0)], 1e-12).is_err());
+        assert!(compute_r_multiple_stats(&[(f64::MIN, 0.0), (f64::MAX, 0.0)], 0.5).is_err());
+        assert_eq!(compute_r_multiple_stats(&[(-1.0, -100.0), (2.0, 200.0)], 0.01).unwrap().buckets.len(), 301);
+    }
 
-    for (date, (daily_pnl, trade_count)) in sorted_dates {
-        cumulative_pnl += daily_pnl;
-        result.push(EquityCurvePoint {
-            date,
+    fn equity_point(date: &str, cumulative_pnl: f64) -> EquityCurvePoint {
+        EquityCurvePoint {
+            date: date.to_string(),
             cumulative_pnl,
-            daily_pnl,
-            trade_count,
-        });
+            daily_pnl: 0.0,
+            trade_count: 1,
+        }
+    }
+
+    #[test]
+    fn test_drawdown_depth_duration_and_recovery() {
+        let points = vec![
+            equity_point("2026-01-01", 100.0),
+            equity_point("2026-01-03", 40.0),
+            equity_point("2026-01-05", -20.0),
+            equity_point("2026-01-08", 150.0),
+            equity_point("2026-01-10", 120.0),
+        ];
+        let dd = compute_drawdown(&points, 10000.0, 200.0);
+
+        assert!((dd.max_drawdown - 120.0).abs() < 1e-9);
+        assert!((dd.max_drawdown_percent - 1.2).abs() < 1e-9);
+        assert!((dd.max_drawdown_r - 0.6).abs() < 1e-9);
+        assert_eq!(dd.max_drawdown_peak_date.as_deref(), Some("2026-01-01"));
+        assert_eq!(dd.max_drawdown_trough_date.as_deref(), Some("2026-01-05"));
+        // Peak on the 1st, recovered on the 8th
+        assert_eq!(dd.longest_drawdown_days, 7);
+
+        assert!((dd.current_drawdown - 30.0).abs() < 1e-9);
+        assert_eq!(dd.current_drawdown_days, 2);
+        assert!((dd.recovery_factor - 1.0).abs() < 1e-9);
+
+        let underwater: Vec<f64> = dd.underwater.iter().map(|p| p.drawdown).collect();
+        assert_eq!(underwater, vec![0.0, -60.0, -120.0, 0.0, -30.0]);
+    }
+
+    #[test]
+    fn test_drawdown_duration_needs_equity_below_peak() {
+        // Every point is a new high, the gaps between them are not drawdowns
+        let points = vec![
+            equity_point("2026-03-01", 100.0),
+            equity_point("2026-03-31", 250.0),
+            equity_point("2026-05-15", 250.0),
+            equity_point("2026-06-20", 400.0),
+        ];
+        let dd = compute_drawdown(&points, 10000.0, 200.0);
+
+        assert_eq!(dd.max_drawdown, 0.0);
+        assert_eq!(dd.longest_drawdown_days, 0);
+        assert_eq!(dd.current_drawdown_days, 0);
+    }
+
+    #[test]
+    fn test_drawdown_from_losing_start() {
+        // Losses before any gain are measured from the zero baseline
+        let points = vec![
+            equity_point("2026-02-01", -50.0),
+            equity_point("2026-02-02", -80.0),
+        ];
+        let dd = compute_drawdown(&points, 10000.0, 0.0);
+
+        assert!((dd.max_drawdown - 80.0).abs() < 1e-9);
+        assert_eq!(dd.max_drawdown_r, 0.0);
+        assert_eq!(dd.current_drawdown_days, 1);
+        assert!(dd.recovery_factor < 0.0);
+    }
+
+    fn stat_trade(pair: &str, trade_date: i64, leverage: i32, status: &str, pnl: f64) -> StatTrade {
+        StatTrade {
+            pair: pair.to_string(),
+            exchange: "BitGet".to_string(),
+            position_type: "LONG".to_string(),
+            import_source: "USER_CREATED".to_string(),
+            trade_date,
+            close_date: Some(trade_date),
+            leverage,
+            status: status.to_string(),
+            total_pnl: Some(pnl),
+            fees: None,
+            funding: None,
+            effective_weighted_rr: Some(pnl / 100.0),
+            pnl_in_r: Some(pnl / 100.0),
+        }
+    }
+
+    #[test]
+    fn test_summarize_trades_matches_dashboard_semantics() {
+        let trades = vec![
+            stat_trade("BTC/USDT", 0, 10, "WIN", 300.0),
+            stat_trade("BTC/USDT", 0, 10, "LOSS", -100.0),
+            stat_trade("BTC/USDT", 0, 10, "BE", 0.0),
+        ];
+        let stats = summarize_trades(&trades);
+
+        assert_eq!(stats.total_trades, 3);
+        assert_eq!(stats.breakevens, 1);
+        assert!((stats.win_rate - 50.0).abs() < 1e-9);
+        assert!((stats.profit_factor - 3.0).abs() < 1e-9);
+        assert!((stats.best_trade - 300.0).abs() < 1e-9);
+        assert!((stats.worst_trade + 100.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_summarize_trades_net_pnl() {
+        let mut win = stat_trade("BTC/USDT", 0, 10, "WIN", 300.0);
+        win.fees = Some(12.0);
+        win.funding = Some(-3.0);
+        let mut loss = stat_trade("BTC/USDT", 0, 10, "LOSS", -100.0);
+        loss.fees = Some(8.0);
+        loss.funding = Some(1.0);
+        let unknown_fees = stat_trade("BTC/USDT", 0, 10, "WIN", 50.0);
+
+        let stats = summarize_trades(&[win, loss, unknown_fees]);
+
+        assert!((stats.total_pnl - 250.0).abs() < 1e-9);
+        assert!((stats.total_fees - 20.0).abs() < 1e-9);
+        assert!((stats.total_funding + 2.0).abs() < 1e-9);
+        assert!((stats.net_pnl - 228.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_breakdown_by_pair_sorted_by_pnl() {
+        let trades = vec![
+            stat_trade("BTC/USDT", 0, 10, "WIN", 300.0),
+            stat_trade("ETH/USDT", 0, 10, "LOSS", -100.0),
+            stat_trade("BTC/USDT", 0, 10, "LOSS", -50.0),
+        ];
+        let groups = compute_breakdown(&trades, "pair", &Tz::UTC, "total_pnl", true).unwrap();
+
+        assert_eq!(groups.len(), 2);
+        assert_eq!(groups[0].key, "BTC/USDT");
+        assert_eq!(groups[0].stats.total_trades, 2);
+        assert!((groups[0].stats.total_pnl - 250.0).abs() < 1e-9);
+        assert_eq!(groups[1].key, "ETH/USDT");
+    }
+
+    #[test]
+    fn test_breakdown_by_weekday_and_leverage() {
+        // 2024-01-01 was a Monday, 2024-01-03 a Wednesday
+        let monday = 1704067200;
+        let wednesday = monday + 2 * 86400;
+        let trades = vec![
+            stat_trade("BTC/USDT", wednesday, 25, "WIN", 100.0),
+            stat_trade("BTC/USDT", monday, 3, "LOSS", -100.0),
+        ];
+
+        let by_day = compute_breakdown(&trades, "weekday", &Tz::UTC, "key", false).unwrap();
+        let days: Vec<&str> = by_day.iter().map(|g| g.key.as_str()).collect();
+        assert_eq!(days, vec!["Mon", "Wed"]);
+
+        let by_leverage = compute_breakdown(&trades, "leverage", &Tz::UTC, "key", false).unwrap();
+        let buckets: Vec<&str> = by_leverage.iter().map(|g| g.key.as_str()).collect();
+        assert_eq!(buckets, vec!["1-5x", "21-50x"]);
+
+        assert!(compute_breakdown(&trades, "strategy", &Tz::UTC, "key", false).is_err());
+    }
+
+    fn outcomes(statuses: &[&str]) -> Vec<(String, f64)> {
+        statuses
+            .iter()
+            .map(|s| {
+                let pnl = match *s {
+                    "WIN" => 100.0,
+                    "LOSS" => -50.0,
+                    _ => 0.0,
+                };
+                (s.to_string(), pnl)
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_streak_lengths_and_distribution() {
+        let streaks = compute_streaks(
+            &outcomes(&["WIN", "WIN", "LOSS", "LOSS", "LOSS", "BE", "WIN", "LOSS", "LOSS"]),
+            3,
+        );
+
+        assert_eq!(streaks.longest_win_streak, 2);
+        assert_eq!(streaks.longest_loss_streak, 3);
+        assert_eq!(streaks.current_streak_status.as_deref(), Some("LOSS"));
+        assert_eq!(streaks.current_streak_length, 2);
+
+        let wins: Vec<(i32, i32)> = streaks.win_streak_distribution.iter().map(|s| (s.length, s.count)).collect();
+        assert_eq!(wins, vec![(1, 1), (2, 1)]);
+        let losses: Vec<(i32, i32)> = streaks.loss_streak_distribution.iter().map(|s| (s.length, s.count)).collect();
+        assert_eq!(losses, vec![(2, 1), (3, 1)]);
+    }
+
+    #[test]
+    fn test_win_rate_after_consecutive_losses() {
+        let streaks = compute_streaks(&outcomes(&["LOSS", "WIN", "LOSS", "LOSS", "WIN", "LOSS", "LOSS", "LOSS"]), 2);
+
+        // After >=1 loss: WIN, LOSS, WIN, LOSS, LOSS -> 2 wins / 3 losses
+        let after_one = &streaks.after_losses[0];
+        assert_eq!((after_one.wins, after_one.losses), (2, 3));
+        assert!((after_one.win_rate - 40.0).abs() < 1e-9);
+
+        // After >=2 losses: WIN, LOSS -> 1 win / 1 loss
+        let after_two = &streaks.after_losses[1];
+        assert_eq!(after_two.sample_size, 2);
+        assert!((after_two.win_rate - 50.0).abs() < 1e-9);
+        assert!((after_two.avg_pnl - 25.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_bootstrap_is_seeded_and_brackets_estimate() {
+        let trades: Vec<StatTrade> = (0..40)
+            .map(|i| {
+                let (status, pnl) = if i % 4 == 0 { ("LOSS", -100.0) } else { ("WIN", 150.0) };
+                stat_trade("BTC/USDT", i, 10, status, pnl)
+            })
+            .collect();
+        let refs: Vec<&StatTrade> = trades.iter().collect();
+
+        let metrics = compute_bootstrap(&refs, &[90.0, 95.0], 500, 7, MIN_RELIABLE_SAMPLE);
+        assert_eq!(metrics.len(), 3);
+        let win_rate = &metrics[0];
+        assert_eq!(win_rate.estimate, Some(75.0));
+        assert!(!win_rate.insufficient_sample);
+
+        let (ci90, ci95) = (&win_rate.intervals[0], &win_rate.intervals[1]);
+        assert!(ci95.lower.unwrap() <= ci90.lower.unwrap() && ci90.lower.unwrap() < 75.0);
+        assert!(ci95.upper.unwrap() >= ci90.upper.unwrap() && ci90.upper.unwrap() > 75.0);
+        assert!((metrics[1].estimate.unwrap() - 0.875).abs() < 1e-9);
+
+        let again = compute_bootstrap(&refs, &[90.0, 95.0], 500, 7, MIN_RELIABLE_SAMPLE);
+        assert_eq!(again[2].intervals[1].lower, metrics[2].intervals[1].lower);
+    }
+
+    #[test]
+    fn test_bootstrap_flags_small_and_undefined_samples() {
+        let trades: Vec<StatTrade> = (0..3).map(|i| stat_trade("ETH/USDT", i, 10, "WIN", 50.0)).collect();
+        let refs: Vec<&StatTrade> = trades.iter().collect();
+        let metrics = compute_bootstrap(&refs, &[95.0], 100, 1, MIN_RELIABLE_SAMPLE);
+
+        assert!(metrics.iter().all(|m| m.insufficient_sample));
+        // No losses: profit factor is unbounded
+        assert_eq!(metrics[2].estimate, None);
+        assert_eq!(metrics[2].intervals[0].upper, None);
+
+        let empty = compute_bootstrap(&[], &[95.0], 100, 1, MIN_RELIABLE_SAMPLE);
+        assert_eq!(empty[0].sample_size, 0);
+        assert_eq!(empty[0].intervals[0].lower, None);
+    }
+
+    #[test]
+    fn test_period_start_uses_user_timezone() {
+        let tz: Tz = "Asia/Singapore".parse().unwrap();
+        // 2024-01-01 20:00 UTC is already 2024-01-02 04:00 in UTC+8
+        let ts = 1704139200;
+        assert_eq!(period_start(ts, &Tz::UTC, "day").unwrap().to_string(), "2024-01-01");
+        assert_eq!(period_start(ts, &tz, "day").unwrap().to_string(), "2024-01-02");
+        // 2024-01-02 is a Tuesday, so its week starts on Monday the 1st
+        assert_eq!(period_start(ts, &tz, "week").unwrap().to_string(), "2024-01-01");
+        assert_eq!(period_start(ts, &tz, "month").unwrap().to_string(), "2024-01-01");
+        assert!(period_start(ts, &tz, "quarter").is_err());
+    }
+
+    #[test]
+    fn test_date_window_prefers_explicit_bounds() {
+        let window = DateWindow::resolve(Some("week"), Some(100), Some(200), &Tz::UTC);
+        assert_eq!(window, DateWindow { start: Some(100), end: Some(200) });
+        assert_eq!(window.filter(), ("AND close_date >= ? AND close_date <= ?", vec![100, 200]));
+
+        assert_eq!(DateWindow::resolve(None, None, None, &Tz::UTC).filter().0, "");
+    }
+
+    #[test]
+    fn test_local_midnight() {
+        let tz: Tz = "Asia/Singapore".parse().unwrap();
+        let date = chrono::NaiveDate::from_ymd_opt(2024, 1, 2).unwrap();
+        // Midnight in UTC+8 is 16:00 UTC the previous day
+        assert_eq!(local_midnight(date, &tz), 1704124800);
+    }
+
+    #[test]
+    fn test_local_midnight_skipped_by_dst() {
+        // Santiago moved from UTC-4 to UTC-3 at midnight on 2022-09-11, the day starts at 01:00 (04:00 UTC)
+        let tz: Tz = "America/Santiago".parse().unwrap();
+        let date = chrono::NaiveDate::from_ymd_opt(2022, 9, 11).unwrap();
+        assert_eq!(local_midnight(date, &tz), 1662868800);
+        // The day before still starts at its own midnight, in UTC-4
+        assert_eq!(local_midnight(date.pred_opt().unwrap(), &tz), 1662868800 - 24 * 3600);
+    }
+
+    #[test]
+    fn test_named_ranges_roll_or_start_on_calendar_boundaries() {
+        let tz: Tz = "Europe/Paris".parse().unwrap();
+        // Wednesday 2024-04-10 15:00 local, summer time since 2024-03-31
+        let now = tz.with_ymd_and_hms(2024, 4, 10, 15, 0, 0).unwrap();
+        let local = |m, d, h| tz.with_ymd_and_hms(2024, m, d, h, 0, 0).unwrap().timestamp();
+
+        assert_eq!(range_start("today", now), Some(local(4, 10, 0)));
+        assert_eq!(range_start("week", now), Some(local(4, 3, 15)));
+        // Across the DST change: same local time, 30 days minus one hour ago
+        assert_eq!(range_start("month", now), Some(local(3, 11, 15)));
+        // Summer time at both ends
+        assert_eq!(range_start("year", now), Some(now.timestamp() - 365 * 24 * 3600));
+        assert_eq!(range_start("this_week", now), Some(local(4, 8, 0)));
+        assert_eq!(range_start("this_month", now), Some(local(4, 1, 0)));
+        assert_eq!(range_start("this_year", now), Some(local(1, 1, 0)));
+        assert_eq!(range_start("all", now), None);
     }
 
-    Ok(result)
 }
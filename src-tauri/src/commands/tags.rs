@@ -0,0 +1,298 @@
+use tauri::State;
+use crate::commands::revisions::{record_revision, trade_snapshot};
+use crate::commands::search::{reindex_trade, reindex_trades, tagged_trade_ids};
+use crate::db::Database;
+use crate::models::{CreateTagInput, Tag, TradeTag, UpdateTagInput};
+use chrono::Utc;
+
+fn map_row_to_tag(row: &rusqlite::Row) -> rusqlite::Result<Tag> {
+    Ok(Tag {
+        id: row.get("id")?,
+        name: row.get("name")?,
+        kind: row.get("kind")?,
+        color: row.get("color")?,
+        description: row.get("description")?,
+        created_at: row.get("created_at")?,
+        updated_at: row.get("updated_at")?,
+    })
+}
+
+/// Trimmed tag name, or an error for an invalid name or kind
+fn validate_tag(name: &str, kind: &str) -> Result<String, String> {
+    if kind != "TAG" && kind != "STRATEGY" {
+        return Err(format!("Invalid tag kind: {} (expected TAG or STRATEGY)", kind));
+    }
+    // Leading hashtags from notes-style tags are not part of the name
+    let name = name.trim().trim_start_matches('#').trim();
+    if name.is_empty() {
+        return Err("Tag name cannot be empty".to_string());
+    }
+    Ok(name.to_string())
+}
+
+/// Unique constraint violations become a readable duplicate-name error
+fn tag_write_error(e: rusqlite::Error, name: &str, kind: &str) -> String {
+    match e {
+        rusqlite::Error::SqliteFailure(err, _) if err.code == rusqlite::ErrorCode::ConstraintViolation => {
+            format!("A {} named '{}' already exists", kind.to_lowercase(), name)
+        }
+        e => e.to_string(),
+    }
+}
+
+fn get_tag_by_id(conn: &rusqlite::Connection, id: &str) -> Result<Tag, String> {
+    conn.query_row("SELECT * FROM tags WHERE id = ?", [id], map_row_to_tag)
+        .map_err(|e| e.to_string())
+}
+
+pub(crate) fn load_trade_tags(conn: &rusqlite::Connection, trade_id: &str) -> Result<Vec<Tag>, String> {
+    let mut stmt = conn.prepare(
+        "SELECT tags.* FROM tags
+         JOIN trade_tags ON trade_tags.tag_id = tags.id
+         WHERE trade_tags.trade_id = ?
+         ORDER BY tags.kind ASC, tags.name ASC"
+    ).map_err(|e| e.to_string())?;
+
+    let tags = stmt
+        .query_map([trade_id], map_row_to_tag)
+        .map_err(|e| e.to_string())?
+        .collect::<Result<Vec<_>, _>>()
+        .map_err(|e| e.to_string())?;
+
+    Ok(tags)
+}
+
+/// Every tag and every trade link, for backups
+pub(crate) fn load_all_tags(conn: &rusqlite::Connection) -> Result<(Vec<Tag>, Vec<TradeTag>), String> {
+    let mut stmt = conn
+        .prepare("SELECT * FROM tags ORDER BY created_at ASC")
+        .map_err(|e| e.to_string())?;
+    let tags = stmt
+        .query_map([], map_row_to_tag)
+        .map_err(|e| e.to_string())?
+        .collect::<Result<Vec<_>, _>>()
+        .map_err(|e| e.to_string())?;
+
+    let mut stmt = conn
+        .prepare("SELECT trade_id, tag_id, created_at FROM trade_tags ORDER BY trade_id, tag_id")
+        .map_err(|e| e.to_string())?;
+    let links = stmt
+        .query_map([], |row| {
+            Ok(TradeTag {
+                trade_id: row.get(0)?,
+                tag_id: row.get(1)?,
+                created_at: row.get(2)?,
+            })
+        })
+        .map_err(|e| e.to_string())?
+        .collect::<Result<Vec<_>, _>>()
+        .map_err(|e| e.to_string())?;
+
+    Ok((tags, links))
+}
+
+/// Copy a trade's tags onto another trade (used when duplicating)
+pub(crate) fn copy_trade_tags(conn: &rusqlite::Connection, from_trade_id: &str, to_trade_id: &str) -> Result<(), String> {
+    conn.execute(
+        "INSERT OR IGNORE INTO trade_tags (trade_id, tag_id, created_at)
+         SELECT ?, tag_id, ? FROM trade_tags WHERE trade_id = ?",
+        rusqlite::params![to_trade_id, Utc::now().timestamp(), from_trade_id],
+    ).map_err(|e| e.to_string())?;
+    Ok(())
+}
+
+/// All tags and strategies, or only one kind, alphabetically
+#[tauri::command]
+pub async fn get_tags(db: State<'_, Database>, kind: Option<String>) -> Result<Vec<Tag>, String> {
+    let conn = db.conn.lock().map_err(|e| e.to_string())?;
+
+    let mut stmt = conn.prepare(
+        "SELECT * FROM tags WHERE (?1 IS NULL OR kind = ?1) ORDER BY kind ASC, name ASC"
+    ).map_err(|e| e.to_string())?;
+
+    let tags = stmt
+        .query_map([kind], map_row_to_tag)
+        .map_err(|e| e.to_string())?
+        .collect::<Result<Vec<_>, _>>()
+        .map_err(|e| e.to_string())?;
+
+    Ok(tags)
+}
+
+#[tauri::command]
+pub async fn create_tag(db: State<'_, Database>, tag: CreateTagInput) -> Result<Tag, String> {
+    let name = validate_tag(&tag.name, &tag.kind)?;
+
+    let conn = db.conn.lock().map_err(|e| e.to_string())?;
+    let id = format!("TAG-{}-{}", Utc::now().timestamp_millis(), uuid::Uuid::new_v4());
+    let now = Utc::now().timestamp();
+
+    conn.execute(
+        "INSERT INTO tags (id, name, kind, color, description, created_at, updated_at)
+         VALUES (?, ?, ?, ?, ?, ?, ?)",
+        rusqlite::params![id, name, tag.kind, tag.color, tag.description, now, now],
+    ).map_err(|e| tag_write_error(e, &name, &tag.kind))?;
+
+    get_tag_by_id(&conn, &id)
+}
+
+#[tauri::command]
+pub async fn update_tag(db: State<'_, Database>, id: String, tag: UpdateTagInput) -> Result<Tag, String> {
+    let conn = db.conn.lock().map_err(|e| e.to_string())?;
+    let existing = get_tag_by_id(&conn, &id)?;
+
+    let kind = tag.kind.unwrap_or(existing.kind);
+    let name = validate_tag(tag.name.as_deref().unwrap_or(&existing.name), &kind)?;
+
+    conn.execute(
+        "UPDATE tags SET name = ?, kind = ?, color = ?, description = ?, updated_at = ? WHERE id = ?",
+        rusqlite::params![
+            name,
+            kind,
+            tag.color.or(existing.color),
+            tag.description.unwrap_or(existing.description),
+            Utc::now().timestamp(),
+            id,
+        ],
+    ).map_err(|e| tag_write_error(e, &name, &kind))?;
+
+    if name != existing.name {
+        reindex_trades(&conn, &tagged_trade_ids(&conn, &id)?)?;
+    }
+
+    get_tag_by_id(&conn, &id)
+}
+
+/// Delete a tag, unlinking it from every trade
+#[tauri::command]
+pub async fn delete_tag(db: State<'_, Database>, id: String) -> Result<(), String> {
+    let conn = db.conn.lock().map_err(|e| e.to_string())?;
+    let trade_ids = tagged_trade_ids(&conn, &id)?;
+    conn.execute("DELETE FROM trade_tags WHERE tag_id = ?", [&id])
+        .map_err(|e| e.to_string())?;
+    let deleted = conn
+        .execute("DELETE FROM tags WHERE id = ?", [&id])
+        .map_err(|e| e.to_string())?;
+    if deleted == 0 {
+        return Err(format!("Tag not found: {}", id));
+    }
+    reindex_trades(&conn, &trade_ids)
+}
+
+#[tauri::command]
+pub async fn get_trade_tags(db: State<'_, Database>, trade_id: String) -> Result<Vec<Tag>, String> {
+    let conn = db.conn.lock().map_err(|e| e.to_string())?;
+    load_trade_tags(&conn, &trade_id)
+}
+
+/// Replace the tags and strategies linked to a trade
+#[tauri::command]
+pub async fn set_trade_tags(
+    db: State<'_, Database>,
+    trade_id: String,
+    tag_ids: Vec<String>,
+) -> Result<Vec<Tag>, String> {
+    let mut conn = db.conn.lock().map_err(|e| e.to_string())?;
+
+    let trade_exists: bool = conn
+        .query_row("SELECT EXISTS(SELECT 1 FROM trades WHERE id = ?)", [&trade_id], |row| row.get(0))
+        .map_err(|e| e.to_string())?;
+    if !trade_exists {
+        return Err(format!("Trade not found: {}", trade_id));
+    }
+
+    let tx = conn.transaction().map_err(|e| e.to_string())?;
+    let before = trade_snapshot(&tx, &trade_id)?;
+    tx.execute("DELETE FROM trade_tags WHERE trade_id = ?", [&trade_id])
+        .map_err(|e| e.to_string())?;
+
+    let now = Utc::now().timestamp();
+    for tag_id in &tag_ids {
+        get_tag_by_id(&tx, tag_id).map_err(|_| format!("Tag not found: {}", tag_id))?;
+        tx.execute(
+            "INSERT OR IGNORE INTO trade_tags (trade_id, tag_id, created_at) VALUES (?, ?, ?)",
+            rusqlite::params![trade_id, tag_id, now],
+        ).map_err(|e| e.to_string())?;
+    }
+    record_revision(&tx, &trade_id, "UPDATE", "USER", before)?;
+    reindex_trade(&tx, &trade_id, false)?;
+    tx.commit().map_err(|e| e.to_string())?;
+
+    load_trade_tags(&conn, &trade_id)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::commands::stats::{trade_filter, DateWindow, TagFilter};
+    use crate::db::{insert_test_trade, test_connection};
+    use rusqlite::Connection;
+
+    fn setup() -> Connection {
+        let conn = test_connection();
+        for (id, close_date) in [("a", 100), ("b", 200), ("c", 300)] {
+            insert_test_trade(&conn, id, &[("close_date", &close_date)]);
+        }
+        for (id, name, kind) in [("t1", "breakout", "TAG"), ("t2", "news", "TAG"), ("s1", "Range", "STRATEGY")] {
+            conn.execute(
+                "INSERT INTO tags (id, name, kind, created_at, updated_at) VALUES (?, ?, ?, 0, 0)",
+                [id, name, kind],
+            ).unwrap();
+        }
+        for (trade_id, tag_id) in [("a", "t1"), ("a", "s1"), ("b", "t2"), ("c", "t1")] {
+            conn.execute("INSERT INTO trade_tags (trade_id, tag_id, created_at) VALUES (?, ?, 0)", [trade_id, tag_id]).unwrap();
+        }
+        conn
+    }
+
+    fn filtered_ids(conn: &Connection, window: DateWindow, tag_ids: &[&str]) -> Vec<String> {
+        let tags = TagFilter::new(Some(tag_ids.iter().map(|s| s.to_string()).collect()));
+        let (filter, params) = trade_filter(window, &tags);
+        let mut stmt = conn
+            .prepare(&format!("SELECT id FROM trades WHERE deleted_at IS NULL {} ORDER BY id", filter))
+            .unwrap();
+        stmt.query_map(rusqlite::params_from_iter(params.iter()), |row| row.get(0))
+            .unwrap()
+            .collect::<Result<Vec<_>, _>>()
+            .unwrap()
+    }
+
+    #[test]
+    fn test_tag_filter_matches_any_tag_within_window() {
+        let conn = setup();
+        let all = DateWindow::default();
+
+        assert_eq!(filtered_ids(&conn, all, &["t1"]), ["a", "c"]);
+        assert_eq!(filtered_ids(&conn, all, &["t2", "s1"]), ["a", "b"]);
+        assert_eq!(filtered_ids(&conn, all, &[]), ["a", "b", "c"]);
+        assert_eq!(filtered_ids(&conn, DateWindow { start: Some(150), end: None }, &["t1"]), ["c"]);
+    }
+
+    #[test]
+    fn test_links_follow_trade_and_tag_deletes() {
+        let conn = setup();
+        copy_trade_tags(&conn, "a", "b").unwrap();
+        assert_eq!(load_trade_tags(&conn, "b").unwrap().len(), 3);
+        // Strategies are listed before tags
+        assert_eq!(load_trade_tags(&conn, "a").unwrap()[0].name, "Range");
+
+        conn.execute("DELETE FROM trades WHERE id = 'a'", []).unwrap();
+        conn.execute("DELETE FROM tags WHERE id = 't2'", []).unwrap();
+        let (_, links) = load_all_tags(&conn).unwrap();
+        let pairs: Vec<(&str, &str)> = links.iter().map(|l| (l.trade_id.as_str(), l.tag_id.as_str())).collect();
+        assert_eq!(pairs, [("b", "s1"), ("b", "t1"), ("c", "t1")]);
+
+        // Names are unique per kind, case-insensitively
+        assert!(conn.execute(
+            "INSERT INTO tags (id, name, kind, created_at, updated_at) VALUES ('t3', 'BREAKOUT', 'TAG', 0, 0)", [],
+        ).is_err());
+    }
+
+    #[test]
+    fn test_validate_tag() {
+        assert_eq!(validate_tag("  #breakout ", "TAG").unwrap(), "breakout");
+        assert_eq!(validate_tag("London open", "STRATEGY").unwrap(), "London open");
+        assert!(validate_tag("#", "TAG").is_err());
+        assert!(validate_tag("range", "SETUP").is_err());
+    }
+}
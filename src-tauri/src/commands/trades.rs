@@ -1,11 +1,18 @@
-use tauri::State;
+use tauri::{AppHandle, State};
+use crate::commands::attachments::{attachments_dir, prune_attachment_files};
+use crate::commands::daily_stats::{invalidate_daily_stats, refresh_trade_days, trade_close_date};
+use crate::commands::review::validate_execution_score;
+use crate::commands::revisions::{record_revision, trade_snapshot};
+use crate::commands::search::{prune_search_index, reindex_trade};
+use crate::commands::stats::TagFilter;
+use crate::commands::tags::copy_trade_tags;
 use crate::db::Database;
 use crate::models::{Trade, CreateTradeInput, TradeFilters};
 use chrono::Utc;
 
 /// Helper function to map a database row to a Trade struct using named columns.
 /// Named access is resilient to column order changes caused by ALTER TABLE migrations.
-fn map_row_to_trade(row: &rusqlite::Row) -> rusqlite::Result<Trade> {
+pub(crate) fn map_row_to_trade(row: &rusqlite::Row) -> rusqlite::Result<Trade> {
     Ok(Trade {
         id: row.get("id")?,
         pair: row.get("pair")?,
@@ -34,6 +41,9 @@ fn map_row_to_trade(row: &rusqlite::Row) -> rusqlite::Result<Trade> {
         effective_weighted_rr: row.get("effective_weighted_rr").ok(),
         total_pnl: row.get("total_pnl").ok(),
         pnl_in_r: row.get("pnl_in_r").ok(),
+        fees: row.get("fees").ok(),
+        funding: row.get("funding").ok(),
+        execution_score: row.get("execution_score").ok(),
         notes: row.get("notes")?,
         import_fingerprint: row.get("import_fingerprint").ok(),
         import_source: row.get("import_source")?,
@@ -85,6 +95,12 @@ pub async fn get_trades(
         query.push_str(&format!(" AND {}", conditions.join(" AND ")));
     }
 
+    // Trades linked to any of the selected tags/strategies
+    let tags = TagFilter::new(filters.as_ref().and_then(|f| f.tag_ids.clone()));
+    let (tag_filter, tag_params) = tags.filter();
+    query.push_str(&format!(" {}", tag_filter));
+    params.extend(tag_params.into_iter().map(|id| Box::new(id) as Box<dyn rusqlite::ToSql>));
+
     query.push_str(" ORDER BY trade_date DESC, created_at DESC");
 
     if let Some(f) = &filters {
@@ -126,6 +142,8 @@ pub async fn create_trade(
     db: State<'_, Database>,
     trade: CreateTradeInput,
 ) -> Result<Trade, String> {
+    validate_execution_score(trade.execution_score.map(i64::from))?;
+
     let id = {
         let conn = db.conn.lock().map_err(|e| e.to_string())?;
 
@@ -139,17 +157,20 @@ pub async fn create_trade(
                 planned_tps, planned_entries, position_type, one_r, margin, position_size, quantity,
                 planned_weighted_rr, notes, execution_portfolio, execution_r_percent, execution_margin,
                 execution_position_size, execution_quantity, execution_one_r, execution_potential_profit,
-                import_source, created_at, updated_at
-            ) VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?)",
+                fees, funding, execution_score, import_source, created_at, updated_at
+            ) VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?)",
             rusqlite::params![
                 id, trade.pair, trade.exchange, trade.analysis_date, trade.trade_date, trade.status,
                 trade.portfolio_value, trade.r_percent, trade.min_rr, trade.planned_pe, trade.planned_sl, trade.leverage,
                 trade.planned_tps, trade.planned_entries, trade.position_type, trade.one_r, trade.margin, trade.position_size, trade.quantity,
                 trade.planned_weighted_rr, trade.notes, trade.execution_portfolio, trade.execution_r_percent, trade.execution_margin,
                 trade.execution_position_size, trade.execution_quantity, trade.execution_one_r, trade.execution_potential_profit,
-                "USER_CREATED", now, now
+                trade.fees, trade.funding, trade.execution_score, "USER_CREATED", now, now
             ],
         ).map_err(|e| e.to_string())?;
+        refresh_trade_days(&conn, &id, None)?;
+        record_revision(&conn, &id, "CREATE", "USER", None)?;
+        reindex_trade(&conn, &id, true)?;
 
         id
     };
@@ -163,11 +184,14 @@ pub async fn delete_trade(
     id: String,
 ) -> Result<(), String> {
     let conn = db.conn.lock().map_err(|e| e.to_string())?;
+    let before = trade_snapshot(&conn, &id)?;
     let now = Utc::now().timestamp();
     conn.execute(
         "UPDATE trades SET deleted_at = ? WHERE id = ?",
         rusqlite::params![now, &id]
     ).map_err(|e| e.to_string())?;
+    refresh_trade_days(&conn, &id, None)?;
+    record_revision(&conn, &id, "DELETE", "USER", before)?;
     Ok(())
 }
 
@@ -194,10 +218,13 @@ pub async fn restore_trade(
     id: String,
 ) -> Result<(), String> {
     let conn = db.conn.lock().map_err(|e| e.to_string())?;
+    let before = trade_snapshot(&conn, &id)?;
     conn.execute(
         "UPDATE trades SET deleted_at = NULL WHERE id = ?",
         [&id]
     ).map_err(|e| e.to_string())?;
+    refresh_trade_days(&conn, &id, None)?;
+    record_revision(&conn, &id, "RESTORE", "USER", before)?;
     Ok(())
 }
 
@@ -211,6 +238,8 @@ pub async fn update_trade(
         let conn = db.conn.lock().map_err(|e| e.to_string())?;
 
         let now = Utc::now().timestamp();
+        let previous_close_date = trade_close_date(&conn, &id)?;
+        let before = trade_snapshot(&conn, &id)?;
 
         // Build dynamic UPDATE query based on provided fields
         let mut updates = vec!["updated_at = ?"];
@@ -372,12 +401,40 @@ pub async fn update_trade(
                 values.push(Box::new(val));
             }
         }
+        if let Some(v) = trade_update.get("fees") {
+            if v.is_null() {
+                updates.push("fees = NULL");
+            } else if let Some(val) = v.as_f64() {
+                updates.push("fees = ?");
+                values.push(Box::new(val));
+            }
+        }
+        if let Some(v) = trade_update.get("funding") {
+            if v.is_null() {
+                updates.push("funding = NULL");
+            } else if let Some(val) = v.as_f64() {
+                updates.push("funding = ?");
+                values.push(Box::new(val));
+            }
+        }
+        if let Some(v) = trade_update.get("execution_score") {
+            if v.is_null() {
+                updates.push("execution_score = NULL");
+            } else if let Some(val) = v.as_i64() {
+                validate_execution_score(Some(val))?;
+                updates.push("execution_score = ?");
+                values.push(Box::new(val));
+            }
+        }
 
         let query = format!("UPDATE trades SET {} WHERE id = ?", updates.join(", "));
         values.push(Box::new(id.clone()));
 
         let params: Vec<&dyn rusqlite::ToSql> = values.iter().map(|v| v.as_ref()).collect();
         conn.execute(&query, params.as_slice()).map_err(|e| e.to_string())?;
+        refresh_trade_days(&conn, &id, previous_close_date)?;
+        record_revision(&conn, &id, "UPDATE", "USER", before)?;
+        reindex_trade(&conn, &id, false)?;
     }
 
     get_trade(db, id).await
@@ -419,6 +476,10 @@ pub async fn duplicate_trade(
                 original.planned_weighted_rr, notes, "USER_CREATED", now, now
             ],
         ).map_err(|e| e.to_string())?;
+        refresh_trade_days(&conn, &new_id, None)?;
+        copy_trade_tags(&conn, &original.id, &new_id)?;
+        record_revision(&conn, &new_id, "CREATE", "USER", None)?;
+        reindex_trade(&conn, &new_id, true)?;
 
         new_id
     };
@@ -428,10 +489,14 @@ pub async fn duplicate_trade(
 
 #[tauri::command]
 pub async fn delete_all_trades(
+    app: AppHandle,
     db: State<'_, Database>,
 ) -> Result<usize, String> {
     let conn = db.conn.lock().map_err(|e| e.to_string())?;
     let count = conn.execute("DELETE FROM trades", [])
         .map_err(|e| e.to_string())?;
+    invalidate_daily_stats(&conn)?;
+    prune_attachment_files(&conn, &attachments_dir(&app)?)?;
+    prune_search_index(&conn)?;
     Ok(count)
 }
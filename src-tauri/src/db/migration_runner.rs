@@ -132,6 +132,56 @@ impl MigrationRunner {
                 "ensure_execution_columns",
                 include_str!("migrations/009_ensure_execution_columns.sql"),
             ),
+            Migration::new(
+                10,
+                "add_timezone_setting",
+                include_str!("migrations/010_add_timezone_setting.sql"),
+            ),
+            Migration::new(
+                11,
+                "add_trade_fees",
+                include_str!("migrations/011_add_trade_fees.sql"),
+            ),
+            Migration::new(
+                12,
+                "add_capital_flows",
+                include_str!("migrations/012_add_capital_flows.sql"),
+            ),
+            Migration::new(
+                13,
+                "add_daily_stats",
+                include_str!("migrations/013_add_daily_stats.sql"),
+            ),
+            Migration::new(
+                14,
+                "add_goals",
+                include_str!("migrations/014_add_goals.sql"),
+            ),
+            Migration::new(
+                15,
+                "add_tags",
+                include_str!("migrations/015_add_tags.sql"),
+            ),
+            Migration::new(
+                16,
+                "add_attachments",
+                include_str!("migrations/016_add_attachments.sql"),
+            ),
+            Migration::new(
+                17,
+                "add_trade_review",
+                include_str!("migrations/017_add_trade_review.sql"),
+            ),
+            Migration::new(
+                18,
+                "add_trade_revisions",
+                include_str!("migrations/018_add_trade_revisions.sql"),
+            ),
+            Migration::new(
+                19,
+                "add_trade_search",
+                include_str!("migrations/019_add_trade_search.sql"),
+            ),
         ]
     }
 
@@ -193,6 +243,15 @@ impl MigrationRunner {
         Ok(applied)
     }
 
+    /// Apply every migration to a fresh database, skipping the backup (test databases are in memory)
+    #[cfg(test)]
+    pub(crate) fn apply_all(&self, conn: &Connection) -> Result<()> {
+        for migration in &self.migrations {
+            self.apply_migration(conn, migration)?;
+        }
+        Ok(())
+    }
+
     fn apply_migration(&self, conn: &Connection, migration: &Migration) -> Result<()> {
         let start = SystemTime::now();
 
@@ -0,0 +1,5 @@
+-- Migration 010: Add user timezone setting
+-- Reason: Stats and equity curve bucket trades by calendar day/week/month, which depends on the user's timezone
+-- Date: 2026-10-18
+-- Breaking: No - existing installations default to UTC, matching the previous behavior
+ALTER TABLE settings ADD COLUMN timezone TEXT NOT NULL DEFAULT 'UTC';
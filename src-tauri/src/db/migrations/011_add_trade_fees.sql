@@ -0,0 +1,9 @@
+-- Migration 011: Store trading fees and funding per trade
+-- Reason: Importers and API sync already parse fees but dropped them, so net PnL was not visible
+-- Date: 2026-10-18
+-- Breaking: No - nullable columns, existing trades report no fee data
+-- fees: total trading fees paid (positive = cost)
+-- funding: net funding payments (positive = received, negative = paid)
+-- total_pnl keeps storing the gross realized PnL, net PnL = total_pnl - fees + funding
+ALTER TABLE trades ADD COLUMN fees REAL;
+ALTER TABLE trades ADD COLUMN funding REAL;
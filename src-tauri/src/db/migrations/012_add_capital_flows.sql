@@ -0,0 +1,15 @@
+-- Migration 012: Capital ledger for deposits and withdrawals
+-- Reason: Account equity was initial_capital + PnL only, so added or withdrawn funds skewed percent returns
+-- Date: 2026-10-18
+-- Breaking: No - new table, an empty ledger keeps the previous equity behavior
+CREATE TABLE IF NOT EXISTS capital_flows (
+    id TEXT PRIMARY KEY,
+    flow_date INTEGER NOT NULL,
+    flow_type TEXT NOT NULL CHECK(flow_type IN ('DEPOSIT', 'WITHDRAWAL')),
+    amount REAL NOT NULL CHECK(amount > 0),
+    note TEXT NOT NULL DEFAULT '',
+    created_at INTEGER NOT NULL,
+    updated_at INTEGER NOT NULL
+);
+
+CREATE INDEX IF NOT EXISTS idx_capital_flows_date ON capital_flows(flow_date);
@@ -0,0 +1,37 @@
+-- Migration 013: Materialized per-day trade aggregates
+-- Reason: Dashboard stats and the equity curve rescanned every trade on each call, which lags with large API imports
+-- Date: 2026-10-18
+-- Breaking: No - derived data, rebuilt from trades on first read
+-- day is the local calendar date of close_date in daily_stats_state.timezone
+-- daily_stats_state.timezone is NULL when the aggregates are stale and must be rebuilt
+CREATE TABLE IF NOT EXISTS daily_stats (
+    day TEXT PRIMARY KEY,
+    day_start INTEGER NOT NULL,
+    day_end INTEGER NOT NULL,
+    trade_count INTEGER NOT NULL DEFAULT 0,
+    wins INTEGER NOT NULL DEFAULT 0,
+    losses INTEGER NOT NULL DEFAULT 0,
+    breakevens INTEGER NOT NULL DEFAULT 0,
+    open_trades INTEGER NOT NULL DEFAULT 0,
+    total_pnl REAL NOT NULL DEFAULT 0,
+    gross_profit REAL NOT NULL DEFAULT 0,
+    gross_loss REAL NOT NULL DEFAULT 0,
+    best_trade REAL,
+    worst_trade REAL,
+    total_fees REAL NOT NULL DEFAULT 0,
+    total_funding REAL NOT NULL DEFAULT 0,
+    rr_sum REAL NOT NULL DEFAULT 0,
+    rr_count INTEGER NOT NULL DEFAULT 0,
+    closed_count INTEGER NOT NULL DEFAULT 0,
+    closed_pnl REAL NOT NULL DEFAULT 0
+);
+
+CREATE INDEX IF NOT EXISTS idx_daily_stats_day_start ON daily_stats(day_start);
+
+CREATE TABLE IF NOT EXISTS daily_stats_state (
+    id INTEGER PRIMARY KEY CHECK(id = 1),
+    timezone TEXT,
+    rebuilt_at INTEGER
+);
+
+INSERT OR IGNORE INTO daily_stats_state (id, timezone, rebuilt_at) VALUES (1, NULL, NULL);
@@ -0,0 +1,18 @@
+-- Migration 014: Goals with targets over a week, month or custom period
+-- Reason: Settings had no way to express PnL, R, drawdown, trade count or journaling targets
+-- Date: 2026-10-18
+-- Breaking: No - new table
+CREATE TABLE IF NOT EXISTS goals (
+    id TEXT PRIMARY KEY,
+    name TEXT NOT NULL DEFAULT '',
+    metric TEXT NOT NULL CHECK(metric IN ('PNL', 'R', 'MAX_DRAWDOWN', 'MAX_TRADES', 'JOURNAL_COMPLETENESS')),
+    period TEXT NOT NULL CHECK(period IN ('WEEK', 'MONTH', 'CUSTOM')),
+    target REAL NOT NULL,
+    start_date INTEGER,
+    end_date INTEGER,
+    active INTEGER NOT NULL DEFAULT 1,
+    created_at INTEGER NOT NULL,
+    updated_at INTEGER NOT NULL
+);
+
+CREATE INDEX IF NOT EXISTS idx_goals_active ON goals(active);
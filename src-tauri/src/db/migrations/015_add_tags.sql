@@ -0,0 +1,25 @@
+-- Migration 015: Tags and strategies linked to trades
+-- Reason: Setups were tracked as hashtags in notes, which cannot be queried or filtered
+-- Date: 2026-10-18
+-- Breaking: No - new tables
+CREATE TABLE IF NOT EXISTS tags (
+    id TEXT PRIMARY KEY,
+    name TEXT NOT NULL COLLATE NOCASE,
+    kind TEXT NOT NULL DEFAULT 'TAG' CHECK(kind IN ('TAG', 'STRATEGY')),
+    color TEXT,
+    description TEXT NOT NULL DEFAULT '',
+    created_at INTEGER NOT NULL,
+    updated_at INTEGER NOT NULL,
+    UNIQUE(kind, name)
+);
+
+CREATE TABLE IF NOT EXISTS trade_tags (
+    trade_id TEXT NOT NULL,
+    tag_id TEXT NOT NULL,
+    created_at INTEGER NOT NULL,
+    PRIMARY KEY (trade_id, tag_id),
+    FOREIGN KEY (trade_id) REFERENCES trades(id) ON DELETE CASCADE,
+    FOREIGN KEY (tag_id) REFERENCES tags(id) ON DELETE CASCADE
+);
+
+CREATE INDEX IF NOT EXISTS idx_trade_tags_tag ON trade_tags(tag_id);
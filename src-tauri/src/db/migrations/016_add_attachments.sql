@@ -0,0 +1,22 @@
+-- Migration 016: Chart screenshots and files attached to trades
+-- Reason: Pre and post trade charts lived outside the journal and were lost with the trade
+-- Date: 2026-10-18
+-- Breaking: No - new table, files are stored in the attachments folder next to the database
+CREATE TABLE IF NOT EXISTS attachments (
+    id TEXT PRIMARY KEY,
+    trade_id TEXT NOT NULL,
+    kind TEXT NOT NULL DEFAULT 'OTHER' CHECK(kind IN ('PRE_TRADE', 'POST_TRADE', 'OTHER')),
+    file_name TEXT NOT NULL,
+    stored_name TEXT NOT NULL,
+    content_hash TEXT NOT NULL,
+    mime_type TEXT NOT NULL,
+    size_bytes INTEGER NOT NULL,
+    caption TEXT NOT NULL DEFAULT '',
+    sort_order INTEGER NOT NULL DEFAULT 0,
+    created_at INTEGER NOT NULL,
+    UNIQUE(trade_id, content_hash),
+    FOREIGN KEY (trade_id) REFERENCES trades(id) ON DELETE CASCADE
+);
+
+CREATE INDEX IF NOT EXISTS idx_attachments_trade ON attachments(trade_id, sort_order);
+CREATE INDEX IF NOT EXISTS idx_attachments_hash ON attachments(content_hash);
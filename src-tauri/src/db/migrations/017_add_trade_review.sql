@@ -0,0 +1,41 @@
+-- Migration 017: Mistakes, emotions and execution score per trade
+-- Reason: Mistakes were only written in notes, so their cost could not be measured
+-- Date: 2026-10-18
+-- Breaking: No - nullable column and new tables, the catalogue starts with common entries
+ALTER TABLE trades ADD COLUMN execution_score INTEGER CHECK(execution_score IS NULL OR execution_score BETWEEN 1 AND 5);
+
+CREATE TABLE IF NOT EXISTS review_labels (
+    id TEXT PRIMARY KEY,
+    name TEXT NOT NULL COLLATE NOCASE,
+    category TEXT NOT NULL CHECK(category IN ('MISTAKE', 'EMOTION')),
+    description TEXT NOT NULL DEFAULT '',
+    active INTEGER NOT NULL DEFAULT 1,
+    created_at INTEGER NOT NULL,
+    updated_at INTEGER NOT NULL,
+    UNIQUE(category, name)
+);
+
+CREATE TABLE IF NOT EXISTS trade_review_labels (
+    trade_id TEXT NOT NULL,
+    label_id TEXT NOT NULL,
+    created_at INTEGER NOT NULL,
+    PRIMARY KEY (trade_id, label_id),
+    FOREIGN KEY (trade_id) REFERENCES trades(id) ON DELETE CASCADE,
+    FOREIGN KEY (label_id) REFERENCES review_labels(id) ON DELETE CASCADE
+);
+
+CREATE INDEX IF NOT EXISTS idx_trade_review_labels_label ON trade_review_labels(label_id);
+
+INSERT OR IGNORE INTO review_labels (id, name, category, description, created_at, updated_at) VALUES
+    ('MISTAKE-moved-stop', 'Moved stop', 'MISTAKE', 'Stop loss moved away from the plan', strftime('%s', 'now'), strftime('%s', 'now')),
+    ('MISTAKE-fomo-entry', 'FOMO entry', 'MISTAKE', 'Entered late or without a setup', strftime('%s', 'now'), strftime('%s', 'now')),
+    ('MISTAKE-early-exit', 'Early exit', 'MISTAKE', 'Closed before the target or the stop', strftime('%s', 'now'), strftime('%s', 'now')),
+    ('MISTAKE-oversized', 'Oversized', 'MISTAKE', 'Risked more than the planned R', strftime('%s', 'now'), strftime('%s', 'now')),
+    ('MISTAKE-revenge-trade', 'Revenge trade', 'MISTAKE', 'Taken to win back a previous loss', strftime('%s', 'now'), strftime('%s', 'now')),
+    ('MISTAKE-no-stop', 'No stop', 'MISTAKE', 'Position opened without a stop loss', strftime('%s', 'now'), strftime('%s', 'now')),
+    ('EMOTION-calm', 'Calm', 'EMOTION', '', strftime('%s', 'now'), strftime('%s', 'now')),
+    ('EMOTION-confident', 'Confident', 'EMOTION', '', strftime('%s', 'now'), strftime('%s', 'now')),
+    ('EMOTION-anxious', 'Anxious', 'EMOTION', '', strftime('%s', 'now'), strftime('%s', 'now')),
+    ('EMOTION-greedy', 'Greedy', 'EMOTION', '', strftime('%s', 'now'), strftime('%s', 'now')),
+    ('EMOTION-frustrated', 'Frustrated', 'EMOTION', '', strftime('%s', 'now'), strftime('%s', 'now')),
+    ('EMOTION-bored', 'Bored', 'EMOTION', '', strftime('%s', 'now'), strftime('%s', 'now'));
@@ -0,0 +1,17 @@
+-- Migration 018: Change history of every trade
+-- Reason: Edits, imports and live mirror updates overwrote trades in place, lost notes could not be recovered
+-- Date: 2026-10-18
+-- Breaking: No - new table, history starts with the next change of each trade
+-- changes: JSON array of {field, old_value, new_value}, tag_ids and review_label_ids hold the linked ids
+-- History is removed together with the trade when it is permanently deleted
+CREATE TABLE IF NOT EXISTS trade_revisions (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    trade_id TEXT NOT NULL,
+    action TEXT NOT NULL CHECK(action IN ('CREATE', 'UPDATE', 'DELETE', 'RESTORE', 'REVERT')),
+    source TEXT NOT NULL,
+    changes TEXT NOT NULL,
+    created_at INTEGER NOT NULL,
+    FOREIGN KEY (trade_id) REFERENCES trades(id) ON DELETE CASCADE
+);
+
+CREATE INDEX IF NOT EXISTS idx_trade_revisions_trade ON trade_revisions(trade_id, id);
@@ -0,0 +1,38 @@
+-- Migration 019: Full-text search over trade notes, pair, exchange and tags
+-- Reason: Notes could not be searched and pair filtering was a plain LIKE match
+-- Date: 2026-10-18
+-- Breaking: No - new FTS5 table filled from existing trades
+-- Kept in sync from the Rust write paths (see commands/search.rs), not with triggers
+-- tags holds the names of the linked tags and strategies separated by spaces
+-- Integer key of each trade, used as the FTS5 rowid so an entry is replaced by rowid
+-- instead of scanning the unindexed trade_id column
+CREATE TABLE IF NOT EXISTS trade_search_keys (
+    id INTEGER PRIMARY KEY,
+    trade_id TEXT NOT NULL UNIQUE
+);
+
+CREATE VIRTUAL TABLE IF NOT EXISTS trade_search USING fts5(
+    trade_id UNINDEXED,
+    pair,
+    exchange,
+    tags,
+    notes,
+    tokenize = 'unicode61 remove_diacritics 2'
+);
+
+INSERT OR IGNORE INTO trade_search_keys (trade_id) SELECT id FROM trades;
+
+INSERT INTO trade_search (rowid, trade_id, pair, exchange, tags, notes)
+SELECT
+    trade_search_keys.id,
+    trades.id,
+    trades.pair,
+    trades.exchange,
+    COALESCE((
+        SELECT group_concat(tags.name, ' ')
+        FROM trade_tags JOIN tags ON tags.id = trade_tags.tag_id
+        WHERE trade_tags.trade_id = trades.id
+    ), ''),
+    trades.notes
+FROM trades
+JOIN trade_search_keys ON trade_search_keys.trade_id = trades.id;
@@ -3,3 +3,55 @@ pub mod migration_runner;
 pub mod migrations;
 
 pub use connection::Database;
+
+/// In-memory database with the full migrated schema, for module tests
+#[cfg(test)]
+pub(crate) fn test_connection() -> rusqlite::Connection {
+    let conn = rusqlite::Connection::open_in_memory().unwrap();
+    conn.execute_batch("PRAGMA foreign_keys = ON").unwrap();
+    migration_runner::MigrationRunner::new().apply_all(&conn).unwrap();
+    conn
+}
+
+/// Insert a trade with placeholder values for every required column, overridden by `columns`
+#[cfg(test)]
+pub(crate) fn insert_test_trade(conn: &rusqlite::Connection, id: &str, columns: &[(&str, &dyn rusqlite::ToSql)]) {
+    let mut values: Vec<(&str, &dyn rusqlite::ToSql)> = vec![
+        ("id", &id),
+        ("pair", &"BTCUSDT"),
+        ("exchange", &"BitGet"),
+        ("analysis_date", &0),
+        ("trade_date", &0),
+        ("status", &"OPEN"),
+        ("portfolio_value", &10000.0),
+        ("r_percent", &0.02),
+        ("min_rr", &2.0),
+        ("planned_pe", &100.0),
+        ("planned_sl", &95.0),
+        ("leverage", &10),
+        ("planned_tps", &"[]"),
+        ("position_type", &"LONG"),
+        ("one_r", &200.0),
+        ("margin", &400.0),
+        ("position_size", &4000.0),
+        ("quantity", &40.0),
+        ("planned_weighted_rr", &2.0),
+        ("created_at", &0),
+        ("updated_at", &0),
+    ];
+    for &(column, value) in columns {
+        match values.iter_mut().find(|(name, _)| *name == column) {
+            Some(existing) => existing.1 = value,
+            None => values.push((column, value)),
+        }
+    }
+
+    let names: Vec<&str> = values.iter().map(|(name, _)| *name).collect();
+    let sql = format!(
+        "INSERT INTO trades ({}) VALUES ({})",
+        names.join(", "),
+        vec!["?"; names.len()].join(", ")
+    );
+    conn.execute(&sql, rusqlite::params_from_iter(values.iter().map(|(_, value)| *value)))
+        .unwrap();
+}
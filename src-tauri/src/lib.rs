@@ -135,6 +135,56 @@ pub fn run() {
             commands::delete_all_trades,
             commands::get_dashboard_stats,
             commands::get_equity_curve,
+            commands::get_r_multiple_stats,
+            commands::get_drawdown_analysis,
+            commands::get_performance_breakdown,
+            commands::get_streak_analysis,
+            commands::get_bootstrap_stats,
+            commands::get_adherence_report,
+            commands::run_monte_carlo_simulation,
+            commands::get_calendar_data,
+            commands::get_kelly_analysis,
+            commands::get_holding_time_analysis,
+            commands::compare_periods,
+            commands::get_rolling_metrics,
+            commands::get_risk_analysis,
+            commands::get_risk_overshoot,
+            commands::get_exposure_analysis,
+            commands::get_goals,
+            commands::create_goal,
+            commands::update_goal,
+            commands::delete_goal,
+            commands::get_goal_progress,
+            commands::get_tags,
+            commands::create_tag,
+            commands::update_tag,
+            commands::delete_tag,
+            commands::get_trade_tags,
+            commands::set_trade_tags,
+            commands::get_attachments,
+            commands::add_attachment,
+            commands::update_attachment,
+            commands::delete_attachment,
+            commands::reorder_attachments,
+            commands::get_attachment_data,
+            commands::get_review_labels,
+            commands::create_review_label,
+            commands::update_review_label,
+            commands::delete_review_label,
+            commands::get_trade_review,
+            commands::set_trade_review,
+            commands::get_review_costs,
+            commands::get_trade_history,
+            commands::revert_trade_to_revision,
+            commands::search_trades,
+            commands::rebuild_search_index,
+            commands::get_capital_flows,
+            commands::create_capital_flow,
+            commands::update_capital_flow,
+            commands::delete_capital_flow,
+            commands::get_account_balance,
+            commands::get_capital_performance,
+            commands::rebuild_daily_stats,
             commands::preview_bitget_import,
             commands::import_bitget_csv,
             commands::delete_bitget_trades,
@@ -0,0 +1,47 @@
+use serde::{Deserialize, Serialize};
+
+/// File attached to a trade, stored once per content hash in the attachments folder
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Attachment {
+    pub id: String,
+    pub trade_id: String,
+    pub kind: String,        // PRE_TRADE | POST_TRADE | OTHER
+    pub file_name: String,   // original name, for display
+    pub stored_name: String, // <sha256>.<ext> inside the attachments folder
+    pub content_hash: String,
+    pub mime_type: String,
+    pub size_bytes: i64,
+    pub caption: String,
+    pub sort_order: i64,
+    pub created_at: i64,
+}
+
+/// Either a path picked in a file dialog or base64 data (pasted screenshots)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AddAttachmentInput {
+    pub trade_id: String,
+    pub source_path: Option<String>,
+    pub data_base64: Option<String>,
+    pub file_name: Option<String>,
+    #[serde(default = "default_attachment_kind")]
+    pub kind: String,
+    #[serde(default)]
+    pub caption: String,
+}
+
+fn default_attachment_kind() -> String {
+    "OTHER".to_string()
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct UpdateAttachmentInput {
+    pub kind: Option<String>,
+    pub caption: Option<String>,
+}
+
+/// File contents embedded in a JSON backup, one entry per stored file
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AttachmentFile {
+    pub stored_name: String,
+    pub data_base64: String,
+}
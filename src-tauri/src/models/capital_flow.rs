@@ -0,0 +1,41 @@
+use serde::{Deserialize, Serialize};
+
+/// Deposit into or withdrawal from the trading account
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CapitalFlow {
+    pub id: String,
+    pub flow_date: i64,
+    pub flow_type: String, // DEPOSIT | WITHDRAWAL
+    pub amount: f64,       // always positive, direction comes from flow_type
+    pub note: String,
+    pub created_at: i64,
+    pub updated_at: i64,
+}
+
+impl CapitalFlow {
+    /// Cash flow into the account: deposits positive, withdrawals negative
+    pub fn signed_amount(&self) -> f64 {
+        if self.flow_type == "WITHDRAWAL" {
+            -self.amount
+        } else {
+            self.amount
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CreateCapitalFlowInput {
+    pub flow_date: i64,
+    pub flow_type: String,
+    pub amount: f64,
+    #[serde(default)]
+    pub note: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct UpdateCapitalFlowInput {
+    pub flow_date: Option<i64>,
+    pub flow_type: Option<String>,
+    pub amount: Option<f64>,
+    pub note: Option<String>,
+}
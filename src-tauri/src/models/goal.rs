@@ -0,0 +1,50 @@
+use serde::{Deserialize, Serialize};
+
+/// Target for a metric over a week, month or custom period
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Goal {
+    pub id: String,
+    pub name: String,
+    pub metric: String, // PNL | R | MAX_DRAWDOWN | MAX_TRADES | JOURNAL_COMPLETENESS
+    pub period: String, // WEEK | MONTH | CUSTOM
+    pub target: f64,
+    pub start_date: Option<i64>, // CUSTOM only
+    pub end_date: Option<i64>,   // CUSTOM only
+    pub active: bool,
+    pub created_at: i64,
+    pub updated_at: i64,
+}
+
+impl Goal {
+    /// Limits (drawdown, trade count) are met by staying at or below the target
+    pub fn is_limit(&self) -> bool {
+        self.metric == "MAX_DRAWDOWN" || self.metric == "MAX_TRADES"
+    }
+
+    /// Metrics that accumulate over the period and can be extrapolated to its end
+    pub fn is_cumulative(&self) -> bool {
+        matches!(self.metric.as_str(), "PNL" | "R" | "MAX_TRADES")
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CreateGoalInput {
+    #[serde(default)]
+    pub name: String,
+    pub metric: String,
+    pub period: String,
+    pub target: f64,
+    pub start_date: Option<i64>,
+    pub end_date: Option<i64>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct UpdateGoalInput {
+    pub name: Option<String>,
+    pub metric: Option<String>,
+    pub period: Option<String>,
+    pub target: Option<f64>,
+    pub start_date: Option<i64>,
+    pub end_date: Option<i64>,
+    pub active: Option<bool>,
+}
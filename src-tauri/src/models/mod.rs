@@ -1,7 +1,19 @@
 pub mod api_credential;
+pub mod attachment;
+pub mod capital_flow;
+pub mod goal;
+pub mod review;
+pub mod revision;
 pub mod settings;
+pub mod tag;
 pub mod trade;
 
 pub use api_credential::*;
+pub use attachment::*;
+pub use capital_flow::*;
+pub use goal::*;
+pub use review::*;
+pub use revision::*;
 pub use settings::*;
+pub use tag::*;
 pub use trade::*;
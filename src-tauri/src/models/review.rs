@@ -0,0 +1,45 @@
+use serde::{Deserialize, Serialize};
+
+/// Catalogue entry used to review trades: a mistake (moved stop, FOMO entry, ...) or an emotional state
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ReviewLabel {
+    pub id: String,
+    pub name: String,     // unique per category, case-insensitive
+    pub category: String, // MISTAKE | EMOTION
+    pub description: String,
+    pub active: bool, // inactive labels stay on past trades but are no longer offered
+    pub created_at: i64,
+    pub updated_at: i64,
+}
+
+/// Link between a trade and a review label
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TradeReviewLabel {
+    pub trade_id: String,
+    pub label_id: String,
+    pub created_at: i64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CreateReviewLabelInput {
+    pub name: String,
+    pub category: String,
+    #[serde(default)]
+    pub description: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct UpdateReviewLabelInput {
+    pub name: Option<String>,
+    pub description: Option<String>,
+    pub active: Option<bool>,
+}
+
+/// Review of one trade: execution score plus its mistakes and emotions
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TradeReview {
+    pub trade_id: String,
+    pub execution_score: Option<i32>,
+    pub mistakes: Vec<ReviewLabel>,
+    pub emotions: Vec<ReviewLabel>,
+}
@@ -0,0 +1,20 @@
+use serde::{Deserialize, Serialize};
+
+/// One field of a trade before and after a change, values as stored in the database
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct FieldChange {
+    pub field: String,
+    pub old_value: serde_json::Value,
+    pub new_value: serde_json::Value,
+}
+
+/// Entry in a trade's change history
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TradeRevision {
+    pub id: i64,
+    pub trade_id: String,
+    pub action: String, // CREATE | UPDATE | DELETE | RESTORE | REVERT
+    pub source: String, // USER | CSV_IMPORT | API_IMPORT | LIVE_MIRROR | BACKUP_RESTORE
+    pub changes: Vec<FieldChange>,
+    pub created_at: i64,
+}
@@ -12,6 +12,9 @@ pub struct Settings {
     pub enable_position_monitor: bool,
     #[serde(default)]
     pub enable_api_connections: bool,
+    /// IANA timezone name used to bucket stats by calendar day (e.g. "Europe/Paris")
+    #[serde(default = "default_timezone")]
+    pub timezone: String,
     pub created_at: i64,
     pub updated_at: i64,
 }
@@ -25,4 +28,9 @@ pub struct UpdateSettingsInput {
     pub currency: Option<String>,
     pub enable_position_monitor: Option<bool>,
     pub enable_api_connections: Option<bool>,
+    pub timezone: Option<String>,
+}
+
+fn default_timezone() -> String {
+    "UTC".to_string()
 }
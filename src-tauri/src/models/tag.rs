@@ -0,0 +1,43 @@
+use serde::{Deserialize, Serialize};
+
+/// Label for trades: a free-form tag (breakout, news, ...) or a named strategy
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Tag {
+    pub id: String,
+    pub name: String, // unique per kind, case-insensitive
+    pub kind: String, // TAG | STRATEGY
+    pub color: Option<String>,
+    pub description: String,
+    pub created_at: i64,
+    pub updated_at: i64,
+}
+
+/// Link between a trade and a tag
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TradeTag {
+    pub trade_id: String,
+    pub tag_id: String,
+    pub created_at: i64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CreateTagInput {
+    pub name: String,
+    #[serde(default = "default_tag_kind")]
+    pub kind: String,
+    pub color: Option<String>,
+    #[serde(default)]
+    pub description: String,
+}
+
+fn default_tag_kind() -> String {
+    "TAG".to_string()
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct UpdateTagInput {
+    pub name: Option<String>,
+    pub kind: Option<String>,
+    pub color: Option<String>,
+    pub description: Option<String>,
+}
@@ -39,6 +39,9 @@ pub struct Trade {
     pub effective_weighted_rr: Option<f64>,
     pub total_pnl: Option<f64>,
     pub pnl_in_r: Option<f64>,
+    pub fees: Option<f64>,    // Trading fees paid (positive = cost), total_pnl is gross of fees
+    pub funding: Option<f64>, // Net funding payments (positive = received, negative = paid)
+    pub execution_score: Option<i32>, // 1-5 execution quality, set when the trade is reviewed
 
     pub notes: String,
 
@@ -92,6 +95,10 @@ pub struct CreateTradeInput {
     pub execution_quantity: Option<f64>,
     pub execution_one_r: Option<f64>,
     pub execution_potential_profit: Option<f64>,
+
+    pub fees: Option<f64>,
+    pub funding: Option<f64>,
+    pub execution_score: Option<i32>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -100,6 +107,7 @@ pub struct TradeFilters {
     pub pair: Option<String>,
     pub start_date: Option<i64>,
     pub end_date: Option<i64>,
+    pub tag_ids: Option<Vec<String>>, // trades linked to any of these tags/strategies
     pub page: Option<i32>,
     pub limit: Option<i32>,
 }
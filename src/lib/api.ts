@@ -9,6 +9,7 @@ export interface Settings {
   currency: string;
   enable_position_monitor: boolean;
   enable_api_connections: boolean;
+  timezone: string;
   created_at: number;
   updated_at: number;
 }
@@ -41,6 +42,9 @@ export interface Trade {
   effective_weighted_rr?: number;
   total_pnl?: number;
   pnl_in_r?: number;
+  fees?: number;     // trading fees paid (positive = cost), total_pnl is gross of fees
+  funding?: number;  // net funding (positive = received, negative = paid)
+  execution_score?: number;  // 1-5, set when the trade is reviewed
   notes: string;
   execution_portfolio?: number;
   execution_r_percent?: number;
@@ -62,6 +66,7 @@ export interface TradeFilters {
   end_date?: number;
   page?: number;
   limit?: number;
+  tag_ids?: string[];  // matches trades with any of these tags or strategies
 }
 
 export interface CreateTradeInput {
@@ -92,6 +97,17 @@ export interface CreateTradeInput {
   execution_quantity?: number;
   execution_one_r?: number;
   execution_potential_profit?: number;
+  fees?: number;
+  funding?: number;
+  execution_score?: number;
+}
+
+// Trade selection shared by the stats commands. Explicit start/end timestamps win over dateRange.
+export interface StatsFilter {
+  dateRange?: string;
+  startDate?: number;
+  endDate?: number;
+  tagIds?: string[];  // trades linked to any of these tags or strategies
 }
 
 export interface DashboardStats {
@@ -101,7 +117,10 @@ export interface DashboardStats {
   breakevens: number;
   open_trades: number;
   win_rate: number;
-  total_pnl: number;
+  total_pnl: number;      // gross, before fees and funding
+  total_fees: number;
+  total_funding: number;  // positive = received
+  net_pnl: number;        // total_pnl - total_fees + total_funding
   gross_profit: number;
   gross_loss: number;
   profit_factor: number;
@@ -117,6 +136,711 @@ export interface EquityCurvePoint {
   trade_count: number;
 }
 
+export type EquityInterval = 'day' | 'week' | 'month';
+
+export interface EquityCurveOptions extends StatsFilter {
+  interval?: EquityInterval;
+}
+
+export interface RMultipleBucket {
+  lower: number;
+  upper: number;
+  count: number;
+}
+
+export interface RMultipleOptions extends StatsFilter {
+  bucketWidth?: number;  // in R, default 0.5
+}
+
+export interface RMultipleStats {
+  sample_size: number;
+  bucket_width: number;
+  buckets: RMultipleBucket[];
+  expectancy_r: number;
+  expectancy_currency: number;
+  std_dev_r: number;
+  sqn: number;
+  avg_win_r: number;
+  avg_loss_r: number;
+}
+
+export interface UnderwaterPoint {
+  date: string;
+  cumulative_pnl: number;
+  drawdown: number;
+  drawdown_percent: number;
+}
+
+export interface DrawdownAnalysis {
+  max_drawdown: number;
+  max_drawdown_percent: number;
+  max_drawdown_r: number;
+  max_drawdown_peak_date?: string;
+  max_drawdown_trough_date?: string;
+  current_drawdown: number;
+  current_drawdown_percent: number;
+  current_drawdown_r: number;
+  longest_drawdown_days: number;
+  current_drawdown_days: number;
+  net_profit: number;
+  recovery_factor: number;
+  underwater: UnderwaterPoint[];
+}
+
+export type BreakdownDimension =
+  | 'pair'
+  | 'exchange'
+  | 'position_type'
+  | 'import_source'
+  | 'weekday'
+  | 'hour'
+  | 'leverage';
+
+export type BreakdownSortField =
+  | 'key'
+  | 'total_trades'
+  | 'win_rate'
+  | 'total_pnl'
+  | 'net_pnl'
+  | 'total_fees'
+  | 'profit_factor'
+  | 'avg_effective_rr';
+
+export interface BreakdownOptions extends StatsFilter {
+  dimension: BreakdownDimension;
+  sortBy?: BreakdownSortField;
+  descending?: boolean;
+}
+
+export interface BreakdownGroup {
+  key: string;
+  sort_order: number;
+  stats: DashboardStats;
+}
+
+export interface StreakLengthCount {
+  length: number;
+  count: number;
+}
+
+export interface ConditionalStreakStat {
+  after_status: string; // WIN | LOSS
+  streak_length: number;
+  sample_size: number;
+  wins: number;
+  losses: number;
+  breakevens: number;
+  win_rate: number;
+  avg_pnl: number;
+}
+
+export interface StreakOptions extends StatsFilter {
+  maxConditionalStreak?: number;
+}
+
+export interface StreakAnalysis {
+  longest_win_streak: number;
+  longest_loss_streak: number;
+  current_streak_status?: string;
+  current_streak_length: number;
+  win_streak_distribution: StreakLengthCount[];
+  loss_streak_distribution: StreakLengthCount[];
+  after_losses: ConditionalStreakStat[];
+  after_wins: ConditionalStreakStat[];
+}
+
+export interface ConfidenceInterval {
+  level: number;  // e.g. 95
+  lower: number | null;  // null when unbounded or undefined
+  upper: number | null;
+}
+
+export interface MetricConfidence {
+  metric: 'win_rate' | 'expectancy_r' | 'profit_factor';
+  sample_size: number;
+  estimate: number | null;
+  intervals: ConfidenceInterval[];
+  insufficient_sample: boolean;
+}
+
+export interface BootstrapOptions extends StatsFilter {
+  dimension?: BreakdownDimension;  // with key, restricts to one breakdown group
+  key?: string;
+  confidenceLevels?: number[];  // percentages, default [90, 95]
+  iterations?: number;
+  seed?: number;
+}
+
+export interface BootstrapStats {
+  seed: number;
+  iterations: number;
+  dimension: BreakdownDimension | null;
+  key: string | null;
+  sample_size: number;
+  min_sample_size: number;
+  insufficient_sample: boolean;
+  metrics: MetricConfidence[];
+}
+
+export interface TradeAdherence {
+  trade_id: string;
+  pair: string;
+  position_type: string;
+  status: string;
+  trade_date: number;
+  close_date?: number;
+  planned_pe: number;
+  effective_pe?: number;
+  entry_slippage_percent?: number;  // > 0 = filled worse than planned
+  entry_slippage_r?: number;
+  planned_exit_price?: number;
+  actual_exit_price?: number;
+  exit_deviation_percent?: number;
+  exits_matched_plan?: boolean;
+  planned_rr: number;
+  realized_rr?: number;
+  rr_ratio?: number;
+  r_multiple?: number;
+  stop_violated: boolean;
+}
+
+export interface AdherenceOptions extends StatsFilter {
+  includeImported?: boolean;
+  exitTolerancePercent?: number;
+}
+
+export interface AdherenceReport {
+  trade_count: number;
+  avg_entry_slippage_percent: number;
+  avg_entry_slippage_r: number;
+  adverse_entry_count: number;
+  exit_deviation_count: number;
+  exit_deviation_rate: number;
+  avg_exit_deviation_percent: number;
+  avg_rr_ratio: number;
+  stop_violation_count: number;
+  stop_violation_rate: number;
+  stop_violation_excess_r: number;
+  trades: TradeAdherence[];
+}
+
+export interface MonteCarloBand {
+  trade_index: number;  // 0 = starting equity
+  p5: number;
+  p25: number;
+  p50: number;
+  p75: number;
+  p95: number;
+}
+
+export interface MonteCarloResult {
+  seed: number;  // pass back in to reproduce the same paths
+  sample_size: number;
+  num_trades: number;
+  num_paths: number;
+  r_percent: number;
+  starting_equity: number;
+  bands: MonteCarloBand[];
+  drawdown_threshold_percent: number;
+  drawdown_probability: number;
+  ruin_threshold_percent: number;
+  risk_of_ruin: number;
+  median_max_drawdown_percent: number;
+  median_final_return_percent: number;
+}
+
+export interface MonteCarloOptions extends StatsFilter {
+  numTrades?: number;
+  numPaths?: number;
+  rPercent?: number;  // fraction, defaults to settings.current_r_percent
+  drawdownThresholdPercent?: number;
+  ruinThresholdPercent?: number;
+  seed?: number;
+}
+
+export interface CalendarBucket {
+  period: string;  // first day of the day/week/month (YYYY-MM-DD, user's timezone)
+  trades_opened: number;
+  trade_count: number;  // trades closed in the period
+  wins: number;
+  losses: number;
+  breakevens: number;
+  win_rate: number;
+  total_pnl: number;
+  net_pnl: number;
+  total_r: number;
+}
+
+export interface CalendarOptions {
+  year?: number;  // defaults to the current year
+  tagIds?: string[];
+}
+
+export interface CalendarData {
+  year: number;
+  timezone: string;
+  days: CalendarBucket[];
+  weeks: CalendarBucket[];
+  months: CalendarBucket[];
+  available_years: number[];
+}
+
+export interface RiskBucket {
+  label: string;
+  sort_order: number;
+  trade_count: number;
+  wins: number;
+  losses: number;
+  breakevens: number;
+  win_rate: number;
+  expectancy_r: number;
+  total_pnl: number;
+  avg_pnl: number;
+}
+
+export interface RiskCreep {
+  field: 'r_percent' | 'execution_r_percent';
+  sample_size: number;
+  correlation: number;
+  slope_per_trade: number;
+  first_half_avg: number;
+  second_half_avg: number;
+  change_percent: number;
+  avg_after_win_streak: number | null;
+  avg_otherwise: number | null;
+  detected: boolean;
+}
+
+export interface RiskAnalysis {
+  by_r_percent: RiskBucket[];
+  by_leverage: RiskBucket[];
+  by_loss_size: RiskBucket[];  // losing trades only, loss relative to one_r
+  r_percent_creep: RiskCreep;
+  execution_r_percent_creep: RiskCreep;
+}
+
+export interface RiskOvershootTrade {
+  trade_id: string;
+  pair: string;
+  exchange: string;
+  import_source: string;
+  close_date: number;
+  total_pnl: number;
+  risk_amount: number;  // execution_one_r when set, otherwise one_r
+  uses_execution_one_r: boolean;
+  loss_r: number;
+  overshoot_r: number;
+  overshoot_amount: number;
+}
+
+export interface RiskOvershootGroup {
+  key: string;  // pair or YYYY-MM
+  losing_trades: number;
+  overshoot_count: number;
+  overshoot_rate: number;
+  total_overshoot_r: number;
+  total_overshoot_amount: number;
+  max_overshoot_r: number;
+}
+
+export interface RiskOvershootOptions extends StatsFilter {
+  toleranceR?: number;
+}
+
+export interface RiskOvershootReport {
+  tolerance_r: number;
+  overall: RiskOvershootGroup;
+  by_pair: RiskOvershootGroup[];
+  by_month: RiskOvershootGroup[];
+  trades: RiskOvershootTrade[];
+}
+
+export interface ExposurePoint {
+  timestamp: number;
+  open_positions: number;
+  open_risk_amount: number;
+  open_risk_percent: number;
+  open_risk_r: number;  // in units of settings.current_r_percent
+}
+
+export interface ConcurrencyTime {
+  open_positions: number;
+  seconds: number;
+  percent: number;
+}
+
+export interface ConcurrencyGroup {
+  label: string;
+  sort_order: number;  // positions already open at entry
+  stats: DashboardStats;
+  avg_r: number | null;
+}
+
+export interface ExposureAnalysis {
+  current_r_percent: number;
+  max_concurrent_positions: number;
+  max_concurrent_at: number | null;
+  peak_open_risk_r: number;
+  peak_open_risk_percent: number;
+  peak_open_risk_at: number | null;
+  avg_concurrent_positions: number;
+  overlap_time_percent: number;
+  time_at_concurrency: ConcurrencyTime[];
+  opened_alone: ConcurrencyGroup;
+  opened_while_open: ConcurrencyGroup;
+  by_concurrency_at_entry: ConcurrencyGroup[];
+  series: ExposurePoint[];
+}
+
+export type RollingMode = 'trades' | 'days';
+
+export interface RollingOptions extends StatsFilter {
+  mode?: RollingMode;
+  window?: number;  // trades or days depending on mode
+}
+
+export interface RollingMetricPoint {
+  date: string;  // local close date (YYYY-MM-DD)
+  trade_id: string | null;  // 'trades' mode only
+  close_date: number | null;  // 'trades' mode only
+  sample_size: number;
+  window_full: boolean;
+  win_rate: number | null;
+  expectancy_r: number | null;
+  profit_factor: number | null;
+  avg_rr: number | null;
+}
+
+export interface MetricDelta {
+  metric: string;
+  current: number;
+  baseline: number;  // per-period average for counts and sums when baseline_periods > 1
+  absolute_delta: number | null;
+  percent_delta: number | null;
+}
+
+export interface PeriodComparisonOptions {
+  currentStart: number;
+  currentEnd: number;
+  baselineStart: number;
+  baselineEnd: number;
+  baselinePeriods?: number;
+  tagIds?: string[];
+}
+
+export interface PeriodComparison {
+  current_start: number;
+  current_end: number;
+  baseline_start: number;
+  baseline_end: number;
+  baseline_periods: number;
+  current: DashboardStats;
+  baseline: DashboardStats;
+  deltas: MetricDelta[];
+}
+
+export interface HoldingTimeBucket {
+  label: string;  // '<15m', '15m-1h', ... '30d+'
+  sort_order: number;  // lower bound in seconds
+  trade_count: number;
+  wins: number;
+  losses: number;
+  breakevens: number;
+  win_rate: number;
+  total_pnl: number;
+  avg_pnl: number;
+  total_r: number;
+  avg_r: number;
+}
+
+export interface HoldingTimeOutlier {
+  trade_id: string;
+  pair: string;
+  status: string;
+  trade_date: number;
+  close_date: number;
+  holding_seconds: number;
+  total_pnl: number | null;
+  pnl_in_r: number | null;
+}
+
+export interface HoldingTimeOptions extends StatsFilter {
+  outlierIqrMultiplier?: number;
+}
+
+export interface HoldingTimeAnalysis {
+  trade_count: number;
+  avg_holding_seconds: number;
+  median_holding_seconds: number;
+  avg_winner_holding_seconds: number;
+  avg_loser_holding_seconds: number;
+  avg_breakeven_holding_seconds: number;
+  buckets: HoldingTimeBucket[];
+  outlier_threshold_seconds: number;  // Q3 + multiplier * IQR
+  outliers: HoldingTimeOutlier[];
+}
+
+export interface KellyLevel {
+  label: 'full' | 'half' | 'quarter' | 'current';
+  r_percent: number;  // fraction of equity risked per 1R (0.02 = 2%)
+  growth_rate: number | null;  // expected log growth per trade
+  growth_percent_per_trade: number | null;
+}
+
+export interface KellyStats {
+  key: string;  // 'ALL' or the pair
+  sample_size: number;
+  wins: number;
+  losses: number;
+  breakevens: number;
+  win_rate: number;
+  avg_win_r: number;
+  avg_loss_r: number;
+  payoff_ratio: number | null;
+  full_kelly: number | null;  // <= 0 means no edge
+  levels: KellyLevel[];
+  current_vs_full_kelly: number | null;
+}
+
+export interface KellyAnalysis {
+  current_r_percent: number;
+  overall: KellyStats;
+  by_pair: KellyStats[];
+}
+
+export type CapitalFlowType = 'DEPOSIT' | 'WITHDRAWAL';
+
+export interface CapitalFlow {
+  id: string;
+  flow_date: number;
+  flow_type: CapitalFlowType;
+  amount: number;  // always positive, direction comes from flow_type
+  note: string;
+  created_at: number;
+  updated_at: number;
+}
+
+export interface CreateCapitalFlowInput {
+  flow_date: number;
+  flow_type: CapitalFlowType;
+  amount: number;
+  note?: string;
+}
+
+export interface AccountBalance {
+  initial_capital: number;
+  total_deposits: number;
+  total_withdrawals: number;
+  contributed_capital: number;  // initial_capital + deposits - withdrawals
+  realized_pnl: number;  // closed trades, net of fees and funding
+  balance: number;
+}
+
+export interface LedgerEquityPoint {
+  date: string;
+  pnl: number;
+  net_flow: number;
+  balance: number;
+  time_weighted_return: number;  // cumulative %, flows excluded
+}
+
+export interface CapitalPerformance {
+  account: AccountBalance;
+  series: LedgerEquityPoint[];
+  time_weighted_return: number;
+  money_weighted_return: number | null;
+  money_weighted_return_annualized: number | null;
+}
+
+export type GoalMetric = 'PNL' | 'R' | 'MAX_DRAWDOWN' | 'MAX_TRADES' | 'JOURNAL_COMPLETENESS';
+export type GoalPeriod = 'WEEK' | 'MONTH' | 'CUSTOM';
+
+export interface Goal {
+  id: string;
+  name: string;
+  metric: GoalMetric;
+  period: GoalPeriod;
+  target: number;  // MAX_DRAWDOWN and MAX_TRADES are limits, JOURNAL_COMPLETENESS is a %
+  start_date?: number;  // CUSTOM only
+  end_date?: number;
+  active: boolean;
+  created_at: number;
+  updated_at: number;
+}
+
+export interface CreateGoalInput {
+  name?: string;
+  metric: GoalMetric;
+  period: GoalPeriod;
+  target: number;
+  start_date?: number;
+  end_date?: number;
+}
+
+export interface UpdateGoalInput extends Partial<CreateGoalInput> {
+  active?: boolean;
+}
+
+export type GoalStatus = 'ACHIEVED' | 'ON_TRACK' | 'OFF_TRACK' | 'FAILED' | 'BREACHED';
+
+export interface GoalProgressOptions {
+  asOf?: number;  // defaults to now
+  tagIds?: string[];
+}
+
+export interface GoalProgress {
+  goal: Goal;
+  period_start: number;
+  period_end: number;
+  current_value: number;
+  progress_percent: number | null;
+  time_elapsed_percent: number;
+  expected_value: number | null;  // even-pace value for PNL, R and MAX_TRADES
+  pace_percent: number | null;
+  projected_value: number;
+  period_complete: boolean;
+  status: GoalStatus;
+}
+
+export type TagKind = 'TAG' | 'STRATEGY';
+
+export interface Tag {
+  id: string;
+  name: string;
+  kind: TagKind;
+  color?: string;
+  description: string;
+  created_at: number;
+  updated_at: number;
+}
+
+export interface TradeTag {
+  trade_id: string;
+  tag_id: string;
+  created_at: number;
+}
+
+export interface CreateTagInput {
+  name: string;
+  kind?: TagKind;  // defaults to TAG
+  color?: string;
+  description?: string;
+}
+
+export type UpdateTagInput = Partial<CreateTagInput>;
+
+export type ReviewCategory = 'MISTAKE' | 'EMOTION';
+
+export interface ReviewLabel {
+  id: string;
+  name: string;
+  category: ReviewCategory;
+  description: string;
+  active: boolean;  // inactive labels stay on past trades but are no longer offered
+  created_at: number;
+  updated_at: number;
+}
+
+export interface CreateReviewLabelInput {
+  name: string;
+  category: ReviewCategory;
+  description?: string;
+}
+
+export interface UpdateReviewLabelInput {
+  name?: string;
+  description?: string;
+  active?: boolean;
+}
+
+export interface TradeReview {
+  trade_id: string;
+  execution_score: number | null;
+  mistakes: ReviewLabel[];
+  emotions: ReviewLabel[];
+}
+
+export interface ReviewGroupStats {
+  trade_count: number;
+  wins: number;
+  losses: number;
+  win_rate: number;
+  total_pnl: number;  // net of fees and funding
+  avg_pnl: number;
+  total_r: number;
+  avg_r: number | null;
+}
+
+export interface LabelCost {
+  label: ReviewLabel;
+  stats: ReviewGroupStats;
+  cost_pnl: number;  // vs clean trades, positive = given up
+  cost_r: number | null;
+}
+
+export interface ReviewCostReport {
+  reviewed_trades: number;
+  unreviewed_trades: number;
+  clean: ReviewGroupStats;  // reviewed trades without any mistake
+  mistakes: LabelCost[];  // costliest first
+  emotions: LabelCost[];
+  by_execution_score: { score: number; stats: ReviewGroupStats }[];
+}
+
+export type RevisionAction = 'CREATE' | 'UPDATE' | 'DELETE' | 'RESTORE' | 'REVERT';
+export type RevisionSource = 'USER' | 'CSV_IMPORT' | 'API_IMPORT' | 'LIVE_MIRROR' | 'BACKUP_RESTORE';
+
+export interface FieldChange {
+  field: string;  // trade column, or tag_ids / review_label_ids
+  old_value: unknown;
+  new_value: unknown;
+}
+
+export interface TradeRevision {
+  id: number;
+  trade_id: string;
+  action: RevisionAction;
+  source: RevisionSource;
+  changes: FieldChange[];
+  created_at: number;
+}
+
+export type AttachmentKind = 'PRE_TRADE' | 'POST_TRADE' | 'OTHER';
+
+export interface Attachment {
+  id: string;
+  trade_id: string;
+  kind: AttachmentKind;
+  file_name: string;
+  stored_name: string;  // <sha256>.<ext> in the attachments folder
+  content_hash: string;
+  mime_type: string;  // image/png | image/jpeg | application/pdf
+  size_bytes: number;
+  caption: string;
+  sort_order: number;
+  created_at: number;
+}
+
+export interface AddAttachmentInput {
+  trade_id: string;
+  source_path?: string;  // file picked in a dialog
+  data_base64?: string;  // or pasted image data
+  file_name?: string;
+  kind?: AttachmentKind;  // defaults to OTHER
+  caption?: string;
+}
+
+export interface UpdateAttachmentInput {
+  kind?: AttachmentKind;
+  caption?: string;
+}
+
+export interface TradeSearchResult {
+  trade: Trade;
+  rank: number;  // higher is a better match
+  snippet: string;  // matched terms wrapped in **
+}
+
 export interface ImportPreview {
   pair: string;
   position_type: string;
@@ -254,6 +978,11 @@ export const api = {
   getDeletedTrades: () => invoke<Trade[]>('get_deleted_trades'),
   restoreTrade: (id: string) => invoke<void>('restore_trade', { id }),
   duplicateTrade: (id: string) => invoke<Trade>('duplicate_trade', { id }),
+  getTradeHistory: (tradeId: string) => invoke<TradeRevision[]>('get_trade_history', { tradeId }),
+  revertTradeToRevision: (revisionId: number) => invoke<Trade>('revert_trade_to_revision', { revisionId }),
+  searchTrades: (query: string, limit?: number, includeDeleted?: boolean) =>
+    invoke<TradeSearchResult[]>('search_trades', { query, limit, includeDeleted }),
+  rebuildSearchIndex: () => invoke<number>('rebuild_search_index'),
 
   // Debug commands
   getAllTradesIncludingDeleted: () => invoke<{ total: number; deleted: number; active: number }>('get_all_trades_including_deleted'),
@@ -261,8 +990,89 @@ export const api = {
   deleteAllTrades: () => invoke<number>('delete_all_trades'),
 
   // Stats
-  getDashboardStats: (dateRange?: string) => invoke<DashboardStats>('get_dashboard_stats', { date_range: dateRange }),
-  getEquityCurve: (dateRange?: string) => invoke<EquityCurvePoint[]>('get_equity_curve', { date_range: dateRange }),
+  getDashboardStats: (options: StatsFilter = {}) =>
+    invoke<DashboardStats>('get_dashboard_stats', { ...options }),
+  getEquityCurve: (options: EquityCurveOptions = {}) =>
+    invoke<EquityCurvePoint[]>('get_equity_curve', { ...options }),
+  getRMultipleStats: (options: RMultipleOptions = {}) =>
+    invoke<RMultipleStats>('get_r_multiple_stats', { ...options }),
+  getDrawdownAnalysis: (options: StatsFilter = {}) =>
+    invoke<DrawdownAnalysis>('get_drawdown_analysis', { ...options }),
+  getPerformanceBreakdown: (options: BreakdownOptions) =>
+    invoke<BreakdownGroup[]>('get_performance_breakdown', { ...options }),
+  getStreakAnalysis: (options: StreakOptions = {}) =>
+    invoke<StreakAnalysis>('get_streak_analysis', { ...options }),
+  getBootstrapStats: (options: BootstrapOptions = {}) =>
+    invoke<BootstrapStats>('get_bootstrap_stats', { ...options }),
+  getAdherenceReport: (options: AdherenceOptions = {}) =>
+    invoke<AdherenceReport>('get_adherence_report', { ...options }),
+  runMonteCarloSimulation: (options: MonteCarloOptions = {}) =>
+    invoke<MonteCarloResult>('run_monte_carlo_simulation', { ...options }),
+  getCalendarData: (options: CalendarOptions = {}) =>
+    invoke<CalendarData>('get_calendar_data', { ...options }),
+  getKellyAnalysis: (options: StatsFilter = {}) =>
+    invoke<KellyAnalysis>('get_kelly_analysis', { ...options }),
+  getHoldingTimeAnalysis: (options: HoldingTimeOptions = {}) =>
+    invoke<HoldingTimeAnalysis>('get_holding_time_analysis', { ...options }),
+  comparePeriods: (options: PeriodComparisonOptions) =>
+    invoke<PeriodComparison>('compare_periods', { ...options }),
+  getRollingMetrics: (options: RollingOptions = {}) =>
+    invoke<RollingMetricPoint[]>('get_rolling_metrics', { ...options }),
+  getRiskAnalysis: (options: StatsFilter = {}) =>
+    invoke<RiskAnalysis>('get_risk_analysis', { ...options }),
+  getRiskOvershoot: (options: RiskOvershootOptions = {}) =>
+    invoke<RiskOvershootReport>('get_risk_overshoot', { ...options }),
+  getExposureAnalysis: (options: StatsFilter = {}) =>
+    invoke<ExposureAnalysis>('get_exposure_analysis', { ...options }),
+  rebuildDailyStats: () => invoke<number>('rebuild_daily_stats'),
+
+  // Capital ledger
+  getCapitalFlows: () => invoke<CapitalFlow[]>('get_capital_flows'),
+  createCapitalFlow: (flow: CreateCapitalFlowInput) => invoke<CapitalFlow>('create_capital_flow', { flow }),
+  updateCapitalFlow: (id: string, flow: Partial<CreateCapitalFlowInput>) =>
+    invoke<CapitalFlow>('update_capital_flow', { id, flow }),
+  deleteCapitalFlow: (id: string) => invoke<void>('delete_capital_flow', { id }),
+  getAccountBalance: () => invoke<AccountBalance>('get_account_balance'),
+  getCapitalPerformance: () => invoke<CapitalPerformance>('get_capital_performance'),
+
+  // Goals
+  getGoals: () => invoke<Goal[]>('get_goals'),
+  createGoal: (goal: CreateGoalInput) => invoke<Goal>('create_goal', { goal }),
+  updateGoal: (id: string, goal: UpdateGoalInput) => invoke<Goal>('update_goal', { id, goal }),
+  deleteGoal: (id: string) => invoke<void>('delete_goal', { id }),
+  getGoalProgress: (options: GoalProgressOptions = {}) =>
+    invoke<GoalProgress[]>('get_goal_progress', { ...options }),
+
+  // Tags and strategies
+  getTags: (kind?: TagKind) => invoke<Tag[]>('get_tags', { kind }),
+  createTag: (tag: CreateTagInput) => invoke<Tag>('create_tag', { tag }),
+  updateTag: (id: string, tag: UpdateTagInput) => invoke<Tag>('update_tag', { id, tag }),
+  deleteTag: (id: string) => invoke<void>('delete_tag', { id }),
+  getTradeTags: (tradeId: string) => invoke<Tag[]>('get_trade_tags', { tradeId }),
+  setTradeTags: (tradeId: string, tagIds: string[]) => invoke<Tag[]>('set_trade_tags', { tradeId, tagIds }),
+
+  // Attachments
+  getAttachments: (tradeId: string) => invoke<Attachment[]>('get_attachments', { tradeId }),
+  addAttachment: (attachment: AddAttachmentInput) => invoke<Attachment>('add_attachment', { attachment }),
+  updateAttachment: (id: string, attachment: UpdateAttachmentInput) =>
+    invoke<Attachment>('update_attachment', { id, attachment }),
+  deleteAttachment: (id: string) => invoke<void>('delete_attachment', { id }),
+  reorderAttachments: (tradeId: string, attachmentIds: string[]) =>
+    invoke<Attachment[]>('reorder_attachments', { tradeId, attachmentIds }),
+  getAttachmentData: (id: string) => invoke<string>('get_attachment_data', { id }),
+
+  // Trade review (mistakes, emotions, execution score)
+  getReviewLabels: (category?: ReviewCategory, includeInactive?: boolean) =>
+    invoke<ReviewLabel[]>('get_review_labels', { category, includeInactive }),
+  createReviewLabel: (label: CreateReviewLabelInput) => invoke<ReviewLabel>('create_review_label', { label }),
+  updateReviewLabel: (id: string, label: UpdateReviewLabelInput) =>
+    invoke<ReviewLabel>('update_review_label', { id, label }),
+  deleteReviewLabel: (id: string) => invoke<void>('delete_review_label', { id }),
+  getTradeReview: (tradeId: string) => invoke<TradeReview>('get_trade_review', { tradeId }),
+  setTradeReview: (tradeId: string, labelIds: string[], executionScore?: number | null) =>
+    invoke<TradeReview>('set_trade_review', { tradeId, labelIds, executionScore }),
+  getReviewCosts: (options: StatsFilter = {}) =>
+    invoke<ReviewCostReport>('get_review_costs', { ...options }),
 
   // Import/Export
   previewBitgetImport: (csvContent: string, portfolio: number, rPercent: number) =>
@@ -144,10 +144,10 @@ export default function Import() {
     setCsvContent(content);
 
     try {
-      const settings = await api.getSettings();
-      setPortfolio(settings.initial_capital);
+      const [settings, account] = await Promise.all([api.getSettings(), api.getAccountBalance()]);
+      setPortfolio(account.balance);
       setRPercent(settings.current_r_percent * 100);
-      await previewImport(content, settings.initial_capital, settings.current_r_percent);
+      await previewImport(content, account.balance, settings.current_r_percent);
     } catch (error) {
       console.error('Failed to load settings:', error);
       await previewImport(content, portfolio, rPercent / 100);
@@ -164,10 +164,10 @@ export default function Import() {
     setImportResult(null);
     setLoading(true);
     try {
-      const settings = await api.getSettings();
-      setPortfolio(settings.initial_capital);
+      const [settings, account] = await Promise.all([api.getSettings(), api.getAccountBalance()]);
+      setPortfolio(account.balance);
       setRPercent(settings.current_r_percent * 100);
-      const preview = await api.previewBingxImport(path, settings.initial_capital, settings.current_r_percent);
+      const preview = await api.previewBingxImport(path, account.balance, settings.current_r_percent);
       setPreviews(preview);
     } catch (error) {
       setErrorDialog({ open: true, message: 'Failed to preview import: ' + error });
@@ -117,9 +117,9 @@ export default function TradeNew() {
   useEffect(() => {
     const loadSettings = async () => {
       try {
-        const data = await api.getSettings();
+        const [data, account] = await Promise.all([api.getSettings(), api.getAccountBalance()]);
         if (!calculatorData) {
-          setPortfolio(data.initial_capital);
+          setPortfolio(account.balance);
           setRPercent(data.current_r_percent * 100); // Convert from decimal to percentage
           setMinRR(data.default_min_rr);
           setLeverage(data.default_leverage);